@@ -5,12 +5,15 @@ declare class AbortController {
 	abort(reason?: any): void;
 }
 
-declare class AbortSignal {
+declare class AbortSignal extends EventTarget {
 	static abort(reason?: any): AbortSignal;
 	static timeout(time: number): AbortSignal;
+	static any(signals: Iterable<AbortSignal>): AbortSignal;
 
 	get aborted(): boolean;
 	get reason(): any;
+	get onabort(): ((event: Event) => void) | null;
+	set onabort(handler: ((event: Event) => void) | null);
 
 	throwIfAborted(): void;
 }
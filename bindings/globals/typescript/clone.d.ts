@@ -0,0 +1,5 @@
+interface StructuredSerializeOptions {
+	transfer?: any[],
+}
+
+declare function structuredClone<T = any>(value: T, options?: StructuredSerializeOptions): T;
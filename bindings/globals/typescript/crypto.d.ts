@@ -0,0 +1,165 @@
+declare type IntegerTypedArray = Int8Array | Int16Array | Int32Array
+	| Uint8Array | Uint8ClampedArray | Uint16Array | Uint32Array
+	| BigInt64Array | BigUint64Array;
+
+declare type BufferSource = ArrayBuffer | TypedArray | DataView;
+
+declare type DigestAlgorithm = "SHA-1" | "SHA-256" | "SHA-384" | "SHA-512" | "SHA3-256" | "SHA3-384" | "SHA3-512";
+declare type AlgorithmIdentifier<T extends string = string> = T | { name: T };
+
+declare type KeyType = "secret" | "private" | "public";
+declare type KeyUsage = "encrypt" | "decrypt" | "sign" | "verify" | "deriveKey" | "deriveBits" | "wrapKey" | "unwrapKey";
+declare type KeyFormat = "raw" | "pkcs8" | "spki" | "jwk";
+declare type NamedCurve = "P-256" | "P-384";
+
+declare type SignAlgorithm = AlgorithmIdentifier<"HMAC" | "Ed25519" | "RSASSA-PKCS1-v1_5">
+	| EcdsaParams | RsaPssParams;
+declare type CipherAlgorithm = AesGcmParams | AesCbcParams | RsaOaepParams;
+declare type DeriveAlgorithm = Pbkdf2Params | HkdfParams | EcdhKeyDeriveParams;
+declare type KeyGenAlgorithm = AlgorithmIdentifier<"Ed25519"> | HmacKeyGenParams | AesKeyGenParams | EcKeyGenParams | RsaHashedKeyGenParams;
+declare type ImportAlgorithm = AlgorithmIdentifier<"AES-GCM" | "AES-CBC" | "Ed25519" | "PBKDF2" | "HKDF">
+	| HmacImportParams | EcKeyImportParams | RsaHashedImportParams;
+
+declare interface HmacKeyGenParams {
+	name: "HMAC";
+	hash: AlgorithmIdentifier<DigestAlgorithm>;
+	length?: number;
+}
+
+declare type HmacImportParams = HmacKeyGenParams;
+
+declare interface AesKeyGenParams {
+	name: "AES-GCM" | "AES-CBC";
+	length: 128 | 192 | 256;
+}
+
+declare interface EcKeyGenParams {
+	name: "ECDSA" | "ECDH";
+	namedCurve: NamedCurve;
+}
+
+declare type EcKeyImportParams = EcKeyGenParams;
+
+declare interface RsaHashedKeyGenParams {
+	name: "RSASSA-PKCS1-v1_5" | "RSA-PSS" | "RSA-OAEP";
+	modulusLength: number;
+	publicExponent: Uint8Array;
+	hash: AlgorithmIdentifier<DigestAlgorithm>;
+}
+
+declare interface RsaHashedImportParams {
+	name: "RSASSA-PKCS1-v1_5" | "RSA-PSS" | "RSA-OAEP";
+	hash: AlgorithmIdentifier<DigestAlgorithm>;
+}
+
+declare interface EcdsaParams {
+	name: "ECDSA";
+	hash: AlgorithmIdentifier<DigestAlgorithm>;
+}
+
+declare interface RsaPssParams {
+	name: "RSA-PSS";
+	saltLength: number;
+}
+
+declare interface AesGcmParams {
+	name: "AES-GCM";
+	iv: BufferSource;
+	additionalData?: BufferSource;
+	tagLength?: number;
+}
+
+declare interface AesCbcParams {
+	name: "AES-CBC";
+	iv: BufferSource;
+}
+
+declare interface RsaOaepParams {
+	name: "RSA-OAEP";
+	label?: BufferSource;
+}
+
+declare interface Pbkdf2Params {
+	name: "PBKDF2";
+	salt: BufferSource;
+	iterations: number;
+	hash: AlgorithmIdentifier<DigestAlgorithm>;
+}
+
+declare interface HkdfParams {
+	name: "HKDF";
+	hash: AlgorithmIdentifier<DigestAlgorithm>;
+	salt: BufferSource;
+	info: BufferSource;
+}
+
+declare interface EcdhKeyDeriveParams {
+	name: "ECDH";
+	public: CryptoKey;
+}
+
+declare interface KeyAlgorithm {
+	name: string;
+	hash?: { name: DigestAlgorithm };
+	length?: number;
+	namedCurve?: NamedCurve;
+	modulusLength?: number;
+	publicExponent?: Uint8Array;
+}
+
+declare interface JsonWebKey {
+	kty?: string;
+	use?: string;
+	key_ops?: KeyUsage[];
+	alg?: string;
+	ext?: boolean;
+	crv?: string;
+	x?: string;
+	y?: string;
+	d?: string;
+	n?: string;
+	e?: string;
+	p?: string;
+	q?: string;
+	dp?: string;
+	dq?: string;
+	qi?: string;
+	k?: string;
+}
+
+declare class CryptoKey {
+	get type(): KeyType;
+	get extractable(): boolean;
+	get algorithm(): KeyAlgorithm;
+	get usages(): KeyUsage[];
+}
+
+declare interface CryptoKeyPair {
+	publicKey: CryptoKey;
+	privateKey: CryptoKey;
+}
+
+declare class SubtleCrypto {
+	digest(algorithm: AlgorithmIdentifier<DigestAlgorithm>, data: BufferSource): Promise<ArrayBuffer>;
+
+	importKey(format: "jwk", keyData: JsonWebKey, algorithm: ImportAlgorithm, extractable: boolean, keyUsages: KeyUsage[]): Promise<CryptoKey>;
+	importKey(format: Exclude<KeyFormat, "jwk">, keyData: BufferSource, algorithm: ImportAlgorithm, extractable: boolean, keyUsages: KeyUsage[]): Promise<CryptoKey>;
+	exportKey(format: "jwk", key: CryptoKey): Promise<JsonWebKey>;
+	exportKey(format: Exclude<KeyFormat, "jwk">, key: CryptoKey): Promise<ArrayBuffer>;
+	generateKey(algorithm: KeyGenAlgorithm, extractable: boolean, keyUsages: KeyUsage[]): Promise<CryptoKey | CryptoKeyPair>;
+
+	sign(algorithm: SignAlgorithm, key: CryptoKey, data: BufferSource): Promise<ArrayBuffer>;
+	verify(algorithm: SignAlgorithm, key: CryptoKey, signature: BufferSource, data: BufferSource): Promise<boolean>;
+	encrypt(algorithm: CipherAlgorithm, key: CryptoKey, data: BufferSource): Promise<ArrayBuffer>;
+	decrypt(algorithm: CipherAlgorithm, key: CryptoKey, data: BufferSource): Promise<ArrayBuffer>;
+	deriveBits(algorithm: DeriveAlgorithm, baseKey: CryptoKey, length?: number | null): Promise<ArrayBuffer>;
+}
+
+declare class Crypto {
+	get subtle(): SubtleCrypto;
+
+	getRandomValues<T extends IntegerTypedArray>(array: T): T;
+	randomUUID(): string;
+}
+
+declare const crypto: Crypto;
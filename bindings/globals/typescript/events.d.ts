@@ -0,0 +1,74 @@
+declare interface EventInit {
+	bubbles?: boolean;
+	cancelable?: boolean;
+	composed?: boolean;
+}
+
+declare class Event {
+	static readonly NONE: 0;
+	static readonly CAPTURING_PHASE: 1;
+	static readonly AT_TARGET: 2;
+	static readonly BUBBLING_PHASE: 3;
+
+	constructor(type: string, init?: EventInit);
+
+	get type(): string;
+	get target(): EventTarget | null;
+	get currentTarget(): EventTarget | null;
+	get eventPhase(): number;
+	get bubbles(): boolean;
+	get cancelable(): boolean;
+	get composed(): boolean;
+	get defaultPrevented(): boolean;
+	get isTrusted(): boolean;
+	get timeStamp(): number;
+
+	composedPath(): EventTarget[];
+	preventDefault(): void;
+	stopPropagation(): void;
+	stopImmediatePropagation(): void;
+}
+
+declare interface CustomEventInit<T> extends EventInit {
+	detail?: T;
+}
+
+declare class CustomEvent<T = any> extends Event {
+	constructor(type: string, init?: CustomEventInit<T>);
+
+	get detail(): T;
+}
+
+declare interface MessageEventInit<T> extends EventInit {
+	data?: T;
+	origin?: string;
+	lastEventId?: string;
+}
+
+declare class MessageEvent<T = any> extends Event {
+	constructor(type: string, init?: MessageEventInit<T>);
+
+	get data(): T;
+	get origin(): string;
+	get lastEventId(): string;
+}
+
+declare type EventListener = ((event: Event) => void) | { handleEvent(event: Event): void };
+
+declare interface EventListenerOptions {
+	capture?: boolean;
+}
+
+declare interface AddEventListenerOptions extends EventListenerOptions {
+	once?: boolean;
+	passive?: boolean;
+	signal?: AbortSignal;
+}
+
+declare class EventTarget {
+	constructor();
+
+	addEventListener(type: string, callback: EventListener | null, options?: AddEventListenerOptions | boolean): void;
+	removeEventListener(type: string, callback: EventListener | null, options?: EventListenerOptions | boolean): void;
+	dispatchEvent(event: Event): boolean;
+}
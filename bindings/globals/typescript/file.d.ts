@@ -0,0 +1,28 @@
+declare type BlobPart = string | ArrayBuffer | ArrayBufferView | Blob;
+
+declare interface BlobPropertyBag {
+	type?: string;
+}
+
+declare interface FilePropertyBag extends BlobPropertyBag {
+	lastModified?: number;
+}
+
+declare class Blob {
+	constructor(parts?: BlobPart[], options?: BlobPropertyBag);
+
+	get size(): number;
+	get type(): string;
+
+	slice(start?: number, end?: number, contentType?: string): Blob;
+	text(): Promise<string>;
+	arrayBuffer(): Promise<ArrayBuffer>;
+	stream(): AsyncIterableIterator<Uint8Array>;
+}
+
+declare class File extends Blob {
+	constructor(parts: BlobPart[], name: string, options?: FilePropertyBag);
+
+	get name(): string;
+	get lastModified(): number;
+}
@@ -0,0 +1,17 @@
+declare type FormDataEntryValue = string | File;
+
+declare class FormData {
+	constructor();
+
+	append(name: string, value: string | Blob, filename?: string): void;
+	delete(name: string): void;
+	get(name: string): FormDataEntryValue | null;
+	getAll(name: string): FormDataEntryValue[];
+	has(name: string): boolean;
+	set(name: string, value: string | Blob, filename?: string): void;
+
+	entries(): IterableIterator<[string, FormDataEntryValue]>;
+	keys(): IterableIterator<string>;
+	values(): IterableIterator<FormDataEntryValue>;
+	[Symbol.iterator](): IterableIterator<[string, FormDataEntryValue]>;
+}
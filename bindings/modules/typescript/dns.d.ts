@@ -0,0 +1,76 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+declare module "dns" {
+	export type Family = 0 | 4 | 6;
+
+	export interface ResolveOptions {
+		family?: Family,
+	}
+
+	export interface ResolverOptions {
+		servers?: string[],
+		hosts?: { [host: string]: string | string[] },
+		timeout?: number,
+	}
+
+	export interface SrvRecord {
+		priority: number,
+		weight: number,
+		port: number,
+		name: string,
+	}
+
+	export interface MxRecord {
+		priority: number,
+		exchange: string,
+	}
+
+	export interface Service {
+		hostname: string,
+		service: string,
+	}
+
+	export function resolve(host: string, options?: ResolveOptions): Promise<string[]>;
+	export function resolve4(name: string): Promise<string[]>;
+	export function resolve6(name: string): Promise<string[]>;
+	export function resolveTxt(name: string): Promise<string[][]>;
+	export function resolveSrv(name: string): Promise<SrvRecord[]>;
+	export function resolveMx(name: string): Promise<MxRecord[]>;
+	export function lookupService(address: string, port: number): Promise<Service>;
+
+	export class Resolver {
+		constructor(options?: ResolverOptions);
+
+		resolve(host: string, options?: ResolveOptions): Promise<string[]>;
+		resolve4(name: string): Promise<string[]>;
+		resolve6(name: string): Promise<string[]>;
+		resolveTxt(name: string): Promise<string[][]>;
+		resolveSrv(name: string): Promise<SrvRecord[]>;
+		resolveMx(name: string): Promise<MxRecord[]>;
+		lookupService(address: string, port: number): Promise<Service>;
+	}
+
+	namespace Dns {
+		export {
+			Family,
+			ResolveOptions,
+			ResolverOptions,
+
+			resolve,
+			resolve4,
+			resolve6,
+			resolveTxt,
+			resolveSrv,
+			resolveMx,
+			lookupService,
+
+			Resolver,
+		};
+	}
+
+	export default Dns;
+}
@@ -1,6 +1,11 @@
 declare module "fs" {
+	interface OpenFileOptions {
+		type?: string,
+	}
+
 	export function readBinary(path: string): Promise<Uint8Array>;
 	export function readString(path: string): Promise<string>;
+	export function openFile(path: string, options?: OpenFileOptions): Promise<File>;
 	export function readDir(path: string): Promise<string[]>;
 	export function write(path: string, contents: string): Promise<boolean>;
 	export function createDir(path: string): Promise<boolean>;
@@ -16,6 +21,7 @@ declare module "fs" {
 	export const sync: {
 		readBinary(path: string): Uint8Array,
 		readString(path: string): string,
+		openFile(path: string, options?: OpenFileOptions): File,
 		readDir(path: string): string[],
 		write(path: string, contents: string): boolean,
 		createDir(path: string): boolean,
@@ -33,6 +39,7 @@ declare module "fs" {
 		export {
 			readBinary,
 			readString,
+			openFile,
 			readDir,
 			write,
 			createDir,
@@ -1,4 +1,6 @@
 declare module "http" {
+	import { Resolver } from "dns";
+
 	export type Header = string | string[];
 	export type HeaderEntries = [string, string][];
 	export interface HeadersObject {
@@ -7,17 +9,38 @@ declare module "http" {
 	export type HeadersInit = Headers | HeaderEntries | HeadersObject;
 
 	export type Body = string | String | ArrayBuffer | TypedArray | DataView;
+	export interface BodyStreamReader {
+		read(): Promise<{ done: boolean, value?: Body }>,
+		cancel?: () => unknown,
+	}
+	export interface BodyStream {
+		getReader(): BodyStreamReader,
+	}
+	export type RequestBody = Body | BodyStream | AsyncIterable<Body>;
+
+	export type RedirectAction = boolean | { headers?: HeadersInit } | void;
+	export type RedirectHook = (response: Response, nextUrl: string) => RedirectAction | Promise<RedirectAction>;
 
 	export interface RequestOptions {
 		auth?: string,
 		setHost?: boolean,
+		decompress?: boolean,
+		connectTimeout?: number,
+		readTimeout?: number,
+		timeout?: number,
+		onUploadProgress?: (loaded: number, total?: number) => void,
+		maxRedirects?: number,
+		onRedirect?: RedirectHook,
 
 		client?: ClientRequestOptions,
+		credentials?: Credentials,
+		cache?: Cache,
 		redirect?: Redirect,
+		integrity?: string,
 		signal?: AbortSignal,
 
 		headers?: HeadersInit,
-		body?: Body,
+		body?: RequestBody,
 	}
 
 	export type RequestBuilderOptions = RequestOptions & {
@@ -29,17 +52,86 @@ declare module "http" {
 		keepAliveTimeout?: number,
 		maxIdleSockets?: number,
 		retryCancelled?: boolean,
+		cookies?: boolean,
+		http2?: boolean,
+		http2PriorKnowledge?: boolean,
+		proxy?: boolean | string,
+		noProxy?: string,
+		systemRoots?: boolean,
+		ca?: string[],
+		certificate?: string,
+		key?: string,
+		pinnedCertificates?: string[],
+		connectTimeout?: number,
+		readTimeout?: number,
+		timeout?: number,
+		maxRedirects?: number,
+		onRedirect?: RedirectHook,
+		retry?: RetryPolicy,
+		resolver?: Resolver,
+	}
+
+	export interface RetryPolicy {
+		attempts?: number,
+		backoff?: number,
+		maxBackoff?: number,
+		jitter?: boolean,
+		statuses?: number[],
+		retryAfter?: boolean,
+		retryNonIdempotent?: boolean,
 	}
 
 	export type ClientRequestOptions = undefined | boolean | Client;
 
+	export type Credentials = "omit" | "same-origin" | "include";
+	export type Cache = "default" | "no-store" | "reload" | "no-cache" | "force-cache" | "only-if-cached";
 	export type Redirect = "follow" | "error" | "manual";
 
+	export interface ResponseTiming {
+		dns: number,
+		connect: number,
+		tls: number,
+		ttfb: number,
+		reused: boolean,
+	}
+
+	export interface HostConnections {
+		active: number,
+		idle: number,
+	}
+
+	export interface ClientStats {
+		requests: number,
+		bytesSent: number,
+		bytesReceived: number,
+		hosts: { [host: string]: HostConnections },
+	}
+
+	export interface MockMatcher {
+		method?: string,
+		url: string,
+		headers?: HeadersInit,
+	}
+
+	export interface MockResponseOptions {
+		status?: number,
+		headers?: HeadersInit,
+		body?: Body,
+	}
+
+	export interface RecordedRequest {
+		method: string,
+		url: string,
+		headers: Headers,
+	}
+
 	export function get(url: string, options?: RequestOptions): Promise<Response>;
 	export function post(url: string, options?: RequestOptions): Promise<Response>;
 	export function put(url: string, options?: RequestOptions): Promise<Response>;
 	export function request(resource: string, method: string, options?: RequestOptions): Promise<Response>;
 	export function request(resource: Request): Promise<Response>;
+	export function setTransport(transport: MockTransport | null): void;
+	export function setResolver(resolver: Resolver | null): void;
 
 	export class Headers {
 		constructor();
@@ -52,17 +144,42 @@ declare module "http" {
 		get(name: string): Header | null;
 		has(name: string): boolean;
 		set(name: string, value: string);
+		getSetCookie(): string[];
+
+		entries(): Iterator<[string, string]>;
+		keys(): Iterator<string>;
+		values(): Iterator<string>;
+		forEach(callback: (value: string, name: string, headers: Headers) => void, thisArg?: object): void;
+		[Symbol.iterator](): Iterator<[string, string]>;
 	}
 
 	export class Request {
 		constructor(url: string, options?: RequestBuilderOptions);
 		constructor(url: Request, options?: RequestBuilderOptions);
+
+		get method(): string;
+		get url(): string;
+		get headers(): Headers;
+		get credentials(): Credentials;
+		get cache(): Cache;
+		get redirect(): Redirect;
+		get integrity(): string;
+		get signal(): AbortSignal;
+
+		get bodyUsed(): boolean;
+
+		clone(): Request;
+		arrayBuffer(): Promise<ArrayBuffer>;
+		blob(): Promise<Blob>;
+		text(): Promise<string>;
+		json(): Promise<any>;
 	}
 
 	export class Response {
 		get ok(): boolean;
 		get status(): number;
 		get statusText(): string;
+		get version(): string;
 
 		get bodyUsed(): boolean;
 		get headers(): Headers;
@@ -71,12 +188,33 @@ declare module "http" {
 		get redirected(): boolean;
 		get locations(): string[];
 
+		get timing(): ResponseTiming | null;
+
 		arrayBuffer(): Promise<ArrayBuffer>;
 		text(): Promise<string>;
 	}
 
 	export class Client {
 		constructor(options?: ClientOptions);
+
+		getCookies(url: string): string | undefined;
+		setCookie(url: string, cookie: string): void;
+		clearCookies(): void;
+		saveCookies(path: string): void;
+		loadCookies(path: string): void;
+		setTransport(transport: MockTransport | null): void;
+		setResolver(resolver: Resolver | null): void;
+
+		get stats(): ClientStats;
+	}
+
+	export class MockTransport {
+		constructor();
+
+		get unmatched(): RecordedRequest[];
+
+		on(matcher: MockMatcher, response?: MockResponseOptions): void;
+		clear(): void;
 	}
 
 	namespace Http {
@@ -89,6 +227,8 @@ declare module "http" {
 			post,
 			put,
 			request,
+			setTransport,
+			setResolver,
 
 			Header,
 			HeaderEntries,
@@ -99,6 +239,7 @@ declare module "http" {
 			Response,
 
 			Client,
+			MockTransport,
 		};
 	}
 
@@ -0,0 +1,25 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+declare module "timers" {
+	export type TimerOptions = {
+		signal?: AbortSignal,
+	};
+
+	export function sleep(duration?: number, options?: TimerOptions): Promise<void>;
+	export function setInterval<T = undefined>(duration?: number, value?: T, options?: TimerOptions): AsyncIterableIterator<T>;
+
+	namespace Timers {
+		export {
+			TimerOptions,
+
+			sleep,
+			setInterval
+		};
+	}
+
+	export default Timers;
+}
@@ -59,6 +59,8 @@ declare module "url" {
 	}
 
 	export class URLSearchParams {
+		constructor(init?: string | [string, string][] | { [key: string]: string } | URLSearchParams);
+
 		append(key: string, value: string);
 
 		get(key: string): string | null;
@@ -31,7 +31,13 @@ pub(crate) async fn handle_command(command: Option<Command>) {
 			eval::eval_source(&source).await;
 		}
 
-		Some(Command::Run { path, log_level, debug, script }) => {
+		Some(Command::Run {
+			path,
+			log_level,
+			debug,
+			script,
+			file_urls,
+		}) => {
 			let log_level = if debug {
 				LogLevel::Debug
 			} else {
@@ -45,7 +51,9 @@ pub(crate) async fn handle_command(command: Option<Command>) {
 				}
 			};
 
-			CONFIG.set(Config::default().log_level(log_level).script(script)).unwrap();
+			CONFIG
+				.set(Config::default().log_level(log_level).script(script).file_urls(file_urls))
+				.unwrap();
 			run::run(&path).await;
 		}
 
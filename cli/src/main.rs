@@ -50,6 +50,9 @@ pub(crate) enum Command {
 
 		#[arg(help = "Disables ES Modules Features", short, long)]
 		script: bool,
+
+		#[arg(help = "Allows fetching file: URLs", long)]
+		file_urls: bool,
 	},
 }
 
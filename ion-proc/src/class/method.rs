@@ -64,7 +64,7 @@ where
 		method.block = Box::new(body);
 
 		let method = Method {
-			receiver: if parameters.this.is_some() {
+			receiver: if parameters.has_this() {
 				MethodReceiver::Dynamic
 			} else {
 				MethodReceiver::Static
@@ -136,7 +136,7 @@ pub(crate) fn impl_js_class(mut module: ItemMod) -> Result<ItemMod> {
 											let (getter, parameters) = impl_accessor(&crates, &method, &imp.self_ty, false, false)?;
 											let getter = Method { names, ..getter };
 
-											if parameters.this.is_some() {
+											if parameters.has_this() {
 												insert_accessor(&mut accessors, name.as_string(), Some(getter), None);
 											} else {
 												insert_accessor(&mut static_accessors, name.as_string(), Some(getter), None);
@@ -146,7 +146,7 @@ pub(crate) fn impl_js_class(mut module: ItemMod) -> Result<ItemMod> {
 											let (setter, parameters) = impl_accessor(&crates, &method, &imp.self_ty, false, true)?;
 											let setter = Method { names, ..setter };
 
-											if parameters.this.is_some() {
+											if parameters.has_this() {
 												insert_accessor(&mut accessors, name.as_string(), None, Some(setter));
 											} else {
 												insert_accessor(&mut static_accessors, name.as_string(), None, Some(setter));
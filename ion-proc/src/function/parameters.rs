@@ -300,6 +300,11 @@ impl Parameters {
 		self.parameters.iter().map(|parameter| parameter.to_statement(ion)).collect()
 	}
 
+	/// Checks if the function accesses `this`, either through a receiver or a `#[ion(this)]` parameter.
+	pub(crate) fn has_this(&self) -> bool {
+		self.this.is_some() || self.parameters.iter().any(|parameter| matches!(parameter, Parameter::This(..)))
+	}
+
 	pub(crate) fn get_this_ident(&self) -> Option<Ident> {
 		self.this.as_ref().map(|x| x.1.clone())
 	}
@@ -28,6 +28,18 @@ pub struct ClassInfo {
 	prototype: *mut JSObject,
 }
 
+impl ClassInfo {
+	/// Returns the constructor of the class.
+	pub fn constructor<'cx>(&self, cx: &'cx Context) -> Function<'cx> {
+		Function::from(cx.root_function(self.constructor))
+	}
+
+	/// Returns the prototype of the class.
+	pub fn prototype<'cx>(&self, cx: &'cx Context) -> Object<'cx> {
+		Object::from(cx.root_object(self.prototype))
+	}
+}
+
 pub trait ClassDefinition {
 	const NAME: &'static str;
 	const PARENT_PROTOTYPE_CHAIN_LENGTH: u32 = 0;
@@ -0,0 +1,298 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::ffi::{c_char, c_void, CStr};
+use std::ptr;
+
+use mozjs::glue::{DeleteJSAutoStructuredCloneBuffer, NewJSAutoStructuredCloneBuffer};
+use mozjs::jsapi::{
+	CloneDataPolicy, HandleObject, JS_ReadBytes, JS_ReadStructuredClone, JS_ReadUint32Pair, JS_STRUCTURED_CLONE_VERSION, JS_WriteBytes,
+	JS_WriteStructuredClone, JS_WriteUint32Pair, JSAutoStructuredCloneBuffer, JSContext, JSObject, JSStructuredCloneCallbacks,
+	JSStructuredCloneReader, JSStructuredCloneWriter,
+};
+pub use mozjs::jsapi::StructuredCloneScope;
+use mozjs::rust::Handle;
+
+use crate::{ClassDefinition, Context, Error, ErrorKind, Exception, Object, Result, ResultExc, ThrowException, Value};
+use crate::conversions::ToValue;
+
+/// Minimum tag for user-defined objects, as defined by SpiderMonkey.
+const SCTAG_USER_MIN: u32 = 0xFFFF_8000;
+
+/// Represents native classes which can be cloned with the structured clone algorithm.
+/// Classes must be registered with [register_structured_clone] before they can be cloned.
+pub trait StructuredClone: ClassDefinition + Sized + 'static {
+	/// Writes the state of the native value, which is read by [StructuredClone::read].
+	fn write(&self, cx: &Context, writer: &mut StructuredCloneWriter) -> Result<()>;
+
+	/// Reads the state written by [StructuredClone::write] and creates a new native value.
+	fn read(cx: &Context, reader: &mut StructuredCloneReader) -> Result<Self>;
+
+	/// Initialises the object created from the value returned by [StructuredClone::read].
+	/// Used for state which refers to the object itself.
+	fn initialise(_: &Context, _: &Object) -> Result<()> {
+		Ok(())
+	}
+}
+
+#[derive(Clone, Copy)]
+struct CloneHook {
+	instance_of: fn(&Context, &Object) -> bool,
+	write: fn(&Context, &Object, &mut StructuredCloneWriter) -> Result<()>,
+	read: fn(&Context, &mut StructuredCloneReader) -> Result<*mut JSObject>,
+}
+
+thread_local!(static CLONE_HOOKS: RefCell<Vec<(TypeId, CloneHook)>> = RefCell::new(Vec::new()));
+
+fn instance_of<T: StructuredClone>(cx: &Context, object: &Object) -> bool {
+	T::instance_of(cx, object, None)
+}
+
+fn write<T: StructuredClone>(cx: &Context, object: &Object, writer: &mut StructuredCloneWriter) -> Result<()> {
+	T::get_private(object).write(cx, writer)
+}
+
+fn read<T: StructuredClone>(cx: &Context, reader: &mut StructuredCloneReader) -> Result<*mut JSObject> {
+	let native = T::read(cx, reader)?;
+	let object = Object::from(cx.root_object(T::new_object(cx, native)));
+	T::initialise(cx, &object)?;
+	Ok(object.handle().get())
+}
+
+/// Registers a native class, so that its instances can be cloned with the structured clone algorithm.
+pub fn register_structured_clone<T: StructuredClone>() {
+	CLONE_HOOKS.with(|hooks| {
+		let mut hooks = hooks.borrow_mut();
+		if !hooks.iter().any(|(id, _)| *id == TypeId::of::<T>()) {
+			let hook = CloneHook {
+				instance_of: instance_of::<T>,
+				write: write::<T>,
+				read: read::<T>,
+			};
+			hooks.push((TypeId::of::<T>(), hook));
+		}
+	});
+}
+
+/// Writes the state of native values during structured cloning.
+pub struct StructuredCloneWriter {
+	writer: *mut JSStructuredCloneWriter,
+}
+
+impl StructuredCloneWriter {
+	/// Writes a pair of integers.
+	pub fn write_pair(&mut self, first: u32, second: u32) -> Result<()> {
+		if unsafe { JS_WriteUint32Pair(self.writer, first, second) } {
+			Ok(())
+		} else {
+			Err(Error::new("Failed to write structured clone data", None))
+		}
+	}
+
+	/// Writes a 64-bit integer as a pair of integers.
+	pub fn write_u64(&mut self, value: u64) -> Result<()> {
+		self.write_pair((value >> 32) as u32, value as u32)
+	}
+
+	/// Writes a sequence of bytes, along with its length.
+	pub fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+		let length = u32::try_from(bytes.len()).map_err(|_| Error::new("Structured clone data is too large", ErrorKind::Range))?;
+		self.write_pair(length, 0)?;
+		if unsafe { JS_WriteBytes(self.writer, bytes.as_ptr().cast(), bytes.len()) } {
+			Ok(())
+		} else {
+			Err(Error::new("Failed to write structured clone data", None))
+		}
+	}
+
+	/// Writes a string as UTF-8.
+	pub fn write_string(&mut self, string: &str) -> Result<()> {
+		self.write_bytes(string.as_bytes())
+	}
+}
+
+/// Reads the state of native values during structured cloning.
+pub struct StructuredCloneReader {
+	reader: *mut JSStructuredCloneReader,
+}
+
+impl StructuredCloneReader {
+	/// Reads a pair of integers.
+	pub fn read_pair(&mut self) -> Result<(u32, u32)> {
+		let (mut first, mut second) = (0, 0);
+		if unsafe { JS_ReadUint32Pair(self.reader, &mut first, &mut second) } {
+			Ok((first, second))
+		} else {
+			Err(Error::new("Failed to read structured clone data", None))
+		}
+	}
+
+	/// Reads a 64-bit integer written by [StructuredCloneWriter::write_u64].
+	pub fn read_u64(&mut self) -> Result<u64> {
+		let (high, low) = self.read_pair()?;
+		Ok(((high as u64) << 32) | low as u64)
+	}
+
+	/// Reads a sequence of bytes written by [StructuredCloneWriter::write_bytes].
+	pub fn read_bytes(&mut self) -> Result<Vec<u8>> {
+		let (length, _) = self.read_pair()?;
+		let mut bytes = vec![0; length as usize];
+		if unsafe { JS_ReadBytes(self.reader, bytes.as_mut_ptr().cast(), bytes.len()) } {
+			Ok(bytes)
+		} else {
+			Err(Error::new("Failed to read structured clone data", None))
+		}
+	}
+
+	/// Reads a string written by [StructuredCloneWriter::write_string].
+	pub fn read_string(&mut self) -> Result<String> {
+		String::from_utf8(self.read_bytes()?).map_err(|_| Error::new("Invalid UTF-8 in structured clone data", None))
+	}
+}
+
+unsafe extern "C" fn read_callback(
+	cx: *mut JSContext, reader: *mut JSStructuredCloneReader, _: *const CloneDataPolicy, tag: u32, _: u32, _: *mut c_void,
+) -> *mut JSObject {
+	let cx = unsafe { Context::new_unchecked(cx) };
+	let hook = tag
+		.checked_sub(SCTAG_USER_MIN)
+		.and_then(|index| CLONE_HOOKS.with(|hooks| hooks.borrow().get(index as usize).map(|(_, hook)| *hook)));
+
+	let result = match hook {
+		Some(hook) => (hook.read)(&cx, &mut StructuredCloneReader { reader }),
+		None => Err(Error::new("Unknown structured clone tag", ErrorKind::Type)),
+	};
+	result.unwrap_or_else(|error| {
+		error.throw(&cx);
+		ptr::null_mut()
+	})
+}
+
+unsafe extern "C" fn write_callback(
+	cx: *mut JSContext, writer: *mut JSStructuredCloneWriter, object: HandleObject, _: *mut bool, _: *mut c_void,
+) -> bool {
+	let cx = unsafe { Context::new_unchecked(cx) };
+	let object = Object::from(cx.root_object(unsafe { Handle::from_raw(object) }.get()));
+	let hook = CLONE_HOOKS.with(|hooks| {
+		hooks
+			.borrow()
+			.iter()
+			.enumerate()
+			.find(|(_, (_, hook))| (hook.instance_of)(&cx, &object))
+			.map(|(index, (_, hook))| (SCTAG_USER_MIN + index as u32, *hook))
+	});
+
+	let mut writer = StructuredCloneWriter { writer };
+	let result = match hook {
+		Some((tag, hook)) => writer.write_pair(tag, 0).and_then(|_| (hook.write)(&cx, &object, &mut writer)),
+		None => Err(Error::new("Object cannot be cloned", ErrorKind::Type)),
+	};
+	result.map_err(|error| error.throw(&cx)).is_ok()
+}
+
+unsafe extern "C" fn report_error_callback(cx: *mut JSContext, _: u32, _: *mut c_void, message: *const c_char) {
+	let cx = unsafe { Context::new_unchecked(cx) };
+	if !Exception::is_pending(&cx) {
+		let message = if message.is_null() {
+			String::from("Value cannot be cloned")
+		} else {
+			unsafe { CStr::from_ptr(message) }.to_string_lossy().into_owned()
+		};
+		Error::new(&message, ErrorKind::Type).throw(&cx);
+	}
+}
+
+static STRUCTURED_CLONE_CALLBACKS: JSStructuredCloneCallbacks = JSStructuredCloneCallbacks {
+	read: Some(read_callback),
+	write: Some(write_callback),
+	reportError: Some(report_error_callback),
+	readTransfer: None,
+	writeTransfer: None,
+	freeTransfer: None,
+	canTransfer: None,
+	sabCloned: None,
+};
+
+/// Represents a buffer holding the serialised form of a value, created by the structured clone algorithm.
+/// Built-in values, such as [Date](crate::Date), `Map`, `Set` and typed arrays are supported,
+/// as well as native classes which implement [StructuredClone].
+pub struct StructuredCloneBuffer {
+	buffer: *mut JSAutoStructuredCloneBuffer,
+	scope: StructuredCloneScope,
+}
+
+impl StructuredCloneBuffer {
+	/// Creates a new empty [StructuredCloneBuffer] with the given scope.
+	pub fn new(scope: StructuredCloneScope) -> StructuredCloneBuffer {
+		let buffer = unsafe { NewJSAutoStructuredCloneBuffer(scope, &STRUCTURED_CLONE_CALLBACKS) };
+		StructuredCloneBuffer { buffer, scope }
+	}
+
+	fn policy() -> CloneDataPolicy {
+		CloneDataPolicy {
+			allowIntraClusterClonableSharedObjects_: false,
+			allowSharedMemoryObjects_: false,
+		}
+	}
+
+	/// Serialises a value into the buffer.
+	/// Objects in `transfer`, such as `ArrayBuffer`s, are transferred rather than copied, and are detached afterwards.
+	pub fn write(&mut self, cx: &Context, value: &Value, transfer: &[Object]) -> ResultExc<()> {
+		let transfer = if transfer.is_empty() {
+			Value::undefined(cx)
+		} else {
+			transfer.as_value(cx)
+		};
+
+		let written = unsafe {
+			JS_WriteStructuredClone(
+				cx.as_ptr(),
+				value.handle().into(),
+				&mut (*self.buffer).data_,
+				self.scope,
+				&StructuredCloneBuffer::policy(),
+				&STRUCTURED_CLONE_CALLBACKS,
+				ptr::null_mut(),
+				transfer.handle().into(),
+			)
+		};
+		if written {
+			Ok(())
+		} else {
+			Err(Exception::new(cx).unwrap_or_else(|| Error::new("Failed to clone value", ErrorKind::Type).into()))
+		}
+	}
+
+	/// Deserialises the value held in the buffer.
+	pub fn read<'cx>(&mut self, cx: &'cx Context) -> ResultExc<Value<'cx>> {
+		let mut value = Value::undefined(cx);
+		let read = unsafe {
+			JS_ReadStructuredClone(
+				cx.as_ptr(),
+				&mut (*self.buffer).data_,
+				JS_STRUCTURED_CLONE_VERSION,
+				self.scope,
+				value.handle_mut().into(),
+				&StructuredCloneBuffer::policy(),
+				&STRUCTURED_CLONE_CALLBACKS,
+				ptr::null_mut(),
+			)
+		};
+		if read {
+			Ok(value)
+		} else {
+			Err(Exception::new(cx).unwrap_or_else(|| Error::new("Failed to clone value", ErrorKind::Type).into()))
+		}
+	}
+}
+
+impl Drop for StructuredCloneBuffer {
+	fn drop(&mut self) {
+		unsafe { DeleteJSAutoStructuredCloneBuffer(self.buffer) }
+	}
+}
@@ -12,8 +12,9 @@ use std::slice;
 
 use mozjs::jsapi::{
 	CurrentGlobalOrNull, ESClass, GetBuiltinClass, GetPropertyKeys, JS_DefineFunctionById, JS_DefineFunctions, JS_DefineFunctionsWithHelp,
-	JS_DefineProperties, JS_DefinePropertyById2, JS_DeletePropertyById, JS_GetPropertyById, JS_HasOwnPropertyById, JS_HasPropertyById,
-	JS_NewPlainObject, JS_SetPropertyById, JSFunctionSpec, JSFunctionSpecWithHelp, JSObject, JSPropertySpec, Unbox,
+	JS_DefineProperties, JS_DefinePropertyById2, JS_DeletePropertyById, JS_GetPropertyById, JS_GetPrototype, JS_HasOwnPropertyById,
+	JS_HasPropertyById, JS_NewPlainObject, JS_SetPropertyById, JS_SetPrototype, JSFunctionSpec, JSFunctionSpecWithHelp, JSObject, JSPropertySpec,
+	Unbox,
 };
 use mozjs::jsapi::PropertyKey as JSPropertyKey;
 use mozjs::jsval::NullValue;
@@ -220,6 +221,25 @@ impl<'o> Object<'o> {
 		None
 	}
 
+	/// Gets the prototype of the [Object].
+	///
+	/// Returns [None] if the prototype could not be retrieved, or is null.
+	pub fn get_prototype<'cx>(&self, cx: &'cx Context) -> Option<Object<'cx>> {
+		let mut prototype = Object::null(cx);
+		if unsafe { JS_GetPrototype(cx.as_ptr(), self.handle().into(), prototype.handle_mut().into()) } && !prototype.handle().is_null() {
+			Some(prototype)
+		} else {
+			None
+		}
+	}
+
+	/// Sets the prototype of the [Object].
+	///
+	/// Returns `false` if the prototype could not be set.
+	pub fn set_prototype(&mut self, cx: &Context, prototype: &Object) -> bool {
+		unsafe { JS_SetPrototype(cx.as_ptr(), self.handle().into(), prototype.handle().into()) }
+	}
+
 	/// Returns an iterator of the keys of the [Object].
 	/// Each key can be a [String], [Symbol](crate::symbol) or integer.
 	pub fn keys<'c, 'cx: 'o>(&self, cx: &'cx Context<'c>, flags: Option<IteratorFlags>) -> ObjectKeysIter<'c, 'cx> {
@@ -0,0 +1,17 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export const resolve = ______dnsInternal______.resolve;
+export const resolve4 = ______dnsInternal______.resolve4;
+export const resolve6 = ______dnsInternal______.resolve6;
+export const resolveTxt = ______dnsInternal______.resolveTxt;
+export const resolveSrv = ______dnsInternal______.resolveSrv;
+export const resolveMx = ______dnsInternal______.resolveMx;
+export const lookupService = ______dnsInternal______.lookupService;
+
+export const Resolver = ______dnsInternal______.Resolver;
+
+export default Object.freeze(______dnsInternal______);
@@ -0,0 +1,80 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::conversions::ConversionBehavior::EnforceRange;
+use mozjs::jsapi::JSFunctionSpec;
+
+use ion::{ClassDefinition, Context, Object, Result};
+use runtime::modules::NativeModule;
+
+use crate::dns::resolver::{DnsResolver, Family, lookup_service, MxRecord, ResolveOptions, Resolver, Service, SrvRecord};
+
+#[js_fn]
+async fn resolve(host: String, options: Option<ResolveOptions>) -> Result<Vec<String>> {
+	let family = ResolveOptions::family(options)?;
+	let addresses = DnsResolver::system()?.resolve(&host, family).await?;
+	Ok(addresses.iter().map(ToString::to_string).collect())
+}
+
+#[js_fn]
+async fn resolve4(name: String) -> Result<Vec<String>> {
+	let addresses = DnsResolver::system()?.query_addresses(&name, Family::V4).await?;
+	Ok(addresses.iter().map(ToString::to_string).collect())
+}
+
+#[js_fn]
+async fn resolve6(name: String) -> Result<Vec<String>> {
+	let addresses = DnsResolver::system()?.query_addresses(&name, Family::V6).await?;
+	Ok(addresses.iter().map(ToString::to_string).collect())
+}
+
+#[js_fn]
+async fn resolveTxt(name: String) -> Result<Vec<Vec<String>>> {
+	Ok(DnsResolver::system()?.query_txt(&name).await?)
+}
+
+#[js_fn]
+async fn resolveSrv(name: String) -> Result<Vec<SrvRecord>> {
+	Ok(DnsResolver::system()?.query_srv(&name).await?)
+}
+
+#[js_fn]
+async fn resolveMx(name: String) -> Result<Vec<MxRecord>> {
+	Ok(DnsResolver::system()?.query_mx(&name).await?)
+}
+
+#[js_fn]
+async fn lookupService(address: String, #[ion(convert = EnforceRange)] port: u16) -> Result<Service> {
+	lookup_service(address, port).await
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[
+	function_spec!(resolve, 1),
+	function_spec!(resolve4, 1),
+	function_spec!(resolve6, 1),
+	function_spec!(resolveTxt, 1),
+	function_spec!(resolveSrv, 1),
+	function_spec!(resolveMx, 1),
+	function_spec!(lookupService, 2),
+	JSFunctionSpec::ZERO,
+];
+
+#[derive(Default)]
+pub struct Dns;
+
+impl NativeModule for Dns {
+	const NAME: &'static str = "dns";
+	const SOURCE: &'static str = include_str!("dns.js");
+
+	fn module<'cx>(cx: &'cx Context) -> Option<Object<'cx>> {
+		let mut dns = Object::new(cx);
+		if unsafe { dns.define_methods(cx, FUNCTIONS) } {
+			Resolver::init_class(cx, &mut dns);
+			return Some(dns);
+		}
+		None
+	}
+}
@@ -0,0 +1,348 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use trust_dns_resolver::config::{LookupIpStrategy, NameServerConfig, NameServerConfigGroup, Protocol, ResolverConfig, ResolverOpts};
+use trust_dns_resolver::error::ResolveError;
+use trust_dns_resolver::proto::rr::{RData, RecordType};
+use trust_dns_resolver::TokioAsyncResolver;
+
+pub use class::*;
+use ion::{Context, Error, ErrorKind, Object, OwnedKey, Result, Value};
+use ion::conversions::{ConversionBehavior, FromValue, ToValue};
+use runtime::globals::fetch::{ResolveFuture, Resolver as HostResolver};
+
+static SYSTEM_RESOLVER: OnceLock<DnsResolver> = OnceLock::new();
+
+/// Address family of the addresses to resolve, where `0` resolves both IPv4 and IPv6 addresses.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Family {
+	#[default]
+	Any,
+	V4,
+	V6,
+}
+
+impl Family {
+	fn from_u8(family: u8) -> Result<Family> {
+		match family {
+			0 => Ok(Family::Any),
+			4 => Ok(Family::V4),
+			6 => Ok(Family::V6),
+			_ => Err(Error::new("Family must be 0, 4 or 6", ErrorKind::Type)),
+		}
+	}
+
+	fn matches(&self, address: &IpAddr) -> bool {
+		match self {
+			Family::Any => true,
+			Family::V4 => address.is_ipv4(),
+			Family::V6 => address.is_ipv6(),
+		}
+	}
+}
+
+#[derive(Default, FromValue)]
+pub struct ResolveOptions {
+	#[ion(convert = ConversionBehavior::EnforceRange, default)]
+	family: u8,
+}
+
+impl ResolveOptions {
+	pub fn family(options: Option<ResolveOptions>) -> Result<Family> {
+		Family::from_u8(options.unwrap_or_default().family)
+	}
+}
+
+#[derive(Default, FromValue)]
+pub struct ResolverInit {
+	#[ion(default)]
+	servers: Vec<String>,
+	#[ion(default, parser = |hosts| parse_hosts(cx, hosts))]
+	hosts: HashMap<String, Vec<IpAddr>>,
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	timeout: Option<u64>,
+}
+
+pub struct SrvRecord {
+	priority: u16,
+	weight: u16,
+	port: u16,
+	name: String,
+}
+
+impl<'cx> ToValue<'cx> for SrvRecord {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "priority", &self.priority);
+		object.set_as(cx, "weight", &self.weight);
+		object.set_as(cx, "port", &self.port);
+		object.set_as(cx, "name", self.name.as_str());
+		object.to_value(cx, value);
+	}
+}
+
+pub struct MxRecord {
+	priority: u16,
+	exchange: String,
+}
+
+impl<'cx> ToValue<'cx> for MxRecord {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "priority", &self.priority);
+		object.set_as(cx, "exchange", self.exchange.as_str());
+		object.to_value(cx, value);
+	}
+}
+
+pub struct Service {
+	hostname: String,
+	service: String,
+}
+
+impl<'cx> ToValue<'cx> for Service {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "hostname", self.hostname.as_str());
+		object.set_as(cx, "service", self.service.as_str());
+		object.to_value(cx, value);
+	}
+}
+
+fn parse_server(server: &str) -> Result<SocketAddr> {
+	if let Ok(address) = server.parse::<IpAddr>() {
+		return Ok(SocketAddr::new(address, 53));
+	}
+	server
+		.parse()
+		.map_err(|_| Error::new(&format!("Invalid DNS server: {}", server), ErrorKind::Type))
+}
+
+fn parse_address(address: &str) -> Result<IpAddr> {
+	address
+		.parse()
+		.map_err(|_| Error::new(&format!("Invalid address: {}", address), ErrorKind::Type))
+}
+
+/// Parses hosts which are overridden, as an object of host names to an address or array of addresses.
+fn parse_hosts<'cx: 'v, 'v>(cx: &'cx Context, hosts: Value<'v>) -> Result<HashMap<String, Vec<IpAddr>>> {
+	let object = Object::from_value(cx, &hosts, true, ())?;
+	let mut overrides = HashMap::new();
+	for key in object.keys(cx, None).map(|key| key.to_owned_key(cx)) {
+		let host = match key {
+			OwnedKey::Int(i) => i.to_string(),
+			OwnedKey::String(s) => s,
+			_ => continue,
+		};
+
+		let value = object.get(cx, &host).unwrap();
+		let addresses = if value.handle().is_string() {
+			vec![String::from_value(cx, &value, true, ())?]
+		} else {
+			Vec::<String>::from_value(cx, &value, true, ())?
+		};
+		let addresses = addresses.iter().map(|address| parse_address(address)).collect::<Result<_>>()?;
+		overrides.insert(host.to_ascii_lowercase(), addresses);
+	}
+	Ok(overrides)
+}
+
+fn parse_name(name: &str) -> String {
+	String::from(name.trim_end_matches('.'))
+}
+
+/// Resolver which queries the system name servers, or the configured servers, with hosts which are overridden.
+pub struct DnsResolver {
+	resolver: TokioAsyncResolver,
+	hosts: HashMap<String, Vec<IpAddr>>,
+}
+
+impl DnsResolver {
+	/// Returns the resolver which uses the system configuration, shared by the functions of the module.
+	pub fn system() -> Result<&'static DnsResolver> {
+		if let Some(resolver) = SYSTEM_RESOLVER.get() {
+			return Ok(resolver);
+		}
+		let (config, mut options) = trust_dns_resolver::system_conf::read_system_conf()?;
+		options.ip_strategy = LookupIpStrategy::Ipv4AndIpv6;
+		let resolver = DnsResolver {
+			resolver: TokioAsyncResolver::tokio(config, options),
+			hosts: HashMap::new(),
+		};
+		Ok(SYSTEM_RESOLVER.get_or_init(|| resolver))
+	}
+
+	pub fn new(init: ResolverInit) -> Result<DnsResolver> {
+		let (config, mut options) = if init.servers.is_empty() {
+			trust_dns_resolver::system_conf::read_system_conf()?
+		} else {
+			let mut servers = Vec::with_capacity(init.servers.len() * 2);
+			for server in &init.servers {
+				let address = parse_server(server)?;
+				servers.push(NameServerConfig::new(address, Protocol::Udp));
+				servers.push(NameServerConfig::new(address, Protocol::Tcp));
+			}
+			let config = ResolverConfig::from_parts(None, Vec::new(), NameServerConfigGroup::from(servers));
+			(config, ResolverOpts::default())
+		};
+		options.ip_strategy = LookupIpStrategy::Ipv4AndIpv6;
+		if let Some(timeout) = init.timeout {
+			options.timeout = Duration::from_millis(timeout);
+		}
+
+		Ok(DnsResolver {
+			resolver: TokioAsyncResolver::tokio(config, options),
+			hosts: init.hosts,
+		})
+	}
+
+	/// Resolves the addresses of a host, in the address family. Overridden hosts are not queried.
+	pub async fn resolve(&self, host: &str, family: Family) -> std::result::Result<Vec<IpAddr>, ResolveError> {
+		if let Some(addresses) = self.hosts.get(&host.to_ascii_lowercase()) {
+			return Ok(addresses.iter().filter(|address| family.matches(address)).copied().collect());
+		}
+
+		let lookup = self.resolver.lookup_ip(host).await?;
+		Ok(lookup.iter().filter(|address| family.matches(address)).collect())
+	}
+
+	async fn query(&self, name: &str, kind: RecordType) -> std::result::Result<Vec<RData>, ResolveError> {
+		let lookup = self.resolver.lookup(name, kind).await?;
+		Ok(lookup.iter().cloned().collect())
+	}
+
+	/// Queries the `A` or `AAAA` records of a name. Overridden hosts are not queried.
+	pub async fn query_addresses(&self, name: &str, family: Family) -> std::result::Result<Vec<IpAddr>, ResolveError> {
+		if self.hosts.contains_key(&name.to_ascii_lowercase()) {
+			return self.resolve(name, family).await;
+		}
+		let kind = if family == Family::V6 { RecordType::AAAA } else { RecordType::A };
+		let records = self.query(name, kind).await?;
+		Ok(records.iter().filter_map(RData::ip_addr).collect())
+	}
+
+	/// Queries the `TXT` records of a name, as their chunks.
+	pub async fn query_txt(&self, name: &str) -> std::result::Result<Vec<Vec<String>>, ResolveError> {
+		let records = self.query(name, RecordType::TXT).await?;
+		Ok(records
+			.iter()
+			.filter_map(|record| match record {
+				RData::TXT(txt) => Some(txt.txt_data().iter().map(|chunk| String::from_utf8_lossy(chunk).into_owned()).collect()),
+				_ => None,
+			})
+			.collect())
+	}
+
+	/// Queries the `SRV` records of a name.
+	pub async fn query_srv(&self, name: &str) -> std::result::Result<Vec<SrvRecord>, ResolveError> {
+		let records = self.query(name, RecordType::SRV).await?;
+		Ok(records
+			.iter()
+			.filter_map(|record| match record {
+				RData::SRV(srv) => Some(SrvRecord {
+					priority: srv.priority(),
+					weight: srv.weight(),
+					port: srv.port(),
+					name: parse_name(&srv.target().to_utf8()),
+				}),
+				_ => None,
+			})
+			.collect())
+	}
+
+	/// Queries the `MX` records of a name.
+	pub async fn query_mx(&self, name: &str) -> std::result::Result<Vec<MxRecord>, ResolveError> {
+		let records = self.query(name, RecordType::MX).await?;
+		Ok(records
+			.iter()
+			.filter_map(|record| match record {
+				RData::MX(mx) => Some(MxRecord {
+					priority: mx.preference(),
+					exchange: parse_name(&mx.exchange().to_utf8()),
+				}),
+				_ => None,
+			})
+			.collect())
+	}
+}
+
+impl HostResolver for DnsResolver {
+	fn resolve<'r>(&'r self, host: &'r str) -> ResolveFuture<'r> {
+		Box::pin(async move { DnsResolver::resolve(self, host, Family::Any).await.map_err(io::Error::from) })
+	}
+}
+
+/// Resolves the host name and service of an address and port, with the system resolver.
+pub async fn lookup_service(address: String, port: u16) -> Result<Service> {
+	let address = SocketAddr::new(parse_address(&address)?, port);
+	let (hostname, service) = tokio::task::spawn_blocking(move || dns_lookup::getnameinfo(&address, 0)).await??;
+	Ok(Service { hostname, service })
+}
+
+#[js_class]
+mod class {
+	use std::sync::Arc;
+
+	use mozjs::conversions::ConversionBehavior::EnforceRange;
+
+	use ion::Result;
+
+	use crate::dns::resolver::{DnsResolver, Family, lookup_service, MxRecord, ResolveOptions, ResolverInit, Service, SrvRecord};
+
+	/// Resolver which queries the configured name servers, and resolves overridden hosts to their addresses.
+	/// It can be used as the resolver of a `Client`.
+	#[derive(Clone)]
+	#[ion(from_value, into_value)]
+	pub struct Resolver {
+		pub(crate) resolver: Arc<DnsResolver>,
+	}
+
+	impl Resolver {
+		#[ion(constructor)]
+		pub fn constructor(options: Option<ResolverInit>) -> Result<Resolver> {
+			let resolver = DnsResolver::new(options.unwrap_or_default())?;
+			Ok(Resolver { resolver: Arc::new(resolver) })
+		}
+
+		pub async fn resolve(&self, host: String, options: Option<ResolveOptions>) -> Result<Vec<String>> {
+			let family = ResolveOptions::family(options)?;
+			let addresses = self.resolver.resolve(&host, family).await?;
+			Ok(addresses.iter().map(ToString::to_string).collect())
+		}
+
+		pub async fn resolve4(&self, name: String) -> Result<Vec<String>> {
+			let addresses = self.resolver.query_addresses(&name, Family::V4).await?;
+			Ok(addresses.iter().map(ToString::to_string).collect())
+		}
+
+		pub async fn resolve6(&self, name: String) -> Result<Vec<String>> {
+			let addresses = self.resolver.query_addresses(&name, Family::V6).await?;
+			Ok(addresses.iter().map(ToString::to_string).collect())
+		}
+
+		pub async fn resolveTxt(&self, name: String) -> Result<Vec<Vec<String>>> {
+			Ok(self.resolver.query_txt(&name).await?)
+		}
+
+		pub async fn resolveSrv(&self, name: String) -> Result<Vec<SrvRecord>> {
+			Ok(self.resolver.query_srv(&name).await?)
+		}
+
+		pub async fn resolveMx(&self, name: String) -> Result<Vec<MxRecord>> {
+			Ok(self.resolver.query_mx(&name).await?)
+		}
+
+		pub async fn lookupService(&self, address: String, #[ion(convert = EnforceRange)] port: u16) -> Result<Service> {
+			lookup_service(address, port).await
+		}
+	}
+}
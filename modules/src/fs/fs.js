@@ -6,6 +6,7 @@
 
 export const readBinary = ______fsInternal______.readBinary;
 export const readString = ______fsInternal______.readString;
+export const openFile = ______fsInternal______.openFile;
 export const readDir = ______fsInternal______.readDir;
 export const write = ______fsInternal______.write;
 export const createDir = ______fsInternal______.createDir;
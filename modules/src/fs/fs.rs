@@ -6,7 +6,7 @@
 
 use std::{fs, os};
 use std::iter::Iterator;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use futures::stream::StreamExt;
 use mozjs::jsapi::JSFunctionSpec;
@@ -15,8 +15,15 @@ use tokio_stream::wrappers::ReadDirStream;
 use ion::{Context, Error, Object, Result};
 use ion::flags::PropertyFlags;
 use ion::typedarray::Uint8Array;
+use runtime::globals::file::File;
 use runtime::modules::NativeModule;
 
+#[derive(Default, FromValue)]
+pub struct OpenFileOptions {
+	#[ion(name = "type")]
+	kind: Option<String>,
+}
+
 fn check_exists(path: &Path) -> Result<()> {
 	if path.exists() {
 		Err(Error::new(&format!("Path {} does not exist", path.to_str().unwrap()), None))
@@ -117,6 +124,28 @@ fn readStringSync(path_str: String) -> Result<String> {
 	}
 }
 
+#[js_fn]
+async fn openFile(path_str: String, options: Option<OpenFileOptions>) -> Result<File> {
+	let path = PathBuf::from(&path_str);
+	let kind = options.unwrap_or_default().kind.unwrap_or_default();
+
+	match tokio::fs::metadata(&path).await {
+		Ok(metadata) if metadata.is_file() => Ok(File::from_metadata(path, &metadata, &kind)),
+		_ => Err(Error::new(&format!("Could not open file: {}", path_str), None)),
+	}
+}
+
+#[js_fn]
+fn openFileSync(path_str: String, options: Option<OpenFileOptions>) -> Result<File> {
+	let path = PathBuf::from(&path_str);
+	let kind = options.unwrap_or_default().kind.unwrap_or_default();
+
+	match fs::metadata(&path) {
+		Ok(metadata) if metadata.is_file() => Ok(File::from_metadata(path, &metadata, &kind)),
+		_ => Err(Error::new(&format!("Could not open file: {}", path_str), None)),
+	}
+}
+
 #[js_fn]
 async fn readDir(path_str: String) -> Result<Vec<String>> {
 	let path = Path::new(&path_str);
@@ -350,6 +379,7 @@ fn hardLinkSync(original_str: String, link_str: String) -> Result<bool> {
 const SYNC_FUNCTIONS: &[JSFunctionSpec] = &[
 	function_spec!(readBinarySync, "readBinary", 1),
 	function_spec!(readStringSync, "readString", 1),
+	function_spec!(openFileSync, "openFile", 1),
 	function_spec!(readDirSync, "readDir", 1),
 	function_spec!(writeSync, "write", 2),
 	function_spec!(createDirSync, "createDir", 1),
@@ -367,6 +397,7 @@ const SYNC_FUNCTIONS: &[JSFunctionSpec] = &[
 const ASYNC_FUNCTIONS: &[JSFunctionSpec] = &[
 	function_spec!(readBinary, 1),
 	function_spec!(readString, 1),
+	function_spec!(openFile, 1),
 	function_spec!(readDir, 1),
 	function_spec!(write, 2),
 	function_spec!(createDir, 1),
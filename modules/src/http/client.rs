@@ -4,14 +4,56 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use hyper::client::HttpConnector;
-use hyper_rustls::HttpsConnector;
-
 pub use class::*;
 use ion::{Context, Value};
 use ion::conversions::ConversionBehavior;
 use ion::conversions::FromValue;
-use runtime::globals::fetch::{default_client, GLOBAL_CLIENT};
+use runtime::globals::fetch::{default_client, FetchClient, GLOBAL_CLIENT, Proxy, ProxyConfig, RedirectHook, TlsOptions};
+
+use crate::dns::Resolver;
+use crate::http::retry::RetryPolicy;
+
+#[derive(Clone, Default)]
+pub enum ProxyOption {
+	#[default]
+	Environment,
+	Disabled,
+	Url(String),
+}
+
+impl ProxyOption {
+	pub fn to_config(&self, no_proxy: Option<&str>) -> ion::Result<ProxyConfig> {
+		let config = match self {
+			ProxyOption::Environment => ProxyConfig::from_env(),
+			ProxyOption::Disabled => ProxyConfig::default(),
+			ProxyOption::Url(url) => ProxyConfig::new(Some(Proxy::new(url)?), None),
+		};
+		Ok(match no_proxy {
+			Some(no_proxy) => config.no_proxy(no_proxy),
+			None => config,
+		})
+	}
+}
+
+impl<'cx> FromValue<'cx> for ProxyOption {
+	type Config = ();
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, _: bool, _: ()) -> ion::Result<ProxyOption>
+	where
+		'cx: 'v,
+	{
+		if value.handle().is_undefined() || value.handle().is_null() {
+			Ok(ProxyOption::Environment)
+		} else if value.handle().is_boolean() {
+			if value.handle().to_boolean() {
+				Ok(ProxyOption::Environment)
+			} else {
+				Ok(ProxyOption::Disabled)
+			}
+		} else {
+			Ok(ProxyOption::Url(String::from_value(cx, value, true, ())?))
+		}
+	}
+}
 
 #[derive(Derivative, FromValue)]
 #[derivative(Default)]
@@ -27,6 +69,51 @@ pub struct ClientInit {
 	#[ion(default = true)]
 	#[derivative(Default(value = "true"))]
 	retry_cancelled: bool,
+	#[ion(default = true)]
+	#[derivative(Default(value = "true"))]
+	cookies: bool,
+	#[ion(default = true)]
+	#[derivative(Default(value = "true"))]
+	http2: bool,
+	#[ion(default)]
+	http2_prior_knowledge: bool,
+	#[ion(default)]
+	proxy: ProxyOption,
+	#[ion(default)]
+	no_proxy: Option<String>,
+	#[ion(default)]
+	system_roots: bool,
+	#[ion(default)]
+	ca: Vec<String>,
+	#[ion(default)]
+	certificate: Option<String>,
+	#[ion(default)]
+	key: Option<String>,
+	#[ion(default)]
+	pinned_certificates: Vec<String>,
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	connect_timeout: Option<u64>,
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	read_timeout: Option<u64>,
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	timeout: Option<u64>,
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	max_redirects: Option<u8>,
+	on_redirect: Option<RedirectHook>,
+	retry: Option<RetryPolicy>,
+	resolver: Option<Resolver>,
+}
+
+impl ClientInit {
+	fn tls_options(&self) -> TlsOptions {
+		TlsOptions {
+			system_roots: self.system_roots,
+			ca: self.ca.clone(),
+			certificate: self.certificate.clone(),
+			key: self.key.clone(),
+			pinned_certificates: self.pinned_certificates.clone(),
+		}
+	}
 }
 
 #[derive(Clone, Default)]
@@ -38,7 +125,7 @@ pub enum ClientRequestOptions {
 }
 
 impl ClientRequestOptions {
-	pub fn to_client(&self) -> hyper::Client<HttpsConnector<HttpConnector>> {
+	pub fn to_client(&self) -> FetchClient {
 		use ClientRequestOptions as CRO;
 		match self {
 			CRO::Global => GLOBAL_CLIENT.get().unwrap().clone(),
@@ -46,6 +133,22 @@ impl ClientRequestOptions {
 			CRO::Client(client) => client.client.clone(),
 		}
 	}
+
+	/// Returns the retry policy of the client. Requests through the global client are not retried.
+	pub fn retry_policy(&self) -> Option<RetryPolicy> {
+		match self {
+			ClientRequestOptions::Client(client) => client.retry.clone(),
+			_ => None,
+		}
+	}
+
+	/// Returns the redirect hook of the client, which is used by requests without their own.
+	pub fn redirect_hook(&self) -> Option<RedirectHook> {
+		match self {
+			ClientRequestOptions::Client(client) => client.redirect_hook.clone(),
+			_ => None,
+		}
+	}
 }
 
 impl<'cx> FromValue<'cx> for ClientRequestOptions {
@@ -74,23 +177,34 @@ mod class {
 	use std::ops::Deref;
 	use std::time::Duration;
 
-	use hyper::client::HttpConnector;
-	use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+	use std::sync::Arc;
+
+	use url::Url;
 
+	use ion::Result;
+	use runtime::globals::fetch::{ClientStats, connector, FetchClient, MockTransport, RedirectHook, Resolver as HostResolver, Timeouts};
+
+	use crate::dns::Resolver;
 	use crate::http::client::ClientInit;
+	use crate::http::retry::RetryPolicy;
 
 	#[derive(Clone)]
 	#[ion(from_value, into_value)]
 	pub struct Client {
-		pub(crate) client: hyper::Client<HttpsConnector<HttpConnector>>,
+		pub(crate) client: FetchClient,
+		pub(crate) redirect_hook: Option<RedirectHook>,
+		pub(crate) retry: Option<RetryPolicy>,
 	}
 
 	impl Client {
 		#[ion(constructor)]
-		pub fn constructor(options: Option<ClientInit>) -> Client {
+		pub fn constructor(options: Option<ClientInit>) -> Result<Client> {
 			let options = options.unwrap_or_default();
 
-			let https = HttpsConnectorBuilder::new().with_webpki_roots().https_or_http().enable_http1().build();
+			let proxy = Arc::new(options.proxy.to_config(options.no_proxy.as_deref())?);
+			let tls = options.tls_options().client_config()?;
+			let stats = ClientStats::default();
+			let https = connector(Arc::clone(&proxy), stats.clone(), tls, options.http2 || options.http2_prior_knowledge);
 
 			let mut client = hyper::Client::builder();
 
@@ -103,17 +217,82 @@ mod class {
 			}
 
 			client.retry_canceled_requests(options.retry_cancelled);
+			client.http2_only(options.http2_prior_knowledge);
 			client.set_host(false);
 
-			let client = client.build(https);
-			Client { client }
+			let mut client = FetchClient::new(client.build(https), proxy, stats);
+			if !options.cookies {
+				client.cookies = None;
+			}
+			client.timeouts = Timeouts::from_millis(options.connect_timeout, options.read_timeout, options.timeout);
+			if let Some(max_redirects) = options.max_redirects {
+				client.max_redirects = max_redirects;
+			}
+			client.set_resolver(options.resolver.map(|resolver| resolver.resolver as Arc<dyn HostResolver>));
+			Ok(Client {
+				client,
+				redirect_hook: options.on_redirect,
+				retry: options.retry,
+			})
+		}
+
+		/// Returns the requests issued by the client, the bytes sent and received over its connections, and the active and idle connections to each host.
+		#[ion(get)]
+		pub fn get_stats(&self) -> ClientStats {
+			self.client.stats.clone()
+		}
+
+		/// Sends the requests of the client, and its clones, through a mock transport. Passing `null` restores the network.
+		pub fn setTransport(&self, transport: Option<MockTransport>) {
+			self.client.set_transport(transport.map(|transport| transport.transport()));
+		}
+
+		/// Resolves the hosts which the client connects to with a resolver. Passing `null` restores the system resolver.
+		pub fn setResolver(&self, resolver: Option<Resolver>) {
+			self.client
+				.set_resolver(resolver.map(|resolver| resolver.resolver as Arc<dyn HostResolver>));
+		}
+
+		/// Returns the `Cookie` header which would be sent with a request to the URL.
+		pub fn getCookies(&self, url: String) -> Result<Option<String>> {
+			let url = Url::parse(&url)?;
+			Ok(self.client.cookies.as_ref().and_then(|cookies| cookies.get(&url)))
+		}
+
+		/// Stores a cookie as if it were received in a `Set-Cookie` header from the URL.
+		pub fn setCookie(&self, url: String, cookie: String) -> Result<()> {
+			let url = Url::parse(&url)?;
+			if let Some(cookies) = &self.client.cookies {
+				cookies.set(&url, &cookie);
+			}
+			Ok(())
+		}
+
+		pub fn clearCookies(&self) {
+			if let Some(cookies) = &self.client.cookies {
+				cookies.clear();
+			}
+		}
+
+		pub fn saveCookies(&self, path: String) -> Result<()> {
+			if let Some(cookies) = &self.client.cookies {
+				cookies.save(path)?;
+			}
+			Ok(())
+		}
+
+		pub fn loadCookies(&self, path: String) -> Result<()> {
+			if let Some(cookies) = &self.client.cookies {
+				cookies.load(path)?;
+			}
+			Ok(())
 		}
 	}
 
 	impl Deref for Client {
-		type Target = hyper::Client<HttpsConnector<HttpConnector>>;
+		type Target = FetchClient;
 
-		fn deref(&self) -> &hyper::Client<HttpsConnector<HttpConnector>> {
+		fn deref(&self) -> &FetchClient {
 			&self.client
 		}
 	}
@@ -8,11 +8,14 @@ export const get = ______httpInternal______.get;
 export const post = ______httpInternal______.post;
 export const put = ______httpInternal______.put;
 export const request = ______httpInternal______.request;
+export const setTransport = ______httpInternal______.setTransport;
+export const setResolver = ______httpInternal______.setResolver;
 
 export const Headers = ______httpInternal______.Headers;
 export const Request = ______httpInternal______.Request;
 export const Response = ______httpInternal______.Response;
 
 export const Client = ______httpInternal______.Client;
+export const MockTransport = ______httpInternal______.MockTransport;
 
 export default Object.freeze(______httpInternal______);
@@ -4,16 +4,21 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use hyper::client::HttpConnector;
+use std::sync::Arc;
+
 use hyper::Method;
-use hyper_rustls::HttpsConnector;
 use mozjs::jsapi::JSFunctionSpec;
 
 use ion::{ClassDefinition, Context, Object, ResultExc};
-use runtime::globals::fetch::{default_client, GLOBAL_CLIENT, Headers, Request, request_internal, RequestBuilderInit, RequestInit, Resource, Response};
+use runtime::globals::fetch::{
+	default_client, FetchClient, GLOBAL_CLIENT, Headers, MockTransport, RedirectHook, Request, RequestBuilderInit, RequestInit,
+	Resolver as HostResolver, Resource, Response,
+};
 use runtime::modules::NativeModule;
 
+use crate::dns::Resolver;
 use crate::http::client::{Client, ClientRequestOptions};
+use crate::http::retry::{RetryPolicy, send_with_retries};
 
 #[derive(Default, FromValue)]
 pub struct RequestClientInit {
@@ -22,57 +27,102 @@ pub struct RequestClientInit {
 	init: RequestInit,
 }
 
-fn to_client(init: Option<&RequestClientInit>) -> hyper::client::Client<HttpsConnector<HttpConnector>> {
+fn to_client(init: Option<&RequestClientInit>) -> FetchClient {
 	init.map(|init| init.client.to_client())
 		.unwrap_or_else(|| GLOBAL_CLIENT.get().unwrap().clone())
 }
 
+fn retry_policy(init: Option<&RequestClientInit>) -> Option<RetryPolicy> {
+	init.and_then(|init| init.client.retry_policy())
+}
+
+fn redirect_hook(init: Option<&RequestClientInit>) -> Option<RedirectHook> {
+	init.and_then(|init| init.client.redirect_hook())
+}
+
 #[js_fn]
 async fn get(url: String, init: Option<RequestClientInit>) -> ResultExc<Response> {
 	let client = to_client(init.as_ref());
+	let hook = redirect_hook(init.as_ref());
+	let retry = retry_policy(init.as_ref());
 	let options = RequestBuilderInit::from_request_init(init.map(|opt| opt.init), Method::GET.to_string());
-	let request = Request::constructor(Resource::String(url), Some(options))?;
+	let mut request = Request::new_unguarded(Resource::String(url), Some(options))?;
+	request.set_default_redirect_hook(hook);
 
-	request_internal(request, client).await
+	send_with_retries(request, client, retry).await
 }
 
 #[js_fn]
 async fn post(url: String, init: Option<RequestClientInit>) -> ResultExc<Response> {
 	let client = to_client(init.as_ref());
+	let hook = redirect_hook(init.as_ref());
+	let retry = retry_policy(init.as_ref());
 	let options = RequestBuilderInit::from_request_init(init.map(|opt| opt.init), Method::POST.to_string());
-	let request = Request::constructor(Resource::String(url), Some(options))?;
+	let mut request = Request::new_unguarded(Resource::String(url), Some(options))?;
+	request.set_default_redirect_hook(hook);
 
-	request_internal(request, client).await
+	send_with_retries(request, client, retry).await
 }
 
 #[js_fn]
 async fn put(url: String, init: Option<RequestClientInit>) -> ResultExc<Response> {
 	let client = to_client(init.as_ref());
+	let hook = redirect_hook(init.as_ref());
+	let retry = retry_policy(init.as_ref());
 	let options = RequestBuilderInit::from_request_init(init.map(|opt| opt.init), Method::PUT.to_string());
-	let request = Request::constructor(Resource::String(url), Some(options))?;
+	let mut request = Request::new_unguarded(Resource::String(url), Some(options))?;
+	request.set_default_redirect_hook(hook);
 
-	request_internal(request, client).await
+	send_with_retries(request, client, retry).await
 }
 
 #[js_fn]
 async fn request(resource: Resource, method: Option<String>, init: Option<RequestClientInit>) -> ResultExc<Response> {
 	let client = to_client(init.as_ref());
+	let hook = redirect_hook(init.as_ref());
+	let retry = retry_policy(init.as_ref());
 	match resource {
-		Resource::Request(request) => request_internal(request, client).await,
+		Resource::Request(mut request) => {
+			request.set_default_redirect_hook(hook);
+			send_with_retries(request, client, retry).await
+		}
 		Resource::String(url) => {
 			let options = RequestBuilderInit::from_request_init(init.map(|opt| opt.init), method);
-			let request = Request::constructor(Resource::String(url), Some(options))?;
+			let mut request = Request::new_unguarded(Resource::String(url), Some(options))?;
+			request.set_default_redirect_hook(hook);
 
-			request_internal(request, client).await
+			send_with_retries(request, client, retry).await
 		}
 	}
 }
 
+/// Sends the requests of the global client, including those of `fetch`, through a mock transport.
+/// Passing `null` restores the network.
+#[js_fn]
+fn setTransport(transport: Option<MockTransport>) {
+	GLOBAL_CLIENT
+		.get()
+		.unwrap()
+		.set_transport(transport.map(|transport| transport.transport()));
+}
+
+/// Resolves the hosts which the global client, including `fetch`, connects to with a resolver.
+/// Passing `null` restores the system resolver.
+#[js_fn]
+fn setResolver(resolver: Option<Resolver>) {
+	GLOBAL_CLIENT
+		.get()
+		.unwrap()
+		.set_resolver(resolver.map(|resolver| resolver.resolver as Arc<dyn HostResolver>));
+}
+
 const FUNCTIONS: &[JSFunctionSpec] = &[
 	function_spec!(get, 1),
 	function_spec!(post, 1),
 	function_spec!(put, 1),
 	function_spec!(request, 1),
+	function_spec!(setTransport, 1),
+	function_spec!(setResolver, 1),
 	JSFunctionSpec::ZERO,
 ];
 
@@ -91,6 +141,7 @@ impl NativeModule for Http {
 			http.define_methods(cx, FUNCTIONS);
 		}
 		Client::init_class(cx, &mut http);
+		MockTransport::init_class(cx, &mut http);
 		GLOBAL_CLIENT.get_or_init(default_client);
 
 		if let Some(headers) = global.get(cx, stringify!(Headers)) {
@@ -8,3 +8,4 @@ pub use self::http::*;
 
 mod client;
 mod http;
+mod retry;
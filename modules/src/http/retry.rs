@@ -0,0 +1,124 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::future::pending;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use futures::future::select;
+use hyper::header::RETRY_AFTER;
+use hyper::Method;
+use rand::Rng;
+use tokio::time::sleep;
+
+use ion::conversions::ConversionBehavior;
+use ion::ResultExc;
+use runtime::globals::fetch::{FetchClient, Request, request_attempt, request_internal, Response};
+
+const DEFAULT_RETRY_STATUSES: [u16; 6] = [408, 429, 500, 502, 503, 504];
+const IDEMPOTENT_METHODS: [Method; 6] = [Method::GET, Method::HEAD, Method::OPTIONS, Method::PUT, Method::DELETE, Method::TRACE];
+
+/// Policy for retrying requests which fail, or receive a retryable status.
+/// Delays grow exponentially from `backoff` up to `maxBackoff`, unless the response has a `Retry-After` header.
+#[derive(Clone, Derivative, FromValue)]
+#[derivative(Default)]
+pub struct RetryPolicy {
+	#[ion(convert = ConversionBehavior::EnforceRange, default = 3)]
+	#[derivative(Default(value = "3"))]
+	attempts: u32,
+	#[ion(convert = ConversionBehavior::Clamp, default = 100)]
+	#[derivative(Default(value = "100"))]
+	backoff: u64,
+	#[ion(convert = ConversionBehavior::Clamp, default = 10000)]
+	#[derivative(Default(value = "10000"))]
+	max_backoff: u64,
+	#[ion(default = true)]
+	#[derivative(Default(value = "true"))]
+	jitter: bool,
+	#[ion(convert = ConversionBehavior::EnforceRange, default = DEFAULT_RETRY_STATUSES.to_vec())]
+	#[derivative(Default(value = "DEFAULT_RETRY_STATUSES.to_vec()"))]
+	statuses: Vec<u16>,
+	#[ion(default = true)]
+	#[derivative(Default(value = "true"))]
+	retry_after: bool,
+	#[ion(default)]
+	retry_non_idempotent: bool,
+}
+
+impl RetryPolicy {
+	fn can_retry(&self, request: &Request) -> bool {
+		if self.attempts <= 1 {
+			return false;
+		}
+		let method = Method::from_bytes(request.get_method().as_bytes()).unwrap_or_default();
+		self.retry_non_idempotent || IDEMPOTENT_METHODS.contains(&method)
+	}
+
+	/// Returns the delay before the next attempt, or [None] if the server asked for a longer delay than `maxBackoff`.
+	fn delay(&self, attempt: u32, response: Option<&Response>) -> Option<Duration> {
+		if let Some(delay) = response.filter(|_| self.retry_after).and_then(retry_after) {
+			return (delay <= Duration::from_millis(self.max_backoff)).then_some(delay);
+		}
+
+		let backoff = self.backoff.saturating_mul(1 << (attempt - 1).min(32)).min(self.max_backoff);
+		let backoff = if self.jitter && backoff > 0 {
+			rand::thread_rng().gen_range(backoff / 2..=backoff)
+		} else {
+			backoff
+		};
+		Some(Duration::from_millis(backoff))
+	}
+}
+
+/// Parses the `Retry-After` header of a response, as either a number of seconds or a HTTP date.
+fn retry_after(response: &Response) -> Option<Duration> {
+	let header = response.get_headers().inner().get(RETRY_AFTER)?.to_str().ok()?.trim().to_string();
+	if let Ok(seconds) = header.parse::<u64>() {
+		return Some(Duration::from_secs(seconds));
+	}
+	let date = DateTime::parse_from_rfc2822(&header).ok()?;
+	Some((date.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or_default())
+}
+
+/// Sends a request, retrying it according to the policy.
+/// Only idempotent requests are retried, unless the policy allows otherwise.
+/// Failures are only retried if they are transient, such as connection failures, resets and timeouts.
+/// Aborting the request stops any further attempts.
+pub(crate) async fn send_with_retries(request: Request, client: FetchClient, policy: Option<RetryPolicy>) -> ResultExc<Response> {
+	let policy = match policy {
+		Some(policy) if policy.can_retry(&request) => policy,
+		_ => return request_internal(request, client).await,
+	};
+
+	let mut attempt = 1;
+	while attempt < policy.attempts {
+		let (result, transient) = request_attempt(request.duplicate()?, client.clone()).await;
+		if request.signal().is_aborted() {
+			return result;
+		}
+
+		let delay = match &result {
+			Ok(response) if policy.statuses.contains(&response.get_status()) => policy.delay(attempt, Some(response)),
+			Err(_) if transient => policy.delay(attempt, None),
+			_ => return result,
+		};
+		let delay = match delay {
+			Some(delay) => delay,
+			None => return result,
+		};
+		drop(result);
+
+		let aborted = async {
+			if request.signal().aborted().await.is_none() {
+				pending::<()>().await;
+			}
+		};
+		select(Box::pin(sleep(delay)), Box::pin(aborted)).await;
+		attempt += 1;
+	}
+
+	request_internal(request, client).await
+}
@@ -16,15 +16,19 @@ use ion::{Context, Object};
 use runtime::modules::{init_global_module, init_module, StandardModules};
 
 pub use crate::assert::Assert;
+pub use crate::dns::Dns;
 pub use crate::fs::FileSystem;
 pub use crate::http::Http;
 pub use crate::path::PathM;
+pub use crate::timers::Timers;
 pub use crate::url::UrlM;
 
 mod assert;
+mod dns;
 mod fs;
 mod http;
 mod path;
+mod timers;
 mod url;
 
 pub struct Modules;
@@ -32,17 +36,21 @@ pub struct Modules;
 impl StandardModules for Modules {
 	fn init<'cx: 'o, 'o>(self, cx: &'cx Context, global: &mut Object<'o>) -> bool {
 		init_module::<Assert>(cx, global)
+			&& init_module::<Dns>(cx, global)
 			&& init_module::<FileSystem>(cx, global)
 			&& init_module::<Http>(cx, global)
 			&& init_module::<PathM>(cx, global)
+			&& init_module::<Timers>(cx, global)
 			&& init_module::<UrlM>(cx, global)
 	}
 
 	fn init_globals<'cx: 'o, 'o>(self, cx: &'cx Context, global: &mut Object<'o>) -> bool {
 		init_global_module::<Assert>(cx, global)
+			&& init_global_module::<Dns>(cx, global)
 			&& init_global_module::<FileSystem>(cx, global)
 			&& init_global_module::<Http>(cx, global)
 			&& init_global_module::<PathM>(cx, global)
+			&& init_global_module::<Timers>(cx, global)
 			&& init_global_module::<UrlM>(cx, global)
 	}
 }
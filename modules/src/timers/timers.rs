@@ -0,0 +1,211 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::Cell;
+use std::future::pending;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use futures::future::{Either, select};
+use mozjs::conversions::ConversionBehavior::Clamp;
+use mozjs::jsapi::{Heap, JSFunctionSpec, JSObject};
+use mozjs::jsval::{JSVal, UndefinedValue};
+use tokio::sync::{Notify, oneshot};
+
+pub use class::Interval;
+use ion::{ClassDefinition, Context, Error, Exception, Object, Result, ResultExc, Value};
+use ion::conversions::ToValue;
+use runtime::globals::abort::Signal;
+use runtime::globals::timers::schedule_signal;
+use runtime::modules::NativeModule;
+
+#[derive(Default, FromValue)]
+pub struct TimerOptions {
+	#[ion(default)]
+	signal: Signal,
+}
+
+/// Waits until the signal is aborted, and returns its reason.
+/// Never completes if the signal can no longer be aborted.
+async fn aborted(signal: Signal) -> JSVal {
+	match signal.aborted().await {
+		Some(reason) => reason,
+		None => pending().await,
+	}
+}
+
+/// State shared between an [Interval] and the macrotasks which tick it.
+pub struct IntervalState {
+	ticks: Cell<u32>,
+	notify: Notify,
+	terminate: Arc<AtomicBool>,
+	time: u64,
+}
+
+/// Schedules the next tick of an interval, which schedules the following tick when it runs.
+fn tick(state: Rc<IntervalState>) -> Result<()> {
+	let terminate = state.terminate.clone();
+	let time = state.time;
+	let callback = Box::new(move || {
+		state.ticks.set(state.ticks.get() + 1);
+		state.notify.notify_one();
+		let _ = tick(state);
+	});
+	schedule_signal(callback, terminate, time).map(|_| ())
+}
+
+pub struct IntervalResult {
+	value: JSVal,
+	done: bool,
+}
+
+impl<'cx> ToValue<'cx> for IntervalResult {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "value", &self.value);
+		object.set_as(cx, "done", &self.done);
+		object.to_value(cx, value);
+	}
+}
+
+#[js_class]
+mod class {
+	use std::rc::Rc;
+	use std::sync::atomic::Ordering;
+
+	use futures::future::select;
+	use mozjs::gc::Traceable;
+	use mozjs::jsapi::{Heap, JSObject, JSTracer};
+	use mozjs::jsval::{JSVal, UndefinedValue};
+
+	use ion::{Exception, Object, ResultExc};
+	use ion::symbol::WellKnownSymbolCode;
+	use runtime::globals::abort::Signal;
+
+	use crate::timers::{aborted, IntervalResult, IntervalState};
+
+	#[ion(no_constructor)]
+	pub struct Interval {
+		pub(crate) state: Rc<IntervalState>,
+		pub(crate) signal: Signal,
+		pub(crate) value: Box<Heap<JSVal>>,
+	}
+
+	impl Interval {
+		pub async fn next(&self) -> ResultExc<IntervalResult> {
+			loop {
+				if self.state.terminate.load(Ordering::SeqCst) {
+					return Ok(IntervalResult { value: UndefinedValue(), done: true });
+				}
+				if let Some(reason) = self.signal.reason() {
+					self.close();
+					return Err(Exception::Other(reason));
+				}
+
+				let ticks = self.state.ticks.get();
+				if ticks > 0 {
+					self.state.ticks.set(ticks - 1);
+					return Ok(IntervalResult { value: self.value.get(), done: false });
+				}
+
+				select(Box::pin(self.state.notify.notified()), Box::pin(aborted(self.signal.clone()))).await;
+			}
+		}
+
+		#[ion(name = "return")]
+		pub async fn finish(&self) -> IntervalResult {
+			self.close();
+			IntervalResult { value: UndefinedValue(), done: true }
+		}
+
+		#[ion(name = WellKnownSymbolCode::AsyncIterator)]
+		pub fn iterator(#[ion(this)] this: &Object) -> *mut JSObject {
+			this.handle().get()
+		}
+
+		pub(crate) fn close(&self) {
+			self.state.terminate.store(true, Ordering::SeqCst);
+			self.state.notify.notify_one();
+		}
+	}
+
+	impl Drop for Interval {
+		fn drop(&mut self) {
+			self.close();
+		}
+	}
+
+	unsafe impl Traceable for Interval {
+		#[inline]
+		unsafe fn trace(&self, trc: *mut JSTracer) {
+			unsafe { self.value.trace(trc) }
+		}
+	}
+}
+
+#[js_fn]
+async fn sleep(#[ion(convert = Clamp)] duration: Option<i32>, options: Option<TimerOptions>) -> ResultExc<()> {
+	let signal = options.unwrap_or_default().signal;
+	if let Some(reason) = signal.reason() {
+		return Err(Exception::Other(reason));
+	}
+
+	let (sender, receiver) = oneshot::channel();
+	let terminate = Arc::new(AtomicBool::new(false));
+	let callback = Box::new(move || {
+		let _ = sender.send(());
+	});
+	schedule_signal(callback, terminate.clone(), duration.unwrap_or(0).max(0) as u64)?;
+
+	match select(receiver, Box::pin(aborted(signal))).await {
+		Either::Left((Ok(_), _)) => Ok(()),
+		Either::Left((Err(_), _)) => Err(Error::new("Timer was cancelled", None).into()),
+		Either::Right((reason, _)) => {
+			terminate.store(true, Ordering::SeqCst);
+			Err(Exception::Other(reason))
+		}
+	}
+}
+
+#[js_fn]
+fn setInterval(
+	cx: &Context, #[ion(convert = Clamp)] duration: Option<i32>, value: Option<JSVal>, options: Option<TimerOptions>,
+) -> Result<*mut JSObject> {
+	let state = Rc::new(IntervalState {
+		ticks: Cell::new(0),
+		notify: Notify::new(),
+		terminate: Arc::new(AtomicBool::new(false)),
+		time: duration.unwrap_or(0).max(1) as u64,
+	});
+	tick(state.clone())?;
+
+	let interval = Interval {
+		state,
+		signal: options.unwrap_or_default().signal,
+		value: Heap::boxed(value.unwrap_or_else(UndefinedValue)),
+	};
+	Ok(Interval::new_object(cx, interval))
+}
+
+const FUNCTIONS: &[JSFunctionSpec] = &[function_spec!(sleep, 1), function_spec!(setInterval, 1), JSFunctionSpec::ZERO];
+
+#[derive(Default)]
+pub struct Timers;
+
+impl NativeModule for Timers {
+	const NAME: &'static str = "timers";
+	const SOURCE: &'static str = include_str!("timers.js");
+
+	fn module<'cx>(cx: &'cx Context) -> Option<Object<'cx>> {
+		let mut timers = Object::new(cx);
+		if unsafe { timers.define_methods(cx, FUNCTIONS) } {
+			Interval::init_class(cx, &mut timers);
+			return Some(timers);
+		}
+		None
+	}
+}
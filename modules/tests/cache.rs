@@ -0,0 +1,89 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+use std::env;
+use std::fs::{create_dir_all, remove_dir_all};
+use std::process;
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+mod common;
+
+const CACHE: (&str, &str) = ("cache", include_str!("scripts/cache/cache.js"));
+
+#[tokio::test]
+async fn cache() {
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let port = listener.local_addr().unwrap().port();
+	tokio::spawn(serve(listener));
+
+	// The cache is stored within the home directory, which is replaced so that the cache of the user is not modified.
+	let home = env::temp_dir().join(format!("spiderfire-cache-test-{}", process::id()));
+	create_dir_all(&home).unwrap();
+	env::set_var("HOME", &home);
+
+	common::run_scripts("cache", &[CACHE], &[("port", port)]).await;
+	let _ = remove_dir_all(&home);
+}
+
+/// Serves responses with caching headers for the first segment of the path.
+/// The body of each response is the number of requests made to its path, so that scripts can tell if a response came from the cache.
+async fn serve(listener: TcpListener) {
+	let mut counts = HashMap::new();
+	while let Ok((mut stream, _)) = listener.accept().await {
+		let (path, headers) = read_request(&mut stream).await;
+		let count = counts.entry(path.clone()).or_insert(0);
+		*count += 1;
+
+		let response = match path.split('/').nth(1).unwrap_or_default() {
+			"fresh" => response("200 OK", "Cache-Control: max-age=3600\r\nSet-Cookie: session=1\r\n", *count),
+			"validated" if headers.iter().any(|header| header == "if-none-match: \"v1\"") => {
+				String::from("HTTP/1.1 304 Not Modified\r\nETag: \"v1\"\r\nConnection: close\r\n\r\n")
+			}
+			"validated" => response("200 OK", "Cache-Control: no-cache\r\nETag: \"v1\"\r\n", *count),
+			"stale" => response(
+				"200 OK",
+				"Cache-Control: max-age=0\r\nLast-Modified: Mon, 01 Jan 2024 00:00:00 GMT\r\n",
+				*count,
+			),
+			"no-store" => response("200 OK", "Cache-Control: no-store, max-age=3600\r\n", *count),
+			"public" => response("200 OK", "Cache-Control: public, max-age=3600\r\n", *count),
+			_ => response("200 OK", "", *count),
+		};
+		let _ = stream.write_all(response.as_bytes()).await;
+	}
+}
+
+/// Reads the head of a request, returning its path and its lowercased header lines.
+async fn read_request(stream: &mut TcpStream) -> (String, Vec<String>) {
+	let mut reader = BufReader::new(stream);
+	let mut line = String::new();
+	reader.read_line(&mut line).await.unwrap();
+	let path = line.split(' ').nth(1).unwrap_or_default().to_string();
+
+	let mut headers = Vec::new();
+	loop {
+		line.clear();
+		if reader.read_line(&mut line).await.unwrap() == 0 || line.trim().is_empty() {
+			break;
+		}
+		headers.push(line.trim().to_ascii_lowercase());
+	}
+	(path, headers)
+}
+
+fn response(status: &str, headers: &str, count: u32) -> String {
+	let body = count.to_string();
+	format!(
+		"HTTP/1.1 {}\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+		status,
+		headers,
+		body.len(),
+		body
+	)
+}
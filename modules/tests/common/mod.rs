@@ -0,0 +1,73 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::JSEngine;
+use mozjs::rust::Runtime as RustRuntime;
+use tokio::task::LocalSet;
+
+use ion::{Context, Exception, Object};
+use ion::module::Module;
+use modules::Modules;
+use runtime::{Runtime, RuntimeBuilder};
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::Loader;
+
+/// Evaluates each script as a module in `tests/scripts/{directory}`, and checks that it completes without throwing.
+/// Each of the ports is defined as a global, so that scripts can connect to servers started by the test.
+pub async fn run_scripts(directory: &str, scripts: &[(&str, &str)], ports: &[(&str, u16)]) {
+	CONFIG.set(Config::default().log_level(LogLevel::Debug)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = RustRuntime::new(engine.handle());
+
+	let cx = &Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::new()
+		.modules(Loader::default())
+		.standard_modules(Modules)
+		.microtask_queue()
+		.macrotask_queue()
+		.build(cx);
+
+	let mut global = Object::global(cx);
+	for (name, port) in ports {
+		global.set_as(cx, *name, port);
+	}
+
+	LocalSet::new()
+		.run_until(async {
+			for script in scripts {
+				eval_module(&rt, cx, directory, *script).await;
+			}
+		})
+		.await;
+}
+
+async fn eval_module(rt: &Runtime<'_, '_>, cx: &Context<'_>, directory: &str, test: (&str, &str)) {
+	let (test, script) = test;
+	let filename = format!("{}.js", test);
+	let path = format!("./tests/scripts/{}/{}.js", directory, test);
+
+	let result = Module::compile(cx, &filename, Some(Path::new(&path)), script);
+	assert!(result.is_ok(), "Exception was thrown in: {}", filename);
+
+	let (_, promise) = result.unwrap();
+	let promise = promise.unwrap();
+
+	let result = rt.run_event_loop().await;
+	assert!(result.is_ok(), "{}: {:#?}", filename, result.unwrap_err());
+
+	match promise.state() {
+		PromiseState::Fulfilled => {}
+		PromiseState::Rejected => {
+			let exception = Exception::from_value(cx, &promise.result(cx));
+			panic!("{}: {:#?}", filename, exception);
+		}
+		PromiseState::Pending => panic!("{}: Module did not complete", filename),
+	}
+}
@@ -0,0 +1,15 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+const JAR: (&str, &str) = ("jar", include_str!("scripts/cookies/jar.js"));
+const CREDENTIALS: (&str, &str) = ("credentials", include_str!("scripts/cookies/credentials.js"));
+
+#[tokio::test]
+async fn cookies() {
+	common::run_scripts("cookies", &[JAR, CREDENTIALS], &[]).await;
+}
@@ -0,0 +1,137 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use hyper::{Body, Request, Response, Server};
+use hyper::header::HOST;
+use hyper::service::{make_service_fn, service_fn};
+use tokio::net::UdpSocket;
+
+mod common;
+
+const RESOLVER: (&str, &str) = ("resolver", include_str!("scripts/dns/resolver.js"));
+
+const A: u16 = 1;
+const MX: u16 = 15;
+const TXT: u16 = 16;
+const AAAA: u16 = 28;
+const SRV: u16 = 33;
+
+#[tokio::test]
+async fn dns() {
+	let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(host)) }));
+	let port = server.local_addr().port();
+	tokio::spawn(server);
+
+	let dns = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+	let dns_port = dns.local_addr().unwrap().port();
+	tokio::spawn(serve_dns(dns));
+
+	common::run_scripts("dns", &[RESOLVER], &[("port", port), ("dnsPort", dns_port)]).await;
+}
+
+/// Responds with the `Host` header of the request.
+async fn host(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+	let host = request.headers().get(HOST).map(|host| host.as_bytes().to_vec()).unwrap_or_default();
+	Ok(Response::new(Body::from(host)))
+}
+
+/// Name server which answers queries over UDP from a fixed set of records.
+async fn serve_dns(socket: UdpSocket) {
+	let mut buffer = [0; 512];
+	while let Ok((length, peer)) = socket.recv_from(&mut buffer).await {
+		if let Some(response) = answer(&buffer[..length]) {
+			let _ = socket.send_to(&response, peer).await;
+		}
+	}
+}
+
+/// Builds the response to a query with a single question. Unknown names are answered with `NXDOMAIN`.
+fn answer(query: &[u8]) -> Option<Vec<u8>> {
+	let mut end = 12;
+	let mut labels = Vec::new();
+	while *query.get(end)? != 0 {
+		let length = query[end] as usize;
+		labels.push(String::from_utf8_lossy(query.get(end + 1..end + 1 + length)?).to_ascii_lowercase());
+		end += length + 1;
+	}
+	let kind = u16::from_be_bytes([*query.get(end + 1)?, *query.get(end + 2)?]);
+	let question = query.get(12..end + 5)?;
+
+	let records = records(&labels.join("."), kind);
+	let rcode = if records.is_some() { 0 } else { 3 };
+	let records = records.unwrap_or_default();
+
+	let mut response = Vec::with_capacity(512);
+	response.extend_from_slice(&query[..2]);
+	response.extend_from_slice(&[0x84 | (query[2] & 0x01), 0x80 | rcode]);
+	response.extend_from_slice(&1u16.to_be_bytes());
+	response.extend_from_slice(&(records.len() as u16).to_be_bytes());
+	response.extend_from_slice(&[0, 0, 0, 0]);
+	response.extend_from_slice(question);
+	for (kind, data) in records {
+		// The name of each answer points to the name of the question.
+		response.extend_from_slice(&[0xC0, 0x0C]);
+		response.extend_from_slice(&kind.to_be_bytes());
+		response.extend_from_slice(&[0, 1, 0, 0, 0, 60]);
+		response.extend_from_slice(&(data.len() as u16).to_be_bytes());
+		response.extend_from_slice(&data);
+	}
+	Some(response)
+}
+
+/// Returns the records of a name with the type, or [None] if the name does not exist.
+fn records(name: &str, kind: u16) -> Option<Vec<(u16, Vec<u8>)>> {
+	let records = match (name, kind) {
+		("svc.test", A) => vec![(A, vec![127, 0, 0, 1])],
+		("dual.test", A) => vec![(A, vec![127, 0, 0, 2]), (A, vec![127, 0, 0, 3])],
+		("dual.test", AAAA) => {
+			let mut address = vec![0; 16];
+			address[15] = 1;
+			vec![(AAAA, address)]
+		}
+		("_http._tcp.svc.test", SRV) => vec![(SRV, srv(10, 60, 8080, "svc.test")), (SRV, srv(20, 0, 8081, "backup.svc.test"))],
+		("svc.test", MX) => {
+			let mut mx = 10u16.to_be_bytes().to_vec();
+			mx.extend(encode_name("mail.svc.test"));
+			vec![(MX, mx)]
+		}
+		("svc.test", TXT) => vec![(TXT, txt(&["v=spf1", "-all"])), (TXT, txt(&["hello"]))],
+		("svc.test" | "dual.test" | "_http._tcp.svc.test", _) => Vec::new(),
+		_ => return None,
+	};
+	Some(records)
+}
+
+fn encode_name(name: &str) -> Vec<u8> {
+	let mut encoded = Vec::new();
+	for label in name.split('.') {
+		encoded.push(label.len() as u8);
+		encoded.extend_from_slice(label.as_bytes());
+	}
+	encoded.push(0);
+	encoded
+}
+
+fn srv(priority: u16, weight: u16, port: u16, target: &str) -> Vec<u8> {
+	let mut srv = Vec::new();
+	srv.extend_from_slice(&priority.to_be_bytes());
+	srv.extend_from_slice(&weight.to_be_bytes());
+	srv.extend_from_slice(&port.to_be_bytes());
+	srv.extend(encode_name(target));
+	srv
+}
+
+fn txt(chunks: &[&str]) -> Vec<u8> {
+	let mut txt = Vec::new();
+	for chunk in chunks {
+		txt.push(chunk.len() as u8);
+		txt.extend_from_slice(chunk.as_bytes());
+	}
+	txt
+}
@@ -0,0 +1,15 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+const STREAM: (&str, &str) = ("stream", include_str!("scripts/event_source/stream.js"));
+const RECONNECT: (&str, &str) = ("reconnect", include_str!("scripts/event_source/reconnect.js"));
+
+#[tokio::test]
+async fn event_source() {
+	common::run_scripts("event_source", &[STREAM, RECONNECT], &[]).await;
+}
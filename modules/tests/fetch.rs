@@ -0,0 +1,18 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+const FORM_DATA: (&str, &str) = ("form-data", include_str!("scripts/fetch/form-data.js"));
+const COMPRESSION: (&str, &str) = ("compression", include_str!("scripts/fetch/compression.js"));
+const MOCK: (&str, &str) = ("mock", include_str!("scripts/fetch/mock.js"));
+const REDIRECT: (&str, &str) = ("redirect", include_str!("scripts/fetch/redirect.js"));
+const RETRY: (&str, &str) = ("retry", include_str!("scripts/fetch/retry.js"));
+
+#[tokio::test]
+async fn fetch() {
+	common::run_scripts("fetch", &[FORM_DATA, COMPRESSION, MOCK, REDIRECT, RETRY], &[]).await;
+}
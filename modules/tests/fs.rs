@@ -0,0 +1,14 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+const OPEN_FILE: (&str, &str) = ("open-file", include_str!("scripts/fs/open-file.js"));
+
+#[tokio::test]
+async fn fs() {
+	common::run_scripts("fs", &[OPEN_FILE], &[]).await;
+}
@@ -0,0 +1,30 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use hyper::{Body, Request, Response, Server};
+use hyper::service::{make_service_fn, service_fn};
+
+mod common;
+
+const VERSION: (&str, &str) = ("version", include_str!("scripts/http2/version.js"));
+
+#[tokio::test]
+async fn http2() {
+	// Connections which start with the HTTP/2 connection preface are served with HTTP/2, and others with HTTP/1.1.
+	let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(version)) }));
+	let port = server.local_addr().port();
+	tokio::spawn(server);
+
+	common::run_scripts("http2", &[VERSION], &[("port", port)]).await;
+}
+
+/// Responds with the HTTP version of the request.
+async fn version(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+	Ok(Response::new(Body::from(format!("{:?}", request.version()))))
+}
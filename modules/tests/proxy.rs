@@ -0,0 +1,52 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+use hyper::{Body, Method, Request, Response, Server, StatusCode};
+use hyper::header::PROXY_AUTHORIZATION;
+use hyper::service::{make_service_fn, service_fn};
+
+mod common;
+
+const PROXY: (&str, &str) = ("proxy", include_str!("scripts/proxy/proxy.js"));
+
+#[tokio::test]
+async fn proxy() {
+	let connect = Arc::new(Mutex::new(String::new()));
+	let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service_fn(move |_| {
+		let connect = Arc::clone(&connect);
+		async move { Ok::<_, Infallible>(service_fn(move |request| respond(request, Arc::clone(&connect)))) }
+	}));
+	let port = server.local_addr().port();
+	tokio::spawn(server);
+
+	common::run_scripts("proxy", &[PROXY], &[("port", port)]).await;
+}
+
+/// Stands in for a proxy, responding with the target and `Proxy-Authorization` of each request, on separate lines.
+/// `CONNECT` requests are refused, and their target and `Proxy-Authorization` are returned from `/connect` instead.
+async fn respond(request: Request<Body>, connect: Arc<Mutex<String>>) -> Result<Response<Body>, Infallible> {
+	let authorization = request
+		.headers()
+		.get(PROXY_AUTHORIZATION)
+		.and_then(|authorization| authorization.to_str().ok())
+		.unwrap_or_default();
+	let target = format!("{}\n{}", request.uri(), authorization);
+
+	if request.method() == Method::CONNECT {
+		*connect.lock().unwrap() = target;
+		let mut response = Response::new(Body::empty());
+		*response.status_mut() = StatusCode::PROXY_AUTHENTICATION_REQUIRED;
+		Ok(response)
+	} else if request.uri() == "/connect" {
+		Ok(Response::new(Body::from(connect.lock().unwrap().clone())))
+	} else {
+		Ok(Response::new(Body::from(target)))
+	}
+}
@@ -0,0 +1,77 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, fail} from "assert";
+import {Client, get} from "http";
+
+// Cookies are disabled, as responses to requests with cookies are not stored.
+const client = new Client({proxy: false, cookies: false});
+
+// Paths are unique to each run, so that responses stored by previous runs are not reused.
+const id = Date.now();
+
+async function load(path, cache = "default", headers = {}) {
+	const response = await get(`http://127.0.0.1:${port}/${path}/${id}`, {client, cache, headers});
+	return [response, await response.text()];
+}
+
+let [response, body] = await load("fresh");
+equals(body, "1", "Fresh response was fetched");
+equals(response.headers.getSetCookie().length, 1, "Set-Cookie was received from the network");
+
+[response, body] = await load("fresh");
+equals(body, "1", "Fresh response was served from the cache");
+equals(response.status, 200, "Cached response status");
+equals(response.headers.get("cache-control"), "max-age=3600", "Cached response headers");
+equals(response.headers.getSetCookie().length, 0, "Set-Cookie was not stored");
+
+[, body] = await load("fresh", "no-store");
+equals(body, "2", "no-store bypassed the cache");
+[, body] = await load("fresh");
+equals(body, "1", "no-store did not update the cache");
+
+[, body] = await load("fresh", "reload");
+equals(body, "3", "reload bypassed the cache");
+[, body] = await load("fresh");
+equals(body, "3", "reload updated the cache");
+[, body] = await load("fresh", "only-if-cached");
+equals(body, "3", "only-if-cached served the stored response");
+
+[, body] = await load("validated");
+equals(body, "1", "Validated response was fetched");
+[response, body] = await load("validated");
+equals(response.status, 200, "Not Modified was replaced by the stored response");
+equals(body, "1", "Stored response was revalidated");
+
+[, body] = await load("stale");
+equals(body, "1", "Stale response was fetched");
+[, body] = await load("stale", "force-cache");
+equals(body, "1", "force-cache served the stale response");
+[, body] = await load("stale");
+equals(body, "2", "Stale response was refetched");
+
+[, body] = await load("no-store");
+equals(body, "1", "Unstorable response was fetched");
+[, body] = await load("no-store");
+equals(body, "2", "Unstorable response was not stored");
+
+[, body] = await load("plain");
+[, body] = await load("plain");
+equals(body, "2", "Response without freshness or validators was not stored");
+
+[, body] = await load("fresh/authorization", "default", {Authorization: "Bearer token"});
+equals(body, "1", "Response to a request with Authorization was fetched");
+[, body] = await load("fresh/authorization", "default", {Authorization: "Bearer token"});
+equals(body, "2", "Response to a request with Authorization was not stored");
+[, body] = await load("fresh/cookie", "default", {Cookie: "session=1"});
+[, body] = await load("fresh/cookie");
+equals(body, "2", "Response to a request with Cookie was not stored");
+
+[, body] = await load("public", "default", {Authorization: "Bearer token"});
+[, body] = await load("public", "default", {Authorization: "Bearer token"});
+equals(body, "1", "Public response to a request with Authorization was stored");
+
+await load("missing", "only-if-cached").then(() => fail("only-if-cached fetched an uncached response"), () => {});
@@ -0,0 +1,30 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals} from "assert";
+import {Client, get, MockTransport} from "http";
+
+const client = new Client();
+const mock = new MockTransport();
+mock.on({url: "https://example.com/login"}, {headers: {"Set-Cookie": "session=abc; Path=/"}});
+client.setTransport(mock);
+
+await get("https://example.com/login", {client});
+equals(client.getCookies("https://example.com/"), "session=abc", "Cookie was stored from the response");
+
+await get("https://example.com/omitted", {client, credentials: "omit"}).catch(() => {});
+await get("https://example.com/included", {client, credentials: "include"}).catch(() => {});
+await get("https://example.com/added", {client, headers: {Cookie: "extra=1"}}).catch(() => {});
+
+const [omitted, included, added] = mock.unmatched;
+equals(omitted.headers.get("cookie"), null, "Cookies are not sent with credentials omitted");
+equals(included.headers.get("cookie"), "session=abc", "Cookies are sent with credentials included");
+equals(added.headers.get("cookie"), "extra=1; session=abc", "Cookies are appended to the Cookie header of the request");
+
+const disabled = new Client({cookies: false});
+disabled.setTransport(mock);
+await get("https://example.com/login", {client: disabled});
+equals(disabled.getCookies("https://example.com/"), null, "Cookies are not stored without a cookie jar");
@@ -0,0 +1,72 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals} from "assert";
+import {Client} from "http";
+
+const client = new Client();
+
+client.setCookie("https://example.com/", "host=only");
+equals(client.getCookies("https://example.com/"), "host=only", "Host-only cookie is sent to its host");
+equals(client.getCookies("https://www.example.com/"), null, "Host-only cookie is not sent to subdomains");
+client.clearCookies();
+
+client.setCookie("https://www.example.com/", "shared=1; Domain=.Example.com");
+equals(client.getCookies("https://example.com/"), "shared=1", "Domain cookie is sent to the domain");
+equals(client.getCookies("https://a.b.example.com/"), "shared=1", "Domain cookie is sent to subdomains");
+equals(client.getCookies("https://notexample.com/"), null, "Domain cookie is not sent to other domains ending with it");
+client.clearCookies();
+
+client.setCookie("https://www.example.com/", "other=1; Domain=example.org");
+equals(client.getCookies("https://example.org/"), null, "Cookie for a domain which does not match the host is rejected");
+client.setCookie("https://www.example.com/", "suffix=1; Domain=com");
+equals(client.getCookies("https://other.com/"), null, "Cookie for a public suffix is rejected");
+client.setCookie("https://user.github.io/", "suffix=1; Domain=github.io");
+equals(client.getCookies("https://other.github.io/"), null, "Cookie for a private public suffix is rejected");
+client.setCookie("https://127.0.0.1/", "ip=1; Domain=0.1");
+equals(client.getCookies("https://127.0.0.1/"), null, "Domain cookie for a partial IP address is rejected");
+
+client.setCookie("https://example.com/docs/page", "scoped=1");
+equals(client.getCookies("https://example.com/docs"), "scoped=1", "Default path is the directory of the URL");
+equals(client.getCookies("https://example.com/docs/other"), "scoped=1", "Cookie is sent to paths under its path");
+equals(client.getCookies("https://example.com/docsearch"), null, "Cookie is not sent to paths which only share a prefix");
+client.setCookie("https://example.com/", "root=1; Path=/");
+equals(client.getCookies("https://example.com/docs/page"), "scoped=1; root=1", "Cookies with longer paths are listed first");
+client.clearCookies();
+
+client.setCookie("http://example.com/", "secure=1; Secure");
+equals(client.getCookies("https://example.com/"), null, "Secure cookie cannot be set from an insecure URL");
+client.setCookie("https://example.com/", "secure=1; Secure");
+equals(client.getCookies("http://example.com/"), null, "Secure cookie is not sent to insecure URLs");
+equals(client.getCookies("https://example.com/"), "secure=1", "Secure cookie is sent to secure URLs");
+client.clearCookies();
+
+client.setCookie("https://example.com/", "__Secure-a=1");
+client.setCookie("https://example.com/", "__Host-b=1; Secure; Path=/; Domain=example.com");
+client.setCookie("https://example.com/", "__Host-c=1; Secure; Path=/docs");
+client.setCookie("https://example.com/", "__Host-Secure");
+equals(client.getCookies("https://example.com/docs"), null, "Cookies with invalid prefixes are rejected");
+client.setCookie("https://example.com/", "__Secure-a=1; Secure");
+client.setCookie("https://example.com/", "__Host-b=1; Secure; Path=/");
+equals(client.getCookies("https://example.com/"), "__Secure-a=1; __Host-b=1", "Cookies with valid prefixes are accepted");
+client.clearCookies();
+
+client.setCookie("https://example.com/", "nameless");
+client.setCookie("https://example.com/", "=empty");
+client.setCookie("https://example.com/", "=");
+equals(client.getCookies("https://example.com/"), "empty", "Cookies without a name are sent as their value");
+client.setCookie("https://example.com/", "replaced");
+equals(client.getCookies("https://example.com/"), "replaced", "Cookies without a name replace each other");
+client.clearCookies();
+
+client.setCookie("https://example.com/", "expired=1; Max-Age=60");
+client.setCookie("https://example.com/", "expired=1; Max-Age=0");
+client.setCookie("https://example.com/", "past=1; Expires=Thu, 01 Jan 1970 00:00:00 GMT");
+equals(client.getCookies("https://example.com/"), null, "Expired cookies are removed");
+
+const disabled = new Client({cookies: false});
+disabled.setCookie("https://example.com/", "a=1");
+equals(disabled.getCookies("https://example.com/"), null, "Cookies are not stored without a cookie jar");
@@ -0,0 +1,96 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, fail, ok} from "assert";
+import {Resolver} from "dns";
+import {Client, get, setResolver} from "http";
+
+const server = `127.0.0.1:${dnsPort}`;
+const resolver = new Resolver({servers: [server], timeout: 1000});
+
+async function rejection(promise, message) {
+	return promise.then(() => fail(`${message}: resolved`), error => error);
+}
+
+function sorted(array) {
+	return [...array].sort().join();
+}
+
+equals(sorted(await resolver.resolve("svc.test")), "127.0.0.1", "Names are resolved with the configured server");
+equals(sorted(await resolver.resolve("dual.test")), "127.0.0.2,127.0.0.3,::1", "IPv4 and IPv6 addresses are resolved");
+equals(sorted(await resolver.resolve("DUAL.test", {family: 4})), "127.0.0.2,127.0.0.3", "IPv4 addresses are resolved with family 4");
+equals(sorted(await resolver.resolve("dual.test", {family: 6})), "::1", "IPv6 addresses are resolved with family 6");
+equals(sorted(await resolver.resolve4("dual.test")), "127.0.0.2,127.0.0.3", "A records");
+equals(sorted(await resolver.resolve6("dual.test")), "::1", "AAAA records");
+
+let error = await rejection(resolver.resolve("missing.test"), "Resolving a name which does not exist");
+ok(error instanceof Error, "Names which do not exist reject");
+error = await rejection(resolver.resolve("svc.test", {family: 5}), "Resolving with an invalid family");
+ok(error instanceof TypeError, "Invalid families reject with a TypeError");
+
+const srv = (await resolver.resolveSrv("_http._tcp.svc.test")).sort((a, b) => a.priority - b.priority);
+equals(srv.length, 2, "SRV records");
+equals(srv[0].priority, 10, "SRV priority");
+equals(srv[0].weight, 60, "SRV weight");
+equals(srv[0].port, 8080, "SRV port");
+equals(srv[0].name, "svc.test", "SRV target has no trailing dot");
+equals(`${srv[1].name}:${srv[1].port}`, "backup.svc.test:8081", "Second SRV record");
+await rejection(resolver.resolveSrv("svc.test"), "Name without SRV records");
+
+const mx = await resolver.resolveMx("svc.test");
+equals(mx.length, 1, "MX records");
+equals(mx[0].priority, 10, "MX preference");
+equals(mx[0].exchange, "mail.svc.test", "MX exchange has no trailing dot");
+
+const txt = (await resolver.resolveTxt("svc.test")).map(chunks => chunks.join("|")).sort();
+equals(txt.join(), "hello,v=spf1|-all", "TXT records are returned as their chunks");
+await rejection(resolver.resolveTxt("missing.test"), "Name which does not exist");
+
+const overrides = new Resolver({
+	servers: [server],
+	hosts: {
+		"Override.test": "127.0.0.1",
+		"multi.test": ["127.0.0.1", "::1"],
+		"svc.test": "127.0.0.9",
+	},
+});
+equals(sorted(await overrides.resolve("override.TEST")), "127.0.0.1", "Overridden hosts are matched case-insensitively");
+equals(sorted(await overrides.resolve("multi.test", {family: 6})), "::1", "Overridden addresses are filtered by family");
+equals(sorted(await overrides.resolve("svc.test")), "127.0.0.9", "Overridden hosts are not queried");
+equals(sorted(await overrides.resolve4("svc.test")), "127.0.0.9", "Overridden hosts are not queried for A records");
+equals(sorted(await overrides.resolve("dual.test", {family: 4})), "127.0.0.2,127.0.0.3", "Other hosts are queried");
+
+let threw = null;
+try {
+	new Resolver({servers: ["not a server"]});
+} catch (error) {
+	threw = error;
+}
+ok(threw instanceof TypeError, "Invalid servers throw a TypeError");
+threw = null;
+try {
+	new Resolver({hosts: {"bad.test": "not an address"}});
+} catch (error) {
+	threw = error;
+}
+ok(threw instanceof TypeError, "Invalid overridden addresses throw a TypeError");
+
+const hosts = new Resolver({servers: [server], hosts: {"override.test": "127.0.0.1"}});
+const client = new Client({proxy: false, resolver: hosts});
+let response = await get(`http://override.test:${port}/`, {client});
+equals(await response.text(), `override.test:${port}`, "Client connects to the overridden address, keeping the host");
+response = await get(`http://svc.test:${port}/`, {client});
+equals(await response.text(), `svc.test:${port}`, "Client resolves other hosts with the servers of the resolver");
+
+const later = new Client({proxy: false});
+later.setResolver(hosts);
+response = await get(`http://override.test:${port}/`, {client: later});
+equals(await response.text(), `override.test:${port}`, "Resolver can be set after the client is created");
+
+setResolver(hosts);
+response = await fetch(`http://override.test:${port}/`);
+equals(await response.text(), `override.test:${port}`, "fetch uses the resolver of the global client");
+setResolver(null);
@@ -0,0 +1,43 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals} from "assert";
+import {MockTransport, setTransport} from "http";
+
+const mock = new MockTransport();
+mock.on({url: "http://events.test/resume", headers: {"Last-Event-ID": "2"}}, {
+	headers: {"Content-Type": "text/event-stream"},
+	body: "data: resumed\n\n",
+});
+mock.on({url: "http://events.test/resume"}, {
+	headers: {"Content-Type": "text/event-stream"},
+	body: "retry: 1\nid: 2\ndata: first\n\nid: 3\ndata: incomplete",
+});
+setTransport(mock);
+
+const source = new EventSource("http://events.test/resume");
+equals(source.withCredentials, false, "EventSource.withCredentials");
+
+const events = [];
+await new Promise((resolve, reject) => {
+	const timeout = setTimeout(() => reject(new Error("Timed out waiting for events")), 5000);
+
+	source.onerror = () => events.push(["error", source.readyState]);
+	source.onmessage = event => {
+		events.push([event.data, event.lastEventId]);
+		if (event.data === "resumed") {
+			source.close();
+			clearTimeout(timeout);
+			resolve();
+		}
+	};
+});
+
+equals(JSON.stringify(events), JSON.stringify([
+	["first", "2"],
+	["error", 0],
+	["resumed", "2"],
+]), "Events across reconnection");
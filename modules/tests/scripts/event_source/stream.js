@@ -0,0 +1,51 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "assert";
+import {MockTransport, setTransport} from "http";
+
+const mock = new MockTransport();
+mock.on({url: "http://events.test/stream"}, {
+	headers: {"Content-Type": "text/event-stream"},
+	body: "retry: 10\nid: 1\ndata: first\ndata: line\n\n: comment\nevent: update\ndata: second\r\n\r\nid\ndata: third\r\rid: 4\n\ndata: fourth\n\n",
+});
+setTransport(mock);
+
+const source = new EventSource("http://events.test/stream", {withCredentials: true});
+equals(source.url, "http://events.test/stream", "EventSource.url");
+equals(source.withCredentials, true, "EventSource.withCredentials");
+equals(source.readyState, 0, "EventSource.readyState before opening");
+
+const events = [];
+await new Promise((resolve, reject) => {
+	const timeout = setTimeout(() => reject(new Error("Timed out waiting for events")), 5000);
+
+	source.onopen = () => events.push(["open", source.readyState]);
+	source.onerror = () => {
+		clearTimeout(timeout);
+		reject(new Error("Received an error event"));
+	};
+	source.addEventListener("update", event => events.push([event.type, event.data, event.lastEventId]));
+	source.onmessage = event => {
+		equals(event.origin, "http://events.test", "MessageEvent.origin");
+		events.push([event.type, event.data, event.lastEventId]);
+		if (event.data === "fourth") {
+			source.close();
+			clearTimeout(timeout);
+			resolve();
+		}
+	};
+});
+
+equals(source.readyState, 2, "EventSource.readyState after closing");
+equals(JSON.stringify(events), JSON.stringify([
+	["open", 1],
+	["message", "first\nline", "1"],
+	["update", "second", "1"],
+	["message", "third", ""],
+	["message", "fourth", "4"],
+]), "Dispatched events");
+ok(mock.unmatched.length === 0, "Requests matched the stream");
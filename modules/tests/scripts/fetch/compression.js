@@ -0,0 +1,63 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, fail} from "assert";
+import {get, MockTransport, setTransport} from "http";
+
+const text = new TextEncoder().encode("hello world");
+
+const gzip = new Uint8Array([
+	31, 139, 8, 0, 0, 0, 0, 0, 2, 3, 203, 72, 205, 201, 201, 87, 40, 207, 47, 202, 73, 1, 0, 133, 17, 74, 13, 11, 0, 0, 0,
+]);
+const zlib = new Uint8Array([120, 156, 203, 72, 205, 201, 201, 87, 40, 207, 47, 202, 73, 1, 0, 26, 11, 4, 93]);
+const rawDeflate = new Uint8Array([203, 72, 205, 201, 201, 87, 40, 207, 47, 202, 73, 1, 0]);
+// An uncompressed meta-block containing the text, followed by an empty last meta-block.
+const brotli = new Uint8Array([0xA0, 0x00, 0x10, ...text, 0x03]);
+// The text compressed with deflate, and then with gzip.
+const stacked = new Uint8Array([
+	31, 139, 8, 0, 0, 0, 0, 0, 2, 3, 171, 152, 115, 218, 227, 236, 201, 147, 225, 26, 231, 245, 79, 121, 50, 50, 72, 113,
+	179, 196, 2, 0, 217, 8, 73, 165, 19, 0, 0, 0,
+]);
+
+const mock = new MockTransport();
+mock.on({url: "https://compressed.test/gzip"}, {headers: {"Content-Encoding": "gzip"}, body: gzip});
+mock.on({url: "https://compressed.test/x-gzip"}, {headers: {"Content-Encoding": "X-GZIP"}, body: gzip});
+mock.on({url: "https://compressed.test/zlib"}, {headers: {"Content-Encoding": "deflate"}, body: zlib});
+mock.on({url: "https://compressed.test/raw-deflate"}, {headers: {"Content-Encoding": "deflate"}, body: rawDeflate});
+mock.on({url: "https://compressed.test/br"}, {headers: {"Content-Encoding": "br"}, body: brotli});
+mock.on({url: "https://compressed.test/stacked"}, {headers: {"Content-Encoding": "deflate, identity, gzip"}, body: stacked});
+mock.on({url: "https://compressed.test/unknown"}, {headers: {"Content-Encoding": "zstd"}, body: gzip});
+mock.on({url: "https://compressed.test/truncated"}, {headers: {"Content-Encoding": "gzip"}, body: gzip.slice(0, 20)});
+mock.on({url: "https://compressed.test/empty"}, {headers: {"Content-Encoding": "gzip"}});
+setTransport(mock);
+
+for (const coding of ["gzip", "x-gzip", "zlib", "raw-deflate", "br", "stacked"]) {
+	const response = await fetch(`https://compressed.test/${coding}`);
+	equals(await response.text(), "hello world", `Decoded ${coding} body`);
+}
+
+let response = await fetch("https://compressed.test/gzip", {decompress: false});
+equals(response.headers.get("Content-Encoding"), "gzip", "Content-Encoding is kept");
+const raw = new Uint8Array(await response.arrayBuffer());
+equals(raw.length, gzip.length, "Raw body is not decoded with decompress disabled");
+equals(raw.every((byte, i) => byte === gzip[i]), true, "Raw body bytes");
+
+response = await fetch("https://compressed.test/unknown");
+equals((await response.arrayBuffer()).byteLength, gzip.length, "Body with an unsupported coding is left encoded");
+
+response = await fetch("https://compressed.test/empty");
+equals(await response.text(), "", "Empty body with a coding decodes to nothing");
+
+response = await fetch("https://compressed.test/truncated");
+await response.text().then(() => fail("Truncated body was decoded"), () => {});
+
+await fetch("https://compressed.test/accept").catch(() => {});
+await get("https://compressed.test/accept", {headers: {"Accept-Encoding": "identity"}}).catch(() => {});
+await fetch("https://compressed.test/accept", {decompress: false}).catch(() => {});
+const [added, kept, omitted] = mock.unmatched;
+equals(added.headers.get("Accept-Encoding"), "gzip, deflate, br", "Accept-Encoding is sent by default");
+equals(kept.headers.get("Accept-Encoding"), "identity", "Accept-Encoding of the request is kept");
+equals(omitted.headers.get("Accept-Encoding"), null, "Accept-Encoding is not sent with decompress disabled");
@@ -0,0 +1,36 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, fail, ok} from "assert";
+import {MockTransport, setTransport} from "http";
+
+const form = new FormData();
+form.append("name", "value\nwith lines");
+form.append("quoted\"name", "quoted");
+form.append("file", new File(["file contents"], "file.txt", {type: "text/plain"}));
+
+const mock = new MockTransport();
+mock.on({url: "https://form.test/multipart"}, {body: form});
+mock.on({url: "https://form.test/urlencoded"}, {body: new URLSearchParams({a: "1", b: "x y"})});
+mock.on({url: "https://form.test/text"}, {body: "text"});
+setTransport(mock);
+
+const multipart = await (await fetch("https://form.test/multipart")).formData();
+equals(multipart.get("name"), "value\r\nwith lines", "Decoded string entry");
+equals(multipart.get("quoted\"name"), "quoted", "Decoded entry with an escaped name");
+
+const file = multipart.get("file");
+ok(file instanceof File, "Decoded file entry");
+equals(file.name, "file.txt", "Decoded File.name");
+equals(file.type, "text/plain", "Decoded File.type");
+equals(await file.text(), "file contents", "Decoded File contents");
+
+const urlencoded = await (await fetch("https://form.test/urlencoded")).formData();
+equals(urlencoded.get("a"), "1", "Decoded urlencoded entry");
+equals(urlencoded.get("b"), "x y", "Decoded urlencoded entry with a space");
+
+await (await fetch("https://form.test/text")).formData().then(() => fail("Decoding text as form data"), () => {});
+setTransport(null);
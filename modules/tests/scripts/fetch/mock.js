@@ -0,0 +1,86 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, fail, ok} from "assert";
+import {Client, get, MockTransport, post, request} from "http";
+
+const mock = new MockTransport();
+mock.on({url: "https://api.test/users/me"}, {body: "me"});
+mock.on({url: "https://api.test/users/*"}, {status: 201, headers: {"X-Route": "users"}, body: "user"});
+mock.on({url: "https://*.test/wild/*/end"}, {body: "wildcard"});
+mock.on({method: "post", url: "https://api.test/items"}, {body: "created"});
+mock.on({url: "https://api.test/auth", headers: {Authorization: "Bearer token"}}, {body: "authorised"});
+mock.on({url: "https://api.test/json"}, {headers: {"Content-Type": "application/json"}, body: "{\"a\": 1}"});
+mock.on({url: "https://api.test/blob"}, {body: new Blob(["blob"], {type: "text/custom"})});
+mock.on({url: "https://api.test/empty"}, {status: 204});
+
+const client = new Client();
+client.setTransport(mock);
+
+async function text(url, init = {}) {
+	const response = await get(url, {client, ...init});
+	return response.text();
+}
+
+async function unmatched(promise, message) {
+	const error = await promise.then(() => fail(`${message}: matched a route`), error => error);
+	equals(error instanceof TypeError, true, `${message}: rejects with a TypeError`);
+	return error;
+}
+
+equals(await text("https://api.test/users/me"), "me", "Routes are matched in registration order");
+let response = await get("https://api.test/users/1/posts?page=2", {client});
+equals(response.status, 201, "Route status");
+equals(response.headers.get("X-Route"), "users", "Route headers");
+equals(await response.text(), "user", "Wildcard matches any sequence of characters, including slashes");
+
+equals(await text("https://www.example.test/wild/a/b/end"), "wildcard", "Multiple wildcards match");
+equals(await text("https://api.test/users/me#fragment"), "me", "Fragments are removed before matching");
+
+equals(await (await post("https://api.test/items", {client})).text(), "created", "Method is matched case-insensitively");
+await unmatched(get("https://api.test/items", {client}), "Route with another method");
+await unmatched(request("https://api.test/items", "PUT", {client}), "Route with another method");
+
+equals(await text("https://api.test/auth", {headers: {Authorization: "Bearer token"}}), "authorised", "Route headers are matched");
+await unmatched(get("https://api.test/auth", {client}), "Route with a missing header");
+await unmatched(get("https://api.test/auth", {client, headers: {Authorization: "Bearer other"}}), "Route with another header value");
+
+equals((await (await get("https://api.test/json", {client})).json()).a, 1, "JSON body");
+response = await get("https://api.test/blob", {client});
+equals(response.headers.get("Content-Type"), "text/custom", "Content-Type is implied by a Blob body");
+equals(response.headers.get("Content-Length"), "4", "Content-Length is implied by a Blob body");
+response = await get("https://api.test/empty", {client});
+equals(response.status, 204, "Route without a body");
+equals(await response.text(), "", "Empty body");
+
+mock.clear();
+mock.on({url: "https://api.test/*"}, {body: "cleared"});
+equals(mock.unmatched.length, 0, "clear() removes recorded requests");
+
+const error = await unmatched(get("https://other.test/path#fragment", {client, headers: {"X-Test": "1"}}), "Unmatched request");
+equals(error.message, "No mock matches GET https://other.test/path", "Unmatched request error message");
+await unmatched(request("https://other.test/delete", "DELETE", {client}), "Unmatched request");
+
+const [first, second] = mock.unmatched;
+equals(first.method, "GET", "Recorded method");
+equals(first.url, "https://other.test/path", "Recorded URL");
+equals(first.headers.get("X-Test"), "1", "Recorded headers");
+equals(second.method, "DELETE", "Recorded requests are in order");
+
+let threw = false;
+try {
+	first.headers.set("X-Test", "2");
+} catch (error) {
+	threw = error instanceof TypeError;
+}
+equals(threw, true, "Recorded headers are immutable");
+
+equals(await text("https://api.test/anything"), "cleared", "Routes registered after clear() are matched");
+
+const network = new Client({proxy: "http://127.0.0.1:1"});
+const refused = await get("https://api.test/anything", {client: network}).then(() => fail("Refused connection succeeded"), error => error);
+ok(!refused.message.startsWith("No mock matches"), "Clients without the transport use the network");
+equals(mock.unmatched.length, 2, "Requests of other clients are not recorded");
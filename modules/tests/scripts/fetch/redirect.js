@@ -0,0 +1,127 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, fail, ok} from "assert";
+import {Client, get, MockTransport, post} from "http";
+
+const mock = new MockTransport();
+mock.on({url: "https://a.test/chain/3"}, {status: 302, headers: {Location: "/chain/2"}});
+mock.on({url: "https://a.test/chain/2"}, {status: 301, headers: {Location: "https://a.test/chain/1"}});
+mock.on({url: "https://a.test/chain/1"}, {status: 307, headers: {Location: "/final"}});
+mock.on({url: "https://a.test/final"}, {body: "final"});
+mock.on({url: "https://a.test/same"}, {status: 302, headers: {Location: "/echo"}});
+mock.on({url: "https://a.test/cross"}, {status: 302, headers: {Location: "https://b.test/echo"}});
+mock.on({url: "https://a.test/see-other"}, {status: 303, headers: {Location: "/final"}});
+mock.on({url: "https://a.test/no-location"}, {status: 302, body: "no location"});
+
+const client = new Client();
+client.setTransport(mock);
+
+async function rejection(promise, message) {
+	return promise.then(() => fail(`${message}: resolved`), error => error);
+}
+
+function recorded() {
+	const requests = mock.unmatched;
+	return requests[requests.length - 1];
+}
+
+let response = await get("https://a.test/chain/3", {client});
+equals(await response.text(), "final", "Redirects are followed");
+equals(response.redirected, true, "Redirected response");
+equals(response.url, "https://a.test/final", "URL of the final response");
+equals(response.locations.join(), "https://a.test/chain/3,https://a.test/chain/2,https://a.test/chain/1,https://a.test/final", "Locations of the redirect chain");
+
+response = await get("https://a.test/chain/3", {client, maxRedirects: 3});
+equals(response.status, 200, "Redirect chain as long as maxRedirects is followed");
+let error = await rejection(get("https://a.test/chain/3", {client, maxRedirects: 2}), "Redirect chain longer than maxRedirects");
+equals(error.message, "Too Many Redirects", "Too many redirects error message");
+
+const limited = new Client({maxRedirects: 1});
+limited.setTransport(mock);
+error = await rejection(get("https://a.test/chain/3", {client: limited}), "Redirect chain longer than the maxRedirects of the client");
+equals(error.message, "Too Many Redirects", "maxRedirects of the client is used");
+response = await get("https://a.test/chain/3", {client: limited, maxRedirects: 5});
+equals(response.status, 200, "maxRedirects of the request overrides the client");
+
+response = await get("https://a.test/chain/3", {client, redirect: "manual"});
+equals(response.status, 302, "Manual redirects return the redirect");
+equals(response.headers.get("Location"), "/chain/2", "Manual redirect keeps its Location");
+equals(response.redirected, false, "Manual redirect is not redirected");
+error = await rejection(get("https://a.test/chain/3", {client, redirect: "error"}), "Redirect with the error mode");
+equals(error.message, "Received Redirection", "Error redirect mode rejects");
+
+response = await get("https://a.test/no-location", {client});
+equals(response.status, 302, "Redirect without a Location is returned");
+equals(await response.text(), "no location", "Redirect without a Location keeps its body");
+
+response = await post("https://a.test/see-other", {client});
+equals(await response.text(), "final", "303 redirects a POST to a GET");
+
+const calls = [];
+response = await get("https://a.test/chain/3", {
+	client,
+	onRedirect(redirect, url) {
+		calls.push([redirect.status, redirect.headers.get("Location"), url]);
+	},
+});
+equals(response.status, 200, "Hook returning undefined follows redirects");
+equals(calls.length, 3, "Hook is called for each redirect");
+equals(calls[0].join(), "302,/chain/2,https://a.test/chain/2", "Hook receives the redirect and the resolved URL");
+equals(calls[2].join(), "307,/final,https://a.test/final", "Hook receives later redirects");
+
+response = await get("https://a.test/chain/3", {client, onRedirect: () => false});
+equals(response.status, 302, "Hook returning false stops the redirect");
+equals(response.headers.get("Location"), "/chain/2", "Stopped redirect is the response");
+
+response = await get("https://a.test/chain/3", {client, onRedirect: (redirect, url) => Promise.resolve(!url.endsWith("/chain/1"))});
+equals(response.status, 301, "Hook may return a promise");
+equals(response.url, "https://a.test/chain/2", "Redirects before the stopped redirect are followed");
+
+error = await rejection(get("https://a.test/chain/3", {client, onRedirect: () => Promise.reject(new RangeError("hook"))}), "Hook rejecting");
+ok(error instanceof RangeError, "Rejection of the hook rejects the request");
+error = await rejection(get("https://a.test/chain/3", {
+	client,
+	onRedirect() {
+		throw new SyntaxError("hook");
+	},
+}), "Hook throwing");
+ok(error instanceof SyntaxError, "Exception of the hook rejects the request");
+
+const credentials = {
+	Authorization: "Bearer token",
+	Cookie: "a=1",
+	"Proxy-Authorization": "Basic abc",
+	"X-Custom": "kept",
+	"X-Replaced": "before",
+};
+const hook = () => ({headers: {"X-Replaced": "after", "X-Added": "added", Authorization: "Bearer hook"}});
+
+await rejection(get("https://a.test/same", {client, headers: credentials, onRedirect: hook}), "Same-origin redirect to an unmatched route");
+let headers = recorded().headers;
+equals(recorded().url, "https://a.test/echo", "Same-origin redirect is followed");
+equals(headers.get("Authorization"), "Bearer hook", "Hook headers replace existing headers");
+equals(headers.get("X-Replaced"), "after", "Hook headers replace values");
+equals(headers.get("X-Added"), "added", "Hook headers are added");
+equals(headers.get("X-Custom"), "kept", "Other headers are kept");
+equals(headers.get("Cookie"), "a=1", "Cookie is kept on same-origin redirects");
+equals(headers.get("Proxy-Authorization"), "Basic abc", "Proxy-Authorization is kept on same-origin redirects");
+
+await rejection(get("https://a.test/cross", {client, headers: credentials, onRedirect: hook}), "Cross-origin redirect to an unmatched route");
+headers = recorded().headers;
+equals(recorded().url, "https://b.test/echo", "Cross-origin redirect is followed");
+ok(!headers.has("Authorization"), "Authorization from the hook is removed on cross-origin redirects");
+ok(!headers.has("Cookie"), "Cookie is removed on cross-origin redirects");
+ok(!headers.has("Proxy-Authorization"), "Proxy-Authorization is removed on cross-origin redirects");
+equals(headers.get("X-Added"), "added", "Other hook headers are kept on cross-origin redirects");
+equals(headers.get("Host"), "b.test", "Host is updated on redirects");
+
+const hooked = new Client({onRedirect: () => false});
+hooked.setTransport(mock);
+response = await get("https://a.test/chain/3", {client: hooked});
+equals(response.status, 302, "Hook of the client is used");
+response = await get("https://a.test/chain/3", {client: hooked, onRedirect: () => true});
+equals(response.status, 200, "Hook of the request overrides the client");
@@ -0,0 +1,108 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, fail, ok} from "assert";
+import {Client, get, MockTransport, post, request} from "http";
+
+const mock = new MockTransport();
+mock.on({url: "https://retry.test/unavailable"}, {status: 503, body: "unavailable"});
+mock.on({url: "https://retry.test/teapot"}, {status: 418});
+mock.on({url: "https://retry.test/soon"}, {status: 429, headers: {"Retry-After": "0"}});
+mock.on({url: "https://retry.test/later"}, {status: 503, headers: {"Retry-After": "60"}});
+mock.on({url: "https://retry.test/ok"}, {body: "ok"});
+
+function retrying(retry) {
+	const client = new Client({retry});
+	client.setTransport(mock);
+	return client;
+}
+
+async function requests(client, send) {
+	const before = client.stats.requests;
+	const response = await send();
+	return [response, client.stats.requests - before];
+}
+
+const fast = {backoff: 1, jitter: false};
+
+let client = retrying({...fast, attempts: 4});
+let [response, count] = await requests(client, () => get("https://retry.test/unavailable", {client}));
+equals(count, 4, "Retryable status is retried until the attempts run out");
+equals(response.status, 503, "Last response is returned when the attempts run out");
+equals(await response.text(), "unavailable", "Body of the last response");
+
+[response, count] = await requests(client, () => get("https://retry.test/ok", {client}));
+equals(count, 1, "Successful response is not retried");
+[response, count] = await requests(client, () => get("https://retry.test/teapot", {client}));
+equals(count, 1, "Other statuses are not retried");
+equals(response.status, 418, "Response with a status which is not retried");
+
+client = retrying({...fast, statuses: [418]});
+[, count] = await requests(client, () => get("https://retry.test/teapot", {client}));
+equals(count, 3, "Retried statuses are configurable, with 3 attempts by default");
+[, count] = await requests(client, () => get("https://retry.test/unavailable", {client}));
+equals(count, 1, "Default statuses are replaced");
+
+client = retrying({...fast, attempts: 1});
+[, count] = await requests(client, () => get("https://retry.test/unavailable", {client}));
+equals(count, 1, "Single attempt is not retried");
+
+client = retrying({backoff: 60000, maxBackoff: 1, attempts: 3});
+const start = Date.now();
+[, count] = await requests(client, () => get("https://retry.test/unavailable", {client}));
+equals(count, 3, "Backoff is limited by maxBackoff");
+ok(Date.now() - start < 10000, "Backoff does not exceed maxBackoff");
+
+client = retrying({...fast, maxBackoff: 1000});
+[, count] = await requests(client, () => get("https://retry.test/soon", {client}));
+equals(count, 3, "Retry-After within maxBackoff is retried");
+[response, count] = await requests(client, () => get("https://retry.test/later", {client}));
+equals(count, 1, "Retry-After longer than maxBackoff stops retrying");
+equals(response.status, 503, "Response asking for a longer delay is returned");
+
+client = retrying({...fast, maxBackoff: 1000, retryAfter: false});
+[, count] = await requests(client, () => get("https://retry.test/later", {client}));
+equals(count, 3, "Retry-After is ignored when retryAfter is false");
+
+client = retrying({...fast});
+[, count] = await requests(client, () => post("https://retry.test/unavailable", {client}));
+equals(count, 1, "POST is not retried");
+[, count] = await requests(client, () => request("https://retry.test/unavailable", "PATCH", {client}));
+equals(count, 1, "PATCH is not retried");
+[, count] = await requests(client, () => request("https://retry.test/unavailable", "PUT", {client}));
+equals(count, 3, "PUT is idempotent");
+[, count] = await requests(client, () => request("https://retry.test/unavailable", "DELETE", {client}));
+equals(count, 3, "DELETE is idempotent");
+
+client = retrying({...fast, retryNonIdempotent: true});
+[, count] = await requests(client, () => post("https://retry.test/unavailable", {client}));
+equals(count, 3, "POST is retried with retryNonIdempotent");
+
+client = retrying({...fast});
+let before = client.stats.requests;
+let error = await get("https://retry.test/unmatched", {client}).then(() => fail("Unmatched request succeeded"), error => error);
+ok(error instanceof TypeError, "Unmatched request rejects");
+equals(client.stats.requests - before, 1, "Errors which are not transient are not retried");
+
+const refused = new Client({proxy: false, retry: {...fast, attempts: 3}});
+before = refused.stats.requests;
+error = await get("http://127.0.0.1:1/", {client: refused}).then(() => fail("Refused connection succeeded"), error => error);
+ok(error instanceof Error, "Refused connection rejects after the last attempt");
+equals(refused.stats.requests - before, 3, "Refused connections are retried");
+
+before = refused.stats.requests;
+await post("http://127.0.0.1:1/", {client: refused}).then(() => fail("Refused connection succeeded"), error => error);
+equals(refused.stats.requests - before, 1, "Refused POST is not retried");
+
+client = retrying({backoff: 60000, maxBackoff: 60000, jitter: false});
+const controller = new AbortController();
+setTimeout(() => controller.abort(), 50);
+const aborted = Date.now();
+await get("https://retry.test/unavailable", {client, signal: controller.signal}).then(
+	() => {},
+	() => {},
+);
+ok(Date.now() - aborted < 10000, "Aborting the request stops waiting to retry");
@@ -0,0 +1,27 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, fail, ok} from "assert";
+import {openFile, removeFile, write} from "fs";
+
+const path = "./tests/scripts/fs/open-file.txt";
+ok(await write(path, "Lazily Read File"), "Writing the file");
+
+try {
+	const file = await openFile(path, {type: "text/plain"});
+	equals(file.name, "open-file.txt", "File.name of an opened file");
+	equals(file.type, "text/plain", "File.type of an opened file");
+	equals(file.size, 16, "File.size of an opened file");
+	equals(await file.slice(7, 11).text(), "Read", "File.slice of an opened file");
+	equals(file.slice(11, 7).size, 0, "File.slice of an opened file with an end before the start");
+	equals(await file.slice(11, 7).text(), "", "Reading a reversed slice of an opened file");
+	equals(await new Blob(["A ", file.slice(7)]).text(), "A Read File", "Blob containing part of an opened file");
+	equals(await file.text(), "Lazily Read File", "File.text of an opened file");
+} finally {
+	await removeFile(path);
+}
+
+await openFile("./tests/scripts/fs/missing.txt").then(() => fail("Opening a missing file"), () => {});
@@ -0,0 +1,24 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals} from "assert";
+import {Client, get} from "http";
+
+const url = `http://127.0.0.1:${port}/`;
+
+async function version(options) {
+	const response = await get(url, {client: new Client({proxy: false, ...options})});
+	equals(await response.text(), response.version, "Response version matches the version received by the server");
+	return response.version;
+}
+
+equals(await version({}), "HTTP/1.1", "HTTP/1.1 is used without TLS by default");
+equals(await version({http2: false}), "HTTP/1.1", "HTTP/1.1 is used with HTTP/2 disabled");
+equals(await version({http2PriorKnowledge: true}), "HTTP/2.0", "HTTP/2 is used with prior knowledge");
+equals(await version({http2: false, http2PriorKnowledge: true}), "HTTP/2.0", "Prior knowledge enables HTTP/2");
+equals(await version({keepAlive: true, http2PriorKnowledge: true}), "HTTP/2.0", "HTTP/2 is used with pooled connections");
+
+equals((await fetch("data:,")).version, "HTTP/1.1", "Local responses have the default version");
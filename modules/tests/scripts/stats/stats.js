@@ -0,0 +1,74 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "assert";
+import {Client, get, MockTransport, post} from "http";
+
+const host = `127.0.0.1:${port}`;
+const url = `http://${host}/`;
+
+function number(value, message) {
+	ok(typeof value === "number" && value >= 0, `${message}: expected a non-negative number, received ${value}`);
+}
+
+const client = new Client({proxy: false, keepAlive: true});
+let stats = client.stats;
+equals(stats.requests, 0, "New client has issued no requests");
+equals(stats.bytesSent, 0, "New client has sent no bytes");
+equals(stats.bytesReceived, 0, "New client has received no bytes");
+equals(Object.keys(stats.hosts).length, 0, "New client has no connections");
+
+let response = await post(url, {client, body: "hello"});
+stats = client.stats;
+equals(stats.requests, 1, "Requests are counted");
+ok(stats.bytesSent > 5, "Bytes sent include the request head and body");
+equals(stats.hosts[host].active, 1, "Connection is active until the body is read");
+equals(stats.hosts[host].idle, 0, "Active connection is not idle");
+
+let timing = response.timing;
+equals(timing.reused, false, "First request opens a new connection");
+number(timing.dns, "DNS duration");
+number(timing.connect, "Connect duration");
+equals(timing.tls, 0, "Connections without TLS have no TLS duration");
+number(timing.ttfb, "Time to first byte");
+
+const snapshot = client.stats;
+equals(await response.text(), "hello", "Response body");
+stats = client.stats;
+ok(stats.bytesReceived > 5, "Bytes received include the response head and body");
+equals(stats.hosts[host].active, 0, "Connection is no longer active after the body is read");
+equals(stats.hosts[host].idle, 1, "Kept alive connection is idle after the body is read");
+equals(snapshot.hosts[host].active, 1, "Statistics are a snapshot");
+
+const sent = stats.bytesSent;
+response = await get(`${url}slow`, {client});
+timing = response.timing;
+equals(timing.reused, true, "Second request reuses the idle connection");
+equals(timing.dns, 0, "Reused connection has no DNS duration");
+equals(timing.connect, 0, "Reused connection has no connect duration");
+ok(timing.ttfb >= 90, `Time to first byte includes the delay of the server, received ${timing.ttfb}`);
+await response.text();
+stats = client.stats;
+equals(stats.requests, 2, "Requests over reused connections are counted");
+ok(stats.bytesSent > sent, "Bytes sent grow with each request");
+equals(stats.hosts[host].idle, 1, "Reused connection is idle again");
+
+equals(new Client().stats.requests, 0, "Statistics are separate for each client");
+
+const mock = new MockTransport();
+mock.on({url: "https://mock.test/*"}, {body: "mocked"});
+const mocked = new Client();
+mocked.setTransport(mock);
+response = await get("https://mock.test/path", {client: mocked});
+timing = response.timing;
+equals(timing.reused, false, "Mocked response is not over a reused connection");
+equals(timing.connect, 0, "Mocked response has no connect duration");
+number(timing.ttfb, "Mocked time to first byte");
+await response.text();
+equals(mocked.stats.requests, 1, "Mocked requests are counted");
+equals(mocked.stats.bytesSent, 0, "Mocked requests send no bytes over connections");
+
+equals((await fetch("data:,")).timing, null, "Local responses have no timing");
@@ -0,0 +1,38 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, fail} from "assert";
+import {Resolver} from "dns";
+import {Client, get} from "http";
+
+async function text(path, client = {}, init = {}) {
+	const response = await get(`http://127.0.0.1:${port}${path}`, {client: new Client({proxy: false, ...client}), ...init});
+	return response.text();
+}
+
+async function timeout(promise, expected, message) {
+	const error = await promise.then(() => fail(`${message}: did not time out`), error => error);
+	equals(error.message, expected, message);
+}
+
+equals(await text("/", {connectTimeout: 1000, readTimeout: 1000, timeout: 1000}), "ok", "Response within the timeouts");
+
+await timeout(text("/stall-headers", {timeout: 100}), "Request Timeout: 100ms", "Total timeout while waiting for headers");
+await timeout(text("/stall-body", {readTimeout: 100}), "Read Timeout: 100ms", "Read timeout while reading the body");
+await timeout(text("/stall-body", {timeout: 100}), "Request Timeout: 100ms", "Total timeout while reading the body");
+
+equals(await text("/slow-body", {readTimeout: 200}), "abcdef", "Read timeout applies to each chunk");
+await timeout(text("/slow-body", {readTimeout: 200, timeout: 150}), "Request Timeout: 150ms", "Total timeout applies to the whole body");
+
+await timeout(text("/stall-headers", {timeout: 5000}, {timeout: 100}), "Request Timeout: 100ms", "Request timeout overrides the client");
+await timeout(text("/stall-body", {readTimeout: 100}, {timeout: 5000}), "Read Timeout: 100ms", "Client timeout is used when the request does not set it");
+await timeout(text("/stall-body", {}, {readTimeout: 100}), "Read Timeout: 100ms", "Request timeout is used without a client timeout");
+
+const resolver = new Resolver({servers: [`127.0.0.1:${dnsPort}`]});
+const stalled = get(`http://stalled.test:${port}/`, {client: new Client({proxy: false, resolver, connectTimeout: 100})});
+await timeout(stalled, "Connect Timeout: 100ms", "Connect timeout while connecting");
+const overridden = get(`http://stalled.test:${port}/`, {client: new Client({proxy: false, resolver, connectTimeout: 5000}), connectTimeout: 100});
+await timeout(overridden, "Connect Timeout: 100ms", "Request connect timeout overrides the client");
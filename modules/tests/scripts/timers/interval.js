@@ -0,0 +1,31 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, fail} from "assert";
+import {setInterval} from "timers";
+
+let ticks = 0;
+for await (const value of setInterval(5, "value")) {
+	equals(value, "value", "Values of setInterval");
+	if (++ticks === 3) {
+		break;
+	}
+}
+equals(ticks, 3, "Ticks of setInterval");
+
+const interval = setInterval(5);
+await interval.next();
+equals((await interval.return()).done, true, "setInterval after return");
+equals((await interval.next()).done, true, "setInterval after it has returned");
+
+const controller = new AbortController();
+const aborted = setInterval(5, null, {signal: controller.signal});
+await aborted.next();
+controller.abort("stopped");
+await aborted.next().then(
+	() => fail("setInterval which was aborted"),
+	reason => equals(reason, "stopped", "setInterval rejects with the reason of its signal"),
+);
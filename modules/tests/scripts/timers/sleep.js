@@ -0,0 +1,34 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, fail, ok} from "assert";
+import {sleep} from "timers";
+
+const start = Date.now();
+await sleep(20);
+ok(Date.now() - start >= 15, "sleep waits for the duration");
+
+await sleep(10, {signal: AbortSignal.abort("aborted")}).then(
+	() => fail("sleep with an aborted signal"),
+	reason => equals(reason, "aborted", "sleep rejects with the reason of an aborted signal"),
+);
+
+const controller = new AbortController();
+const sleeping = sleep(10000, {signal: controller.signal});
+await sleep(10);
+controller.abort("cancelled");
+
+const cancelled = Date.now();
+await sleeping.then(
+	() => fail("sleep which was aborted"),
+	reason => equals(reason, "cancelled", "sleep rejects with the reason of the signal when aborted"),
+);
+ok(Date.now() - cancelled < 1000, "sleep rejects as soon as its signal is aborted");
+
+await sleep(10000, {signal: AbortSignal.timeout(10)}).then(
+	() => fail("sleep with a timeout signal"),
+	reason => ok(reason instanceof Error, "sleep rejects with the reason of a timeout signal"),
+);
@@ -0,0 +1,62 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, fail} from "assert";
+import {Client, get} from "http";
+
+const plain = new Client({proxy: false});
+
+async function file(name) {
+	return get(`http://127.0.0.1:${port}/${name}`, {client: plain});
+}
+
+const ca = await (await file("ca.pem")).text();
+const certificate = await (await file("client.pem")).text();
+const key = await (await file("client.key")).text();
+
+const digest = new Uint8Array(await crypto.subtle.digest("SHA-256", await (await file("server.der")).arrayBuffer()));
+const fingerprint = Array.from(digest, byte => byte.toString(16).padStart(2, "0")).join("");
+const separated = fingerprint.toUpperCase().match(/../g).join(":");
+const other = "00".repeat(32);
+
+const secure = `https://localhost:${tlsPort}/`;
+const mutual = `https://localhost:${mtlsPort}/`;
+
+async function connect(url, options) {
+	const response = await get(url, {client: new Client({proxy: false, ...options})});
+	return response.text();
+}
+
+async function refused(url, options, message) {
+	await connect(url, options).then(() => fail(message), () => {});
+}
+
+function invalid(options, message) {
+	let error = null;
+	try {
+		new Client(options);
+	} catch (e) {
+		error = e;
+	}
+	equals(error instanceof TypeError, true, message);
+}
+
+await refused(secure, {}, "Certificate issued by an unknown CA was accepted");
+equals(await connect(secure, {ca: [ca]}), "secure", "Certificate issued by an extra CA is accepted");
+
+equals(await connect(secure, {ca: [ca], pinnedCertificates: [fingerprint]}), "secure", "Pinned certificate is accepted");
+equals(await connect(secure, {ca: [ca], pinnedCertificates: [other, separated]}), "secure", "Any colon-separated pinned certificate matches");
+await refused(secure, {ca: [ca], pinnedCertificates: [other]}, "Certificate which was not pinned was accepted");
+await refused(secure, {pinnedCertificates: [fingerprint]}, "Pinned certificate without a trusted chain was accepted");
+
+await refused(mutual, {ca: [ca]}, "Connection without a client certificate was accepted");
+equals(await connect(mutual, {ca: [ca], certificate, key}), "secure", "Client certificate is presented");
+
+invalid({pinnedCertificates: ["abcdef"]}, "Fingerprint with the wrong length throws a TypeError");
+invalid({pinnedCertificates: ["zz".repeat(32)]}, "Fingerprint which is not hexadecimal throws a TypeError");
+invalid({ca: ["not a certificate"]}, "CA without certificates throws a TypeError");
+invalid({certificate}, "Client certificate without a key throws a TypeError");
+invalid({certificate, key: ca}, "Client key without a private key throws a TypeError");
@@ -0,0 +1,44 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "assert";
+import {Client, post} from "http";
+
+const client = new Client({proxy: false});
+const url = `http://127.0.0.1:${port}/echo`;
+
+async function upload(body) {
+	const events = [];
+	const response = await post(url, {client, body, onUploadProgress: (loaded, total) => events.push([loaded, total])});
+	await response.text();
+	await new Promise(resolve => setTimeout(resolve, 10));
+	return events;
+}
+
+function increasing(events, message) {
+	for (let i = 1; i < events.length; i++) {
+		ok(events[i][0] >= events[i - 1][0], `${message}: progress never decreases`);
+	}
+}
+
+const data = new Uint8Array(256 * 1024).fill(97);
+let events = await upload(new Blob([data]));
+ok(events.length > 0, "Progress is reported for a Blob body");
+increasing(events, "Blob body");
+equals(events.at(-1)[0], data.length, "All bytes of a Blob body are reported");
+equals(events.at(-1)[1], data.length, "Total of a Blob body is its size");
+
+async function* chunks() {
+	for (let i = 0; i < 4; i++) {
+		yield data.subarray(i * 1024, (i + 1) * 1024);
+	}
+}
+
+events = await upload(chunks());
+ok(events.length > 0, "Progress is reported for a streamed body");
+increasing(events, "Streamed body");
+equals(events.at(-1)[0], 4096, "All bytes of a streamed body are reported");
+equals(events.at(-1)[1], undefined, "Total of a streamed body is unknown");
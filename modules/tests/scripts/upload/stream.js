@@ -0,0 +1,62 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, fail, ok} from "assert";
+import {Client, post, request, Request} from "http";
+
+const client = new Client({proxy: false});
+const url = `http://127.0.0.1:${port}/echo`;
+
+async function send(body, path = "/echo") {
+	const response = await post(`http://127.0.0.1:${port}${path}`, {client, body});
+	return response.text();
+}
+
+async function* chunks() {
+	yield "hello ";
+	yield new TextEncoder().encode("streamed");
+	yield new Uint8Array([32, 119, 111, 114, 108, 100]).buffer;
+}
+
+equals(await send(chunks()), "POST chunked\nhello streamed world", "Async iterable body is sent with chunked encoding");
+equals(await send("buffered"), "POST length 8\nbuffered", "Buffered body is sent with Content-Length");
+equals(await send(new Blob(["blob"])), "POST length 4\nblob", "Blob body is sent with Content-Length");
+
+let cancelled = false;
+const reads = ["first ", "second"];
+const readable = {
+	getReader() {
+		return {
+			read: async () => (reads.length > 0 ? {done: false, value: reads.shift()} : {done: true}),
+			cancel() {
+				cancelled = true;
+			},
+		};
+	},
+};
+equals(await send(readable), "POST chunked\nfirst second", "Body with a reader is read until done");
+equals(cancelled, false, "Exhausted reader is not cancelled");
+
+async function* failing() {
+	yield "partial";
+	throw new Error("Source failed");
+}
+await send(failing()).then(() => fail("Body stream which errored was sent"), () => {});
+
+async function* invalid() {
+	yield 1;
+}
+await send(invalid()).then(() => fail("Body stream with an invalid chunk was sent"), () => {});
+
+const error = await send(chunks(), "/redirect/307").then(() => fail("Streamed body was redirected"), error => error);
+ok(error instanceof TypeError, "Redirect with a streamed body rejects with a TypeError");
+equals(error.message, "Redirected with a Streamed Body", "Redirect with a streamed body error message");
+equals(await send(chunks(), "/redirect/303"), "GET none\n", "See Other redirects a streamed body as GET without a body");
+
+const original = new Request(url, {method: "POST", body: chunks()});
+const copy = original.clone();
+equals(await (await request(original, undefined, {client})).text(), "POST chunked\nhello streamed world", "Original request sends the whole stream");
+equals(await (await request(copy, undefined, {client})).text(), "POST chunked\nhello streamed world", "Cloned request sends the whole stream");
@@ -0,0 +1,34 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use hyper::{Body, Request, Response, Server};
+use hyper::service::{make_service_fn, service_fn};
+use tokio::time::sleep;
+
+mod common;
+
+const STATS: (&str, &str) = ("stats", include_str!("scripts/stats/stats.js"));
+
+#[tokio::test]
+async fn stats() {
+	let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(respond)) }));
+	let port = server.local_addr().port();
+	tokio::spawn(server);
+
+	common::run_scripts("stats", &[STATS], &[("port", port)]).await;
+}
+
+/// Responds with the body of the request, after a delay for `/slow`.
+async fn respond(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+	if request.uri().path() == "/slow" {
+		sleep(Duration::from_millis(100)).await;
+	}
+	Ok(Response::new(request.into_body()))
+}
@@ -0,0 +1,69 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::time::Duration;
+
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream, UdpSocket};
+use tokio::time::sleep;
+
+mod common;
+
+const TIMEOUTS: (&str, &str) = ("timeouts", include_str!("scripts/timeouts/timeouts.js"));
+
+#[tokio::test]
+async fn timeouts() {
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let port = listener.local_addr().unwrap().port();
+	tokio::spawn(serve(listener));
+
+	// Name server which never responds, so that connections to hosts resolved with it stall while connecting.
+	let dns = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+	let dns_port = dns.local_addr().unwrap().port();
+
+	common::run_scripts("timeouts", &[TIMEOUTS], &[("port", port), ("dnsPort", dns_port)]).await;
+}
+
+/// Serves responses which stall before the headers, stall during the body, or send the body slowly.
+/// Connections are held open afterwards, so that clients time out instead of seeing them close.
+async fn serve(listener: TcpListener) {
+	while let Ok((mut stream, _)) = listener.accept().await {
+		tokio::spawn(async move {
+			match read_path(&mut stream).await.as_str() {
+				"/stall-headers" => {}
+				"/stall-body" => {
+					let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nhello").await;
+				}
+				"/slow-body" => {
+					let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 6\r\n\r\n").await;
+					for byte in b"abcdef" {
+						sleep(Duration::from_millis(50)).await;
+						let _ = stream.write_all(&[*byte]).await;
+					}
+				}
+				_ => {
+					let _ = stream.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok").await;
+				}
+			}
+			sleep(Duration::from_secs(10)).await;
+		});
+	}
+}
+
+async fn read_path(stream: &mut TcpStream) -> String {
+	let mut reader = BufReader::new(stream);
+	let mut line = String::new();
+	let _ = reader.read_line(&mut line).await;
+	let path = line.split(' ').nth(1).unwrap_or_default().to_string();
+
+	loop {
+		line.clear();
+		match reader.read_line(&mut line).await {
+			Ok(read) if read > 0 && !line.trim().is_empty() => {}
+			_ => return path,
+		}
+	}
+}
@@ -0,0 +1,15 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+const SLEEP: (&str, &str) = ("sleep", include_str!("scripts/timers/sleep.js"));
+const INTERVAL: (&str, &str) = ("interval", include_str!("scripts/timers/interval.js"));
+
+#[tokio::test]
+async fn timers() {
+	common::run_scripts("timers", &[SLEEP, INTERVAL], &[]).await;
+}
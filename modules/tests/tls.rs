@@ -0,0 +1,107 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use hyper::{Body, Request, Response, Server, StatusCode};
+use hyper::server::conn::Http;
+use hyper::service::{make_service_fn, service_fn};
+use rcgen::{BasicConstraints, Certificate, CertificateParams, DistinguishedName, DnType, IsCa};
+use tokio::net::TcpListener;
+use tokio_rustls::rustls::{self, RootCertStore, ServerConfig};
+use tokio_rustls::rustls::server::AllowAnyAuthenticatedClient;
+use tokio_rustls::TlsAcceptor;
+
+mod common;
+
+const CERTIFICATES: (&str, &str) = ("certificates", include_str!("scripts/tls/certificates.js"));
+
+#[tokio::test]
+async fn tls() {
+	let ca = certificate("ca.test", true);
+	let server = certificate("localhost", false);
+	let client = certificate("client.test", false);
+
+	let ca_der = ca.serialize_der().unwrap();
+	let server_der = server.serialize_der_with_signer(&ca).unwrap();
+	let server_key = server.serialize_private_key_der();
+
+	let mut clients = RootCertStore::empty();
+	clients.add(&rustls::Certificate(ca_der)).unwrap();
+
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let tls_port = listener.local_addr().unwrap().port();
+	tokio::spawn(serve_tls(listener, acceptor(&server_der, &server_key, None)));
+
+	let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+	let mtls_port = listener.local_addr().unwrap().port();
+	tokio::spawn(serve_tls(listener, acceptor(&server_der, &server_key, Some(clients))));
+
+	// Serves the generated certificates and keys over plain HTTP, so that scripts can configure clients with them.
+	let files = Arc::new(HashMap::from([
+		("/ca.pem", ca.serialize_pem().unwrap().into_bytes()),
+		("/server.der", server_der),
+		("/client.pem", client.serialize_pem_with_signer(&ca).unwrap().into_bytes()),
+		("/client.key", client.serialize_private_key_pem().into_bytes()),
+	]));
+	let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service_fn(move |_| {
+		let files = Arc::clone(&files);
+		async move {
+			Ok::<_, Infallible>(service_fn(move |request: Request<Body>| {
+				let file = files.get(request.uri().path()).cloned();
+				async move {
+					Ok::<_, Infallible>(match file {
+						Some(file) => Response::new(Body::from(file)),
+						None => Response::builder().status(StatusCode::NOT_FOUND).body(Body::empty()).unwrap(),
+					})
+				}
+			}))
+		}
+	}));
+	let port = server.local_addr().port();
+	tokio::spawn(server);
+
+	let ports = [("port", port), ("tlsPort", tls_port), ("mtlsPort", mtls_port)];
+	common::run_scripts("tls", &[CERTIFICATES], &ports).await;
+}
+
+fn certificate(name: &str, ca: bool) -> Certificate {
+	let mut params = CertificateParams::new(vec![String::from(name)]);
+	params.distinguished_name = DistinguishedName::new();
+	params.distinguished_name.push(DnType::CommonName, name);
+	if ca {
+		params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+	}
+	Certificate::from_params(params).unwrap()
+}
+
+/// Creates an acceptor which presents the certificate, and requires client certificates issued by the roots if there are any.
+fn acceptor(certificate: &[u8], key: &[u8], clients: Option<RootCertStore>) -> TlsAcceptor {
+	let builder = ServerConfig::builder().with_safe_defaults();
+	let builder = match clients {
+		Some(roots) => builder.with_client_cert_verifier(Arc::new(AllowAnyAuthenticatedClient::new(roots))),
+		None => builder.with_no_client_auth(),
+	};
+	let config = builder
+		.with_single_cert(vec![rustls::Certificate(certificate.to_vec())], rustls::PrivateKey(key.to_vec()))
+		.unwrap();
+	TlsAcceptor::from(Arc::new(config))
+}
+
+async fn serve_tls(listener: TcpListener, acceptor: TlsAcceptor) {
+	while let Ok((stream, _)) = listener.accept().await {
+		let acceptor = acceptor.clone();
+		tokio::spawn(async move {
+			if let Ok(stream) = acceptor.accept(stream).await {
+				let service = service_fn(|_: Request<Body>| async { Ok::<_, Infallible>(Response::new(Body::from("secure"))) });
+				let _ = Http::new().serve_connection(stream, service).await;
+			}
+		});
+	}
+}
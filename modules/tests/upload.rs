@@ -0,0 +1,52 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use hyper::{Body, Request, Response, Server, StatusCode};
+use hyper::header::{CONTENT_LENGTH, LOCATION, TRANSFER_ENCODING};
+use hyper::service::{make_service_fn, service_fn};
+
+mod common;
+
+const STREAM: (&str, &str) = ("stream", include_str!("scripts/upload/stream.js"));
+const PROGRESS: (&str, &str) = ("progress", include_str!("scripts/upload/progress.js"));
+
+#[tokio::test]
+async fn upload() {
+	let server = Server::bind(&SocketAddr::from(([127, 0, 0, 1], 0))).serve(make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(echo)) }));
+	let port = server.local_addr().port();
+	tokio::spawn(server);
+
+	common::run_scripts("upload", &[STREAM, PROGRESS], &[("port", port)]).await;
+}
+
+/// Responds with the method and framing of the request on the first line, followed by its body.
+/// Requests to `/redirect/{status}` are redirected to `/echo` with the status instead.
+async fn echo(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+	if let Some(status) = request.uri().path().strip_prefix("/redirect/") {
+		let response = Response::builder()
+			.status(StatusCode::from_bytes(status.as_bytes()).unwrap())
+			.header(LOCATION, "/echo")
+			.body(Body::empty())
+			.unwrap();
+		return Ok(response);
+	}
+
+	let headers = request.headers();
+	let framing = match (headers.get(TRANSFER_ENCODING), headers.get(CONTENT_LENGTH)) {
+		(Some(encoding), _) => encoding.to_str().unwrap().to_string(),
+		(None, Some(length)) => format!("length {}", length.to_str().unwrap()),
+		(None, None) => String::from("none"),
+	};
+	let method = request.method().clone();
+	let body = hyper::body::to_bytes(request.into_body()).await.unwrap_or_default();
+
+	let mut response = format!("{} {}\n", method, framing).into_bytes();
+	response.extend_from_slice(&body);
+	Ok(Response::new(Body::from(response)))
+}
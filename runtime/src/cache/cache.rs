@@ -173,7 +173,7 @@ impl Display for Error {
 	}
 }
 
-fn hash<T: AsRef<[u8]>>(bytes: T, len: Option<usize>) -> String {
+pub(crate) fn hash<T: AsRef<[u8]>>(bytes: T, len: Option<usize>) -> String {
 	let hash = encode(&Sha3_512::new().chain_update(bytes).finalize());
 	len.map_or(hash.clone(), |len| String::from(&hash[0..len]))
 }
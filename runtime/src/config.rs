@@ -35,6 +35,7 @@ pub struct Config {
 	pub log_level: LogLevel,
 	pub script: bool,
 	pub typescript: bool,
+	pub file_urls: bool,
 }
 
 impl Config {
@@ -50,6 +51,10 @@ impl Config {
 		Config { typescript, ..self }
 	}
 
+	pub fn file_urls(self, file_urls: bool) -> Config {
+		Config { file_urls, ..self }
+	}
+
 	pub fn global() -> &'static Config {
 		CONFIG.get().expect("Configuration not initialised")
 	}
@@ -61,6 +66,7 @@ impl Default for Config {
 			log_level: LogLevel::Error,
 			script: false,
 			typescript: true,
+			file_urls: false,
 		}
 	}
 }
@@ -13,6 +13,7 @@ use futures::future::poll_fn;
 
 use ion::{Context, ErrorReport};
 
+use crate::cache::map::transform_error_report_with_sourcemaps;
 use crate::event_loop::future::FutureQueue;
 use crate::event_loop::macrotasks::MacrotaskQueue;
 use crate::event_loop::microtasks::MicrotaskQueue;
@@ -71,3 +72,9 @@ impl EventLoop {
 			&& self.macrotasks.as_ref().map(|m| m.is_empty()).unwrap_or(true)
 	}
 }
+
+/// Reports an exception which was not handled by a script, without interrupting the event loop.
+pub fn report_error(cx: &Context, mut report: ErrorReport) {
+	transform_error_report_with_sourcemaps(&mut report);
+	println!("{}", report.format(cx));
+}
@@ -5,19 +5,26 @@
  */
 
 use std::future::Future;
+use std::mem::{take, transmute};
 use std::pin::Pin;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::task::Poll;
 
 use futures::FutureExt;
+use mozjs::jsapi::JSObject;
 use mozjs::jsval::JSVal;
 use tokio::sync::watch::Receiver;
 
 pub use controller::AbortController;
-use ion::{ClassDefinition, Context, Object};
+use ion::{ClassDefinition, Context, Error, ErrorKind, Function, Object, Result, Value};
+use ion::conversions::FromValue;
+use ion::flags::PropertyFlags;
 pub use signal::AbortSignal;
 
+use crate::globals::events::{dispatch_event, Event, EventTarget, inherit, register_event_target};
+use crate::promise::future_to_promise;
+
 #[derive(Clone, Debug, Default)]
 pub enum Signal {
 	#[default]
@@ -27,6 +34,72 @@ pub enum Signal {
 	Timeout(Receiver<Option<JSVal>>, Arc<AtomicBool>),
 }
 
+impl Signal {
+	pub fn poll(&self) -> SignalFuture {
+		SignalFuture { inner: self.clone() }
+	}
+
+	pub fn reason(&self) -> Option<JSVal> {
+		match self {
+			Signal::None => None,
+			Signal::Abort(abort) => Some(*abort),
+			Signal::Receiver(receiver) | Signal::Timeout(receiver, _) => *receiver.borrow(),
+		}
+	}
+
+	pub fn is_aborted(&self) -> bool {
+		self.reason().is_some()
+	}
+
+	/// Waits until the signal is aborted, and returns its reason.
+	/// Returns [None] if the signal can no longer be aborted.
+	pub async fn aborted(self) -> Option<JSVal> {
+		match self {
+			Signal::None => None,
+			Signal::Abort(abort) => Some(abort),
+			Signal::Receiver(mut receiver) | Signal::Timeout(mut receiver, _) => {
+				receiver.wait_for(Option::is_some).await.ok().and_then(|abort| *abort)
+			}
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for Signal {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, strict: bool, _: ()) -> Result<Signal>
+	where
+		'cx: 'v,
+	{
+		let object = Object::from_value(cx, value, strict, ())?;
+		if AbortSignal::instance_of(cx, &object, None) {
+			Ok(AbortSignal::get_private(&object).signal.clone())
+		} else {
+			Err(Error::new("Expected AbortSignal", ErrorKind::Type))
+		}
+	}
+}
+
+/// Signal of an `AbortSignal` object, along with the object itself, so that the same object can be exposed again.
+#[derive(Clone, Debug)]
+pub struct SignalObject {
+	pub object: *mut JSObject,
+	pub signal: Signal,
+}
+
+impl<'cx> FromValue<'cx> for SignalObject {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, strict: bool, _: ()) -> Result<SignalObject>
+	where
+		'cx: 'v,
+	{
+		let object = Object::from_value(cx, value, strict, ())?;
+		let signal = Signal::from_value(cx, value, strict, ())?;
+		Ok(SignalObject { object: object.handle().get(), signal })
+	}
+}
+
 pub struct SignalFuture {
 	inner: Signal,
 }
@@ -73,36 +146,52 @@ impl Drop for SignalFuture {
 
 #[js_class]
 mod controller {
+	use mozjs::gc::Traceable;
+	use mozjs::jsapi::{Heap, JSObject, JSTracer};
 	use mozjs::jsval::JSVal;
 	use tokio::sync::watch::{channel, Sender};
 
-	use ion::{Context, Error, Value};
+	use ion::{ClassDefinition, Context, Error, Object, Result, Value};
 	use ion::conversions::ToValue;
 
-	use crate::globals::abort::{AbortSignal, Signal};
+	use crate::globals::abort::{AbortSignal, dispatch_abort, Signal};
 
-	#[ion(into_value)]
 	pub struct AbortController {
 		sender: Sender<Option<JSVal>>,
+		signal: Box<Heap<*mut JSObject>>,
 	}
 
 	impl AbortController {
 		#[ion(constructor)]
-		pub fn constructor() -> AbortController {
-			let (sender, _) = channel(None);
-			AbortController { sender }
+		pub fn constructor(cx: &Context) -> AbortController {
+			let (sender, receiver) = channel(None);
+			let signal = AbortSignal::from_signal(Signal::Receiver(receiver));
+			AbortController {
+				sender,
+				signal: Heap::boxed(AbortSignal::new_object(cx, signal)),
+			}
 		}
 
 		#[ion(get)]
-		pub fn get_signal(&self) -> AbortSignal {
-			AbortSignal {
-				signal: Signal::Receiver(self.sender.subscribe()),
-			}
+		pub fn get_signal(&self) -> *mut JSObject {
+			self.signal.get()
 		}
 
-		pub fn abort<'cx>(&self, cx: &'cx Context, reason: Option<Value<'cx>>) {
+		pub fn abort<'cx>(&self, cx: &'cx Context, reason: Option<Value<'cx>>) -> Result<()> {
+			if self.sender.borrow().is_some() {
+				return Ok(());
+			}
+
 			let reason = reason.unwrap_or_else(|| Error::new("AbortError", None).as_value(cx));
 			self.sender.send_replace(Some(reason.get()));
+			dispatch_abort(cx, &Object::from(cx.root_object(self.signal.get())))
+		}
+	}
+
+	unsafe impl Traceable for AbortController {
+		#[inline]
+		unsafe fn trace(&self, trc: *mut JSTracer) {
+			unsafe { self.signal.trace(trc) }
 		}
 	}
 }
@@ -112,41 +201,62 @@ mod signal {
 	use std::sync::Arc;
 	use std::sync::atomic::AtomicBool;
 
-	use chrono::Duration;
+	use mozjs::gc::Traceable;
+	use mozjs::jsapi::{Heap, JSObject, JSTracer};
 	use mozjs::jsval::JSVal;
-	use tokio::sync::watch::channel;
+	use tokio::sync::watch::{channel, Sender};
 
-	use ion::{Context, Error, Exception, ResultExc, Value};
+	use ion::{ClassDefinition, Context, Error, ErrorKind, Exception, Function, Object, Result, ResultExc, Value};
 	use ion::conversions::{ConversionBehavior, ToValue};
 
-	use crate::event_loop::EVENT_LOOP;
-	use crate::event_loop::macrotasks::{Macrotask, SignalMacrotask};
-	use crate::globals::abort::{Signal, SignalFuture};
+	use crate::globals::abort::{Signal, watch_signal};
+	use crate::globals::events::{EventTarget, EventTargetClass, get_event_target};
+	use crate::globals::timers::schedule_signal;
 
-	#[derive(Clone, Default)]
-	#[ion(no_constructor, from_value, to_value)]
+	#[ion(no_constructor)]
 	pub struct AbortSignal {
 		pub(crate) signal: Signal,
+		pub(crate) event_target: EventTarget,
+		/// Aborts the signal if it is a dependent signal, created by `AbortSignal.any`.
+		pub(crate) sender: Option<Sender<Option<JSVal>>>,
+		/// Signals which abort the signal if it is a dependent signal.
+		pub(crate) sources: Vec<Box<Heap<*mut JSObject>>>,
+		/// Dependent signals which are aborted when the signal is aborted.
+		pub(crate) dependents: Vec<Box<Heap<*mut JSObject>>>,
 	}
 
 	impl AbortSignal {
 		#[ion(skip)]
-		pub fn poll(&self) -> SignalFuture {
-			SignalFuture { inner: self.signal.clone() }
+		pub fn from_signal(signal: Signal) -> AbortSignal {
+			AbortSignal {
+				signal,
+				event_target: EventTarget::default(),
+				sender: None,
+				sources: Vec::new(),
+				dependents: Vec::new(),
+			}
 		}
 
 		#[ion(get)]
 		pub fn get_aborted(&self) -> bool {
-			self.get_reason().is_some()
+			self.signal.is_aborted()
 		}
 
 		#[ion(get)]
 		pub fn get_reason(&self) -> Option<JSVal> {
-			match &self.signal {
-				Signal::None => None,
-				Signal::Abort(abort) => Some(*abort),
-				Signal::Receiver(receiver) | Signal::Timeout(receiver, _) => *receiver.borrow(),
-			}
+			self.signal.reason()
+		}
+
+		#[ion(get)]
+		pub fn get_onabort(#[ion(this)] this: &Object, cx: &Context) -> Result<Option<*mut JSObject>> {
+			Ok(get_event_target(cx, this)?.get_event_handler("abort"))
+		}
+
+		#[ion(set)]
+		pub fn set_onabort<'cx>(#[ion(this)] this: &Object, cx: &'cx Context, handler: Option<Function<'cx>>) -> Result<()> {
+			let handler = handler.map(|handler| handler.to_object(cx).handle().get());
+			get_event_target(cx, this)?.set_event_handler("abort", handler);
+			Ok(())
 		}
 
 		pub fn throwIfAborted(&self) -> ResultExc<()> {
@@ -157,13 +267,13 @@ mod signal {
 			}
 		}
 
-		pub fn abort<'cx>(cx: &'cx Context, reason: Option<Value<'cx>>) -> AbortSignal {
+		pub fn abort<'cx>(cx: &'cx Context, reason: Option<Value<'cx>>) -> *mut JSObject {
 			let reason = reason.unwrap_or_else(|| Error::new("AbortError", None).as_value(cx));
-			AbortSignal { signal: Signal::Abort(reason.get()) }
+			AbortSignal::new_object(cx, AbortSignal::from_signal(Signal::Abort(reason.get())))
 		}
 
-		pub fn timeout(cx: &Context, #[ion(convert = ConversionBehavior::EnforceRange)] time: u64) -> AbortSignal {
-			let (sender, receiver) = channel(None);
+		pub fn timeout(cx: &Context, #[ion(convert = ConversionBehavior::EnforceRange)] time: u64) -> *mut JSObject {
+			let (sender, mut receiver) = channel(None);
 			let terminate = Arc::new(AtomicBool::new(false));
 			let terminate2 = terminate.clone();
 
@@ -172,19 +282,134 @@ mod signal {
 				sender.send_replace(Some(error));
 			});
 
-			let duration = Duration::milliseconds(time as i64);
-			EVENT_LOOP.with(|event_loop| {
-				if let Some(queue) = event_loop.borrow_mut().macrotasks.as_mut() {
-					queue.enqueue(Macrotask::Signal(SignalMacrotask::new(callback, terminate, duration)), None);
-				}
+			let _ = schedule_signal(callback, terminate, time);
+
+			let object = AbortSignal::new_object(cx, AbortSignal::from_signal(Signal::Timeout(receiver.clone(), terminate2)));
+			watch_signal(cx, object, async move {
+				let _ = receiver.changed().await;
 			});
-			AbortSignal {
-				signal: Signal::Timeout(receiver, terminate2),
+			object
+		}
+
+		/// Creates a dependent signal, which is aborted synchronously when any of its source signals is aborted.
+		/// Dependent signals in `signals` are replaced by their own sources.
+		pub fn any<'cx>(cx: &'cx Context, signals: Vec<Object<'cx>>) -> Result<*mut JSObject> {
+			let mut sources: Vec<*mut JSObject> = Vec::new();
+			for object in &signals {
+				if !AbortSignal::instance_of(cx, object, None) {
+					return Err(Error::new("Expected AbortSignal", ErrorKind::Type));
+				}
+				let signal = AbortSignal::get_private(object);
+				if let Some(reason) = signal.signal.reason() {
+					return Ok(AbortSignal::new_object(cx, AbortSignal::from_signal(Signal::Abort(reason))));
+				}
+
+				let signal_sources = if signal.sender.is_some() {
+					signal.sources.iter().map(|source| source.get()).collect()
+				} else {
+					vec![object.handle().get()]
+				};
+				for source in signal_sources {
+					if !sources.contains(&source) {
+						sources.push(source);
+					}
+				}
 			}
+
+			let (sender, receiver) = channel(None);
+			let mut signal = AbortSignal::from_signal(Signal::Receiver(receiver));
+			signal.sender = Some(sender);
+			signal.sources = sources.iter().map(|source| Heap::boxed(*source)).collect();
+			let object = AbortSignal::new_object(cx, signal);
+
+			for source in sources {
+				let source = Object::from(cx.root_object(source));
+				AbortSignal::get_private(&source).dependents.push(Heap::boxed(object));
+			}
+			Ok(object)
 		}
 	}
+
+	impl EventTargetClass for AbortSignal {
+		fn event_target(&mut self) -> &mut EventTarget {
+			&mut self.event_target
+		}
+	}
+
+	unsafe impl Traceable for AbortSignal {
+		#[inline]
+		unsafe fn trace(&self, trc: *mut JSTracer) {
+			unsafe {
+				self.event_target.trace(trc);
+				self.sources.trace(trc);
+				self.dependents.trace(trc);
+			}
+		}
+	}
+}
+
+/// Keeps a signal object alive until the future completes, and then dispatches an `abort` event on it if it has been aborted.
+fn watch_signal<F: Future<Output = ()> + 'static>(cx: &Context, signal: *mut JSObject, future: F) {
+	let signal: Object<'static> = unsafe { transmute(Object::from(cx.root_persistent_object(signal))) };
+	let promise = future_to_promise(cx, async move {
+		future.await;
+		Ok::<_, ()>(())
+	});
+	promise.add_reactions(
+		cx,
+		Some(Function::from_closure(
+			cx,
+			"",
+			Box::new(move |args| {
+				let cx = args.cx();
+				let result = if AbortSignal::get_private(&signal).signal.is_aborted() {
+					dispatch_abort(cx, &signal)
+				} else {
+					Ok(())
+				};
+				cx.unroot_persistent_object(signal.handle().get());
+				result.map(|_| Value::undefined(cx)).map_err(Into::into)
+			}),
+			0,
+			PropertyFlags::empty(),
+		)),
+		None,
+	);
+}
+
+/// Aborts the dependent signals of an aborted signal object with its reason, and then dispatches trusted `abort` events on the
+/// signal and each dependent signal which was not already aborted.
+pub(crate) fn dispatch_abort(cx: &Context, signal: &Object) -> Result<()> {
+	let private = AbortSignal::get_private(signal);
+	let reason = private.signal.reason();
+	let dependents: Vec<_> = take(&mut private.dependents)
+		.into_iter()
+		.map(|dependent| Object::from(cx.root_object(dependent.get())))
+		.filter(|dependent| {
+			let dependent = AbortSignal::get_private(dependent);
+			match &dependent.sender {
+				Some(sender) if !dependent.signal.is_aborted() => {
+					sender.send_replace(reason);
+					true
+				}
+				_ => false,
+			}
+		})
+		.collect();
+
+	dispatch_abort_event(cx, signal)?;
+	for dependent in &dependents {
+		dispatch_abort_event(cx, dependent)?;
+	}
+	Ok(())
+}
+
+fn dispatch_abort_event(cx: &Context, signal: &Object) -> Result<()> {
+	let event = Object::from(cx.root_object(Event::new_object(cx, Event::new_trusted("abort"))));
+	dispatch_event(cx, signal, &event).map(|_| ())
 }
 
 pub fn define(cx: &Context, global: &mut Object) -> bool {
-	AbortController::init_class(cx, global).0 && AbortSignal::init_class(cx, global).0
+	register_event_target::<AbortSignal>();
+	AbortController::init_class(cx, global).0 && AbortSignal::init_class(cx, global).0 && inherit::<AbortSignal, EventTarget>(cx, global)
 }
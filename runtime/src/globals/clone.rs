@@ -0,0 +1,38 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::{JSFunctionSpec, JSObject};
+
+use ion::{Context, Function, Object, ResultExc, Value};
+use ion::clone::{StructuredCloneBuffer, StructuredCloneScope};
+use ion::flags::PropertyFlags;
+
+#[derive(Default, FromValue)]
+pub struct StructuredSerializeOptions {
+	#[ion(default)]
+	transfer: Vec<*mut JSObject>,
+}
+
+#[js_fn]
+fn structuredClone<'cx>(cx: &'cx Context, value: Value<'cx>, options: Option<StructuredSerializeOptions>) -> ResultExc<Value<'cx>> {
+	let options = options.unwrap_or_default();
+	let transfer: Vec<_> = options.transfer.into_iter().map(|object| Object::from(cx.root_object(object))).collect();
+
+	let mut buffer = StructuredCloneBuffer::new(StructuredCloneScope::SameProcess);
+	buffer.write(cx, &value, &transfer)?;
+	buffer.read(cx)
+}
+
+const FUNCTION: JSFunctionSpec = function_spec!(structuredClone, 1);
+
+pub fn define<'cx>(cx: &'cx Context, global: &mut Object<'cx>) -> bool {
+	global.define_as(
+		cx,
+		"structuredClone",
+		&Function::from_spec(cx, &FUNCTION),
+		PropertyFlags::CONSTANT_ENUMERATED,
+	)
+}
@@ -0,0 +1,506 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use sha3::Digest;
+
+use ion::{Context, Error, ErrorKind, Object, Result, Value};
+use ion::conversions::{ConversionBehavior, FromValue, ToValue};
+
+use crate::globals::crypto::{BufferSource, CryptoKey, not_supported_error};
+
+/// Calls the expression with `$D` as the hash function type of a [DigestAlgorithm].
+macro_rules! with_digest {
+	($algorithm:expr, $D:ident => $body:expr) => {
+		match $algorithm {
+			DigestAlgorithm::Sha1 => {
+				type $D = sha1::Sha1;
+				$body
+			}
+			DigestAlgorithm::Sha256 => {
+				type $D = sha2::Sha256;
+				$body
+			}
+			DigestAlgorithm::Sha384 => {
+				type $D = sha2::Sha384;
+				$body
+			}
+			DigestAlgorithm::Sha512 => {
+				type $D = sha2::Sha512;
+				$body
+			}
+			DigestAlgorithm::Sha3_256 => {
+				type $D = sha3::Sha3_256;
+				$body
+			}
+			DigestAlgorithm::Sha3_384 => {
+				type $D = sha3::Sha3_384;
+				$body
+			}
+			DigestAlgorithm::Sha3_512 => {
+				type $D = sha3::Sha3_512;
+				$body
+			}
+		}
+	};
+}
+
+pub(crate) use with_digest;
+
+/// Reads the name of an algorithm, which is either a string or an object with a `name` property.
+pub(crate) fn algorithm_name(cx: &Context, value: &Value) -> Result<String> {
+	if value.handle().is_string() {
+		return String::from_value(cx, value, true, ());
+	} else if !value.handle().is_object() {
+		return Err(Error::new("Expected String or Object for Algorithm", ErrorKind::Type));
+	}
+
+	let object = value.to_object(cx);
+	object
+		.get_as(cx, "name", true, ())
+		.ok_or_else(|| Error::new("Expected Algorithm to have a name", ErrorKind::Type))
+}
+
+/// Creates the error for an algorithm which is not supported by an operation.
+pub(crate) fn unsupported(name: &str) -> Error {
+	Error::new(&format!("Algorithm {} is not supported", name), None)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlgorithmName {
+	Hmac,
+	Ecdsa,
+	Ecdh,
+	Ed25519,
+	RsassaPkcs1,
+	RsaPss,
+	RsaOaep,
+	AesGcm,
+	AesCbc,
+	Pbkdf2,
+	Hkdf,
+}
+
+impl AlgorithmName {
+	const NAMES: [(&'static str, AlgorithmName); 11] = [
+		("HMAC", AlgorithmName::Hmac),
+		("ECDSA", AlgorithmName::Ecdsa),
+		("ECDH", AlgorithmName::Ecdh),
+		("Ed25519", AlgorithmName::Ed25519),
+		("RSASSA-PKCS1-v1_5", AlgorithmName::RsassaPkcs1),
+		("RSA-PSS", AlgorithmName::RsaPss),
+		("RSA-OAEP", AlgorithmName::RsaOaep),
+		("AES-GCM", AlgorithmName::AesGcm),
+		("AES-CBC", AlgorithmName::AesCbc),
+		("PBKDF2", AlgorithmName::Pbkdf2),
+		("HKDF", AlgorithmName::Hkdf),
+	];
+
+	fn parse(cx: &Context, value: &Value) -> Result<AlgorithmName> {
+		AlgorithmName::from_str(&algorithm_name(cx, value)?)
+	}
+}
+
+impl Display for AlgorithmName {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		let (name, _) = AlgorithmName::NAMES.iter().find(|(_, algorithm)| algorithm == self).unwrap();
+		f.write_str(name)
+	}
+}
+
+impl FromStr for AlgorithmName {
+	type Err = Error;
+
+	fn from_str(name: &str) -> Result<AlgorithmName> {
+		AlgorithmName::NAMES
+			.iter()
+			.find(|(algorithm, _)| algorithm.eq_ignore_ascii_case(name))
+			.map(|(_, algorithm)| *algorithm)
+			.ok_or_else(|| unsupported(name))
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DigestAlgorithm {
+	Sha1,
+	Sha256,
+	Sha384,
+	Sha512,
+	Sha3_256,
+	Sha3_384,
+	Sha3_512,
+}
+
+impl DigestAlgorithm {
+	pub fn from_name(name: &str) -> Option<DigestAlgorithm> {
+		use DigestAlgorithm as DA;
+		const ALGORITHMS: [(&str, DigestAlgorithm); 7] = [
+			("SHA-1", DA::Sha1),
+			("SHA-256", DA::Sha256),
+			("SHA-384", DA::Sha384),
+			("SHA-512", DA::Sha512),
+			("SHA3-256", DA::Sha3_256),
+			("SHA3-384", DA::Sha3_384),
+			("SHA3-512", DA::Sha3_512),
+		];
+		ALGORITHMS
+			.iter()
+			.find(|(algorithm, _)| algorithm.eq_ignore_ascii_case(name))
+			.map(|(_, algorithm)| *algorithm)
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			DigestAlgorithm::Sha1 => "SHA-1",
+			DigestAlgorithm::Sha256 => "SHA-256",
+			DigestAlgorithm::Sha384 => "SHA-384",
+			DigestAlgorithm::Sha512 => "SHA-512",
+			DigestAlgorithm::Sha3_256 => "SHA3-256",
+			DigestAlgorithm::Sha3_384 => "SHA3-384",
+			DigestAlgorithm::Sha3_512 => "SHA3-512",
+		}
+	}
+
+	/// Returns the block size of the hash function in bits, which is the default length of HMAC keys.
+	pub fn block_size(&self) -> u32 {
+		match self {
+			DigestAlgorithm::Sha1 | DigestAlgorithm::Sha256 => 512,
+			DigestAlgorithm::Sha384 | DigestAlgorithm::Sha512 => 1024,
+			DigestAlgorithm::Sha3_256 => 1088,
+			DigestAlgorithm::Sha3_384 => 832,
+			DigestAlgorithm::Sha3_512 => 576,
+		}
+	}
+
+	pub fn digest(&self, data: &[u8]) -> Vec<u8> {
+		with_digest!(self, D => D::digest(data).to_vec())
+	}
+}
+
+impl<'cx> FromValue<'cx> for DigestAlgorithm {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, _: bool, _: ()) -> Result<DigestAlgorithm>
+	where
+		'cx: 'v,
+	{
+		let name = algorithm_name(cx, value)?;
+		DigestAlgorithm::from_name(&name).ok_or_else(|| unsupported(&name))
+	}
+}
+
+impl<'cx> ToValue<'cx> for DigestAlgorithm {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "name", self.name());
+		object.to_value(cx, value);
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+	P256,
+	P384,
+}
+
+impl Display for Curve {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			Curve::P256 => "P-256",
+			Curve::P384 => "P-384",
+		})
+	}
+}
+
+impl FromStr for Curve {
+	type Err = Error;
+
+	fn from_str(curve: &str) -> Result<Curve> {
+		match curve {
+			"P-256" => Ok(Curve::P256),
+			"P-384" => Ok(Curve::P384),
+			_ => Err(Error::new(&format!("Curve {} is not supported", curve), None)),
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for Curve {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, strict: bool, _: ()) -> Result<Curve>
+	where
+		'cx: 'v,
+	{
+		let curve = String::from_value(cx, value, strict, ())?;
+		Curve::from_str(&curve)
+	}
+}
+
+impl<'cx> ToValue<'cx> for Curve {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		self.to_string().to_value(cx, value);
+	}
+}
+
+#[derive(FromValue)]
+pub struct HmacParams {
+	pub hash: DigestAlgorithm,
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub length: Option<u32>,
+}
+
+#[derive(FromValue)]
+pub struct AesKeyGenParams {
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub length: u16,
+}
+
+#[derive(FromValue)]
+pub struct EcKeyParams {
+	pub named_curve: Curve,
+}
+
+#[derive(FromValue)]
+pub struct RsaHashedKeyGenParams {
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub modulus_length: u32,
+	pub public_exponent: BufferSource,
+	pub hash: DigestAlgorithm,
+}
+
+#[derive(FromValue)]
+pub struct RsaHashedImportParams {
+	pub hash: DigestAlgorithm,
+}
+
+#[derive(FromValue)]
+pub struct EcdsaParams {
+	pub hash: DigestAlgorithm,
+}
+
+#[derive(FromValue)]
+pub struct RsaPssParams {
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub salt_length: u32,
+}
+
+#[derive(FromValue)]
+pub struct AesGcmParams {
+	pub iv: BufferSource,
+	pub additional_data: Option<BufferSource>,
+	#[ion(convert = ConversionBehavior::EnforceRange, default = 128)]
+	pub tag_length: u8,
+}
+
+#[derive(FromValue)]
+pub struct AesCbcParams {
+	pub iv: BufferSource,
+}
+
+#[derive(FromValue)]
+pub struct RsaOaepParams {
+	/// Label associated with the message, which must be valid UTF-8.
+	#[ion(default, parser = |label| parse_label(cx, label))]
+	pub label: Option<String>,
+}
+
+/// Parses an RSA-OAEP label, which is rejected with a `NotSupportedError` if it is not valid UTF-8,
+/// as the `rsa` crate only accepts labels as strings.
+fn parse_label<'cx: 'v, 'v>(cx: &'cx Context, label: Value<'v>) -> Result<String> {
+	let label = BufferSource::from_value(cx, &label, true, ())?;
+	String::from_utf8(label.0).map_err(|_| not_supported_error(cx, "RSA-OAEP labels which are not valid UTF-8 are not supported"))
+}
+
+#[derive(FromValue)]
+pub struct Pbkdf2Params {
+	pub salt: BufferSource,
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub iterations: u32,
+	pub hash: DigestAlgorithm,
+}
+
+#[derive(FromValue)]
+pub struct HkdfParams {
+	pub hash: DigestAlgorithm,
+	pub salt: BufferSource,
+	pub info: BufferSource,
+}
+
+#[derive(FromValue)]
+pub struct EcdhKeyDeriveParams {
+	pub public: CryptoKey,
+}
+
+/// Algorithm of `sign` and `verify`.
+pub enum SignAlgorithm {
+	Hmac,
+	Ecdsa(DigestAlgorithm),
+	Ed25519,
+	RsassaPkcs1,
+	RsaPss(usize),
+}
+
+impl SignAlgorithm {
+	pub fn name(&self) -> AlgorithmName {
+		match self {
+			SignAlgorithm::Hmac => AlgorithmName::Hmac,
+			SignAlgorithm::Ecdsa(_) => AlgorithmName::Ecdsa,
+			SignAlgorithm::Ed25519 => AlgorithmName::Ed25519,
+			SignAlgorithm::RsassaPkcs1 => AlgorithmName::RsassaPkcs1,
+			SignAlgorithm::RsaPss(_) => AlgorithmName::RsaPss,
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for SignAlgorithm {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, strict: bool, _: ()) -> Result<SignAlgorithm>
+	where
+		'cx: 'v,
+	{
+		match AlgorithmName::parse(cx, value)? {
+			AlgorithmName::Hmac => Ok(SignAlgorithm::Hmac),
+			AlgorithmName::Ecdsa => Ok(SignAlgorithm::Ecdsa(EcdsaParams::from_value(cx, value, strict, ())?.hash)),
+			AlgorithmName::Ed25519 => Ok(SignAlgorithm::Ed25519),
+			AlgorithmName::RsassaPkcs1 => Ok(SignAlgorithm::RsassaPkcs1),
+			AlgorithmName::RsaPss => Ok(SignAlgorithm::RsaPss(
+				RsaPssParams::from_value(cx, value, strict, ())?.salt_length as usize,
+			)),
+			name => Err(unsupported(&name.to_string())),
+		}
+	}
+}
+
+/// Algorithm of `encrypt` and `decrypt`.
+pub enum CipherAlgorithm {
+	AesGcm(AesGcmParams),
+	AesCbc(AesCbcParams),
+	RsaOaep(RsaOaepParams),
+}
+
+impl CipherAlgorithm {
+	pub fn name(&self) -> AlgorithmName {
+		match self {
+			CipherAlgorithm::AesGcm(_) => AlgorithmName::AesGcm,
+			CipherAlgorithm::AesCbc(_) => AlgorithmName::AesCbc,
+			CipherAlgorithm::RsaOaep(_) => AlgorithmName::RsaOaep,
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for CipherAlgorithm {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, strict: bool, _: ()) -> Result<CipherAlgorithm>
+	where
+		'cx: 'v,
+	{
+		match AlgorithmName::parse(cx, value)? {
+			AlgorithmName::AesGcm => Ok(CipherAlgorithm::AesGcm(AesGcmParams::from_value(cx, value, strict, ())?)),
+			AlgorithmName::AesCbc => Ok(CipherAlgorithm::AesCbc(AesCbcParams::from_value(cx, value, strict, ())?)),
+			AlgorithmName::RsaOaep => Ok(CipherAlgorithm::RsaOaep(RsaOaepParams::from_value(cx, value, strict, ())?)),
+			name => Err(unsupported(&name.to_string())),
+		}
+	}
+}
+
+/// Algorithm of `deriveBits`.
+pub enum DeriveAlgorithm {
+	Pbkdf2(Pbkdf2Params),
+	Hkdf(HkdfParams),
+	Ecdh(CryptoKey),
+}
+
+impl DeriveAlgorithm {
+	pub fn name(&self) -> AlgorithmName {
+		match self {
+			DeriveAlgorithm::Pbkdf2(_) => AlgorithmName::Pbkdf2,
+			DeriveAlgorithm::Hkdf(_) => AlgorithmName::Hkdf,
+			DeriveAlgorithm::Ecdh(_) => AlgorithmName::Ecdh,
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for DeriveAlgorithm {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, strict: bool, _: ()) -> Result<DeriveAlgorithm>
+	where
+		'cx: 'v,
+	{
+		match AlgorithmName::parse(cx, value)? {
+			AlgorithmName::Pbkdf2 => Ok(DeriveAlgorithm::Pbkdf2(Pbkdf2Params::from_value(cx, value, strict, ())?)),
+			AlgorithmName::Hkdf => Ok(DeriveAlgorithm::Hkdf(HkdfParams::from_value(cx, value, strict, ())?)),
+			AlgorithmName::Ecdh => Ok(DeriveAlgorithm::Ecdh(EcdhKeyDeriveParams::from_value(cx, value, strict, ())?.public)),
+			name => Err(unsupported(&name.to_string())),
+		}
+	}
+}
+
+/// Algorithm of `generateKey`.
+pub enum KeyGenAlgorithm {
+	Hmac(HmacParams),
+	Aes(AlgorithmName, u16),
+	Ec(AlgorithmName, Curve),
+	Ed25519,
+	Rsa(AlgorithmName, RsaHashedKeyGenParams),
+}
+
+impl<'cx> FromValue<'cx> for KeyGenAlgorithm {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, strict: bool, _: ()) -> Result<KeyGenAlgorithm>
+	where
+		'cx: 'v,
+	{
+		use AlgorithmName as AN;
+		match AlgorithmName::parse(cx, value)? {
+			AN::Hmac => Ok(KeyGenAlgorithm::Hmac(HmacParams::from_value(cx, value, strict, ())?)),
+			name @ (AN::AesGcm | AN::AesCbc) => Ok(KeyGenAlgorithm::Aes(name, AesKeyGenParams::from_value(cx, value, strict, ())?.length)),
+			name @ (AN::Ecdsa | AN::Ecdh) => Ok(KeyGenAlgorithm::Ec(name, EcKeyParams::from_value(cx, value, strict, ())?.named_curve)),
+			AN::Ed25519 => Ok(KeyGenAlgorithm::Ed25519),
+			name @ (AN::RsassaPkcs1 | AN::RsaPss | AN::RsaOaep) => {
+				Ok(KeyGenAlgorithm::Rsa(name, RsaHashedKeyGenParams::from_value(cx, value, strict, ())?))
+			}
+			name => Err(unsupported(&name.to_string())),
+		}
+	}
+}
+
+/// Algorithm of `importKey`.
+pub enum ImportAlgorithm {
+	Hmac(HmacParams),
+	Aes(AlgorithmName),
+	Ec(AlgorithmName, Curve),
+	Ed25519,
+	Rsa(AlgorithmName, DigestAlgorithm),
+	Kdf(AlgorithmName),
+}
+
+impl<'cx> FromValue<'cx> for ImportAlgorithm {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, strict: bool, _: ()) -> Result<ImportAlgorithm>
+	where
+		'cx: 'v,
+	{
+		use AlgorithmName as AN;
+		match AlgorithmName::parse(cx, value)? {
+			AN::Hmac => Ok(ImportAlgorithm::Hmac(HmacParams::from_value(cx, value, strict, ())?)),
+			name @ (AN::AesGcm | AN::AesCbc) => Ok(ImportAlgorithm::Aes(name)),
+			name @ (AN::Ecdsa | AN::Ecdh) => Ok(ImportAlgorithm::Ec(name, EcKeyParams::from_value(cx, value, strict, ())?.named_curve)),
+			AN::Ed25519 => Ok(ImportAlgorithm::Ed25519),
+			name @ (AN::RsassaPkcs1 | AN::RsaPss | AN::RsaOaep) => {
+				Ok(ImportAlgorithm::Rsa(name, RsaHashedImportParams::from_value(cx, value, strict, ())?.hash))
+			}
+			name @ (AN::Pbkdf2 | AN::Hkdf) => Ok(ImportAlgorithm::Kdf(name)),
+		}
+	}
+}
@@ -0,0 +1,101 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use aes::{Aes128, Aes192, Aes256};
+use aes_gcm::AesGcm;
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::aead::consts::U12;
+use aes_gcm::aead::generic_array::GenericArray;
+use cbc::cipher::{BlockCipher, BlockDecryptMut, BlockEncryptMut, KeyInit, KeyIvInit};
+use cbc::cipher::block_padding::Pkcs7;
+use rsa::Oaep;
+
+use ion::{Error, Result};
+
+use crate::globals::crypto::algorithm::{AesCbcParams, AesGcmParams, CipherAlgorithm, DigestAlgorithm, RsaOaepParams, with_digest};
+use crate::globals::crypto::crypto::SystemRng;
+use crate::globals::crypto::key::{CryptoKey, KeyAlgorithm, KeyMaterial, KeyUsage};
+
+fn operation_error(encrypt: bool) -> Error {
+	if encrypt {
+		Error::new("Failed to encrypt data", None)
+	} else {
+		Error::new("Failed to decrypt data", None)
+	}
+}
+
+fn aes_gcm<C: Aead + KeyInit>(key: &[u8], params: &AesGcmParams, data: &[u8], encrypt: bool) -> Result<Vec<u8>> {
+	let cipher = C::new_from_slice(key).map_err(|_| operation_error(encrypt))?;
+	let nonce = GenericArray::from_slice(&params.iv.0);
+	let payload = Payload {
+		msg: data,
+		aad: params.additional_data.as_ref().map_or(&[][..], |aad| &aad.0),
+	};
+
+	let result = if encrypt {
+		cipher.encrypt(nonce, payload)
+	} else {
+		cipher.decrypt(nonce, payload)
+	};
+	result.map_err(|_| operation_error(encrypt))
+}
+
+fn aes_cbc<C: BlockCipher + BlockEncryptMut + BlockDecryptMut + KeyInit>(
+	key: &[u8], params: &AesCbcParams, data: &[u8], encrypt: bool,
+) -> Result<Vec<u8>> {
+	if encrypt {
+		let cipher = cbc::Encryptor::<C>::new_from_slices(key, &params.iv.0).map_err(|_| operation_error(encrypt))?;
+		Ok(cipher.encrypt_padded_vec_mut::<Pkcs7>(data))
+	} else {
+		let cipher = cbc::Decryptor::<C>::new_from_slices(key, &params.iv.0).map_err(|_| operation_error(encrypt))?;
+		cipher.decrypt_padded_vec_mut::<Pkcs7>(data).map_err(|_| operation_error(encrypt))
+	}
+}
+
+/// Encrypts or decrypts with RSA-OAEP.
+/// Labels which are not valid UTF-8 are rejected when the parameters are parsed.
+///
+/// Decryption is not constant-time, and is vulnerable to the Marvin timing side-channel (RUSTSEC-2023-0071).
+fn rsa_oaep(key: &KeyMaterial, hash: DigestAlgorithm, params: &RsaOaepParams, data: &[u8], encrypt: bool) -> Result<Vec<u8>> {
+	let padding = with_digest!(hash, D => match params.label.clone() {
+		Some(label) => Oaep::new_with_label::<D, _>(label),
+		None => Oaep::new::<D>(),
+	});
+
+	let result = match key {
+		KeyMaterial::RsaPublic(public) if encrypt => public.encrypt(&mut SystemRng, padding, data),
+		KeyMaterial::RsaPrivate(private) if !encrypt => private.decrypt(padding, data),
+		_ => return Err(operation_error(encrypt)),
+	};
+	result.map_err(|_| operation_error(encrypt))
+}
+
+/// Encrypts or decrypts the data with a key.
+pub fn cipher(algorithm: &CipherAlgorithm, key: &CryptoKey, data: &[u8], encrypt: bool) -> Result<Vec<u8>> {
+	key.check(algorithm.name(), if encrypt { KeyUsage::Encrypt } else { KeyUsage::Decrypt })?;
+	match (algorithm, &key.material, &key.algorithm) {
+		(CipherAlgorithm::AesGcm(params), KeyMaterial::Secret(secret), _) => {
+			if params.tag_length != 128 {
+				return Err(Error::new("AES-GCM only supports tags of 128 bits", None));
+			}
+			if params.iv.0.len() != 12 {
+				return Err(Error::new("AES-GCM only supports IVs of 96 bits", None));
+			}
+			match secret.len() {
+				16 => aes_gcm::<AesGcm<Aes128, U12>>(secret, params, data, encrypt),
+				24 => aes_gcm::<AesGcm<Aes192, U12>>(secret, params, data, encrypt),
+				_ => aes_gcm::<AesGcm<Aes256, U12>>(secret, params, data, encrypt),
+			}
+		}
+		(CipherAlgorithm::AesCbc(params), KeyMaterial::Secret(secret), _) => match secret.len() {
+			16 => aes_cbc::<Aes128>(secret, params, data, encrypt),
+			24 => aes_cbc::<Aes192>(secret, params, data, encrypt),
+			_ => aes_cbc::<Aes256>(secret, params, data, encrypt),
+		},
+		(CipherAlgorithm::RsaOaep(params), material, KeyAlgorithm::Rsa { hash, .. }) => rsa_oaep(material, *hash, params, data, encrypt),
+		_ => Err(operation_error(encrypt)),
+	}
+}
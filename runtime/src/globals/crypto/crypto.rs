@@ -0,0 +1,124 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::num::NonZeroU32;
+
+use rand_core::{CryptoRng, impls, RngCore};
+use ring::rand::{SecureRandom, SystemRandom};
+
+pub use class::Crypto;
+use ion::{Error, Result};
+
+/// Maximum number of bytes which can be filled by a single call to `getRandomValues`.
+const MAX_RANDOM_BYTES: usize = 65536;
+
+/// Fills the bytes with cryptographically secure random values from the system.
+pub(crate) fn fill_random(bytes: &mut [u8]) -> Result<()> {
+	SystemRandom::new()
+		.fill(bytes)
+		.map_err(|_| Error::new("Failed to generate random values", None))
+}
+
+/// Random number generator for key generation and padding, which uses the same source as [fill_random].
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct SystemRng;
+
+impl RngCore for SystemRng {
+	fn next_u32(&mut self) -> u32 {
+		impls::next_u32_via_fill(self)
+	}
+
+	fn next_u64(&mut self) -> u64 {
+		impls::next_u64_via_fill(self)
+	}
+
+	fn fill_bytes(&mut self, dest: &mut [u8]) {
+		self.try_fill_bytes(dest).expect("Failed to generate random values")
+	}
+
+	fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand_core::Error> {
+		SystemRandom::new()
+			.fill(dest)
+			.map_err(|_| rand_core::Error::from(NonZeroU32::new(rand_core::Error::CUSTOM_START).unwrap()))
+	}
+}
+
+impl CryptoRng for SystemRng {}
+
+#[js_class]
+mod class {
+	use std::fmt::Write;
+
+	use mozjs::gc::Traceable;
+	use mozjs::jsapi::{Heap, JSObject, JSTracer};
+	use mozjs::jsapi::Scalar::Type;
+	use mozjs::typedarray::ArrayBufferView;
+
+	use ion::{Error, ErrorKind, Result};
+
+	use crate::globals::crypto::crypto::{fill_random, MAX_RANDOM_BYTES};
+
+	#[ion(no_constructor)]
+	pub struct Crypto {
+		pub(crate) subtle: Box<Heap<*mut JSObject>>,
+	}
+
+	impl Crypto {
+		#[ion(get)]
+		pub fn get_subtle(&self) -> *mut JSObject {
+			self.subtle.get()
+		}
+
+		pub fn getRandomValues(&self, array: ArrayBufferView) -> Result<*mut JSObject> {
+			let mut array = array;
+			match array.get_array_type() {
+				Type::Int8
+				| Type::Uint8
+				| Type::Uint8Clamped
+				| Type::Int16
+				| Type::Uint16
+				| Type::Int32
+				| Type::Uint32
+				| Type::BigInt64
+				| Type::BigUint64 => {}
+				_ => return Err(Error::new("Expected Integer Typed Array", ErrorKind::Type)),
+			}
+
+			let bytes = unsafe { array.as_mut_slice() };
+			if bytes.len() > MAX_RANDOM_BYTES {
+				return Err(Error::new(
+					&format!("Typed Array of {} bytes exceeds the maximum of {} bytes", bytes.len(), MAX_RANDOM_BYTES),
+					ErrorKind::Range,
+				));
+			}
+			fill_random(bytes)?;
+			Ok(*array.underlying_object())
+		}
+
+		pub fn randomUUID(&self) -> Result<String> {
+			let mut bytes = [0; 16];
+			fill_random(&mut bytes)?;
+			bytes[6] = (bytes[6] & 0x0F) | 0x40;
+			bytes[8] = (bytes[8] & 0x3F) | 0x80;
+
+			let mut uuid = String::with_capacity(36);
+			for (i, byte) in bytes.iter().enumerate() {
+				if matches!(i, 4 | 6 | 8 | 10) {
+					uuid.push('-');
+				}
+				let _ = write!(uuid, "{:02x}", byte);
+			}
+			Ok(uuid)
+		}
+	}
+
+	unsafe impl Traceable for Crypto {
+		#[inline]
+		unsafe fn trace(&self, trc: *mut JSTracer) {
+			unsafe { self.subtle.trace(trc) }
+		}
+	}
+}
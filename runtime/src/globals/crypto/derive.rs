@@ -0,0 +1,73 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use hkdf::Hkdf;
+
+use ion::{Error, Result};
+
+use crate::globals::crypto::algorithm::{AlgorithmName, DeriveAlgorithm, DigestAlgorithm, with_digest};
+use crate::globals::crypto::key::{CryptoKey, EcPrivateKey, EcPublicKey, KeyMaterial, KeyType, KeyUsage};
+
+/// Returns the number of bytes for a length in bits, which must be a non-zero multiple of 8.
+fn byte_length(length: Option<u32>) -> Result<usize> {
+	match length {
+		Some(length) if length != 0 && length % 8 == 0 => Ok(length as usize / 8),
+		_ => Err(Error::new("Length must be a non-zero multiple of 8", None)),
+	}
+}
+
+fn ecdh(private: &EcPrivateKey, public: &CryptoKey) -> Result<Vec<u8>> {
+	if public.algorithm.name() != AlgorithmName::Ecdh || public.material.key_type() != KeyType::Public {
+		return Err(Error::new("Expected public ECDH key", None));
+	}
+	match (private, &public.material) {
+		(EcPrivateKey::P256(private), KeyMaterial::EcPublic(EcPublicKey::P256(public))) => {
+			let secret = p256::ecdh::diffie_hellman(private.to_nonzero_scalar(), public.as_affine());
+			Ok(secret.raw_secret_bytes().to_vec())
+		}
+		(EcPrivateKey::P384(private), KeyMaterial::EcPublic(EcPublicKey::P384(public))) => {
+			let secret = p384::ecdh::diffie_hellman(private.to_nonzero_scalar(), public.as_affine());
+			Ok(secret.raw_secret_bytes().to_vec())
+		}
+		_ => Err(Error::new("Public key must use the same curve as the private key", None)),
+	}
+}
+
+/// Derives bits from a key, which are truncated to the length if given.
+pub fn derive_bits(algorithm: &DeriveAlgorithm, key: &CryptoKey, length: Option<u32>) -> Result<Vec<u8>> {
+	key.check(algorithm.name(), KeyUsage::DeriveBits)?;
+	match (algorithm, &key.material) {
+		(DeriveAlgorithm::Pbkdf2(params), KeyMaterial::Secret(secret)) => {
+			if params.iterations == 0 {
+				return Err(Error::new("Iterations must be greater than 0", None));
+			}
+			let mut bits = vec![0; byte_length(length)?];
+			with_digest!(params.hash, D => pbkdf2::pbkdf2_hmac::<D>(secret, &params.salt.0, params.iterations, &mut bits));
+			Ok(bits)
+		}
+		(DeriveAlgorithm::Hkdf(params), KeyMaterial::Secret(secret)) => {
+			let mut bits = vec![0; byte_length(length)?];
+			with_digest!(params.hash, D => Hkdf::<D>::new(Some(params.salt.0.as_slice()), secret).expand(&params.info.0, &mut bits))
+				.map_err(|_| Error::new("Length is too large for HKDF with this hash", None))?;
+			Ok(bits)
+		}
+		(DeriveAlgorithm::Ecdh(public), KeyMaterial::EcPrivate(private)) => {
+			let mut bits = ecdh(private, public)?;
+			if let Some(length) = length {
+				let bytes = (length as usize + 7) / 8;
+				if bytes > bits.len() {
+					return Err(Error::new("Length is larger than the shared secret", None));
+				}
+				bits.truncate(bytes);
+				if length % 8 != 0 {
+					bits[bytes - 1] &= 0xFF << (8 - length % 8);
+				}
+			}
+			Ok(bits)
+		}
+		_ => Err(Error::new("Key cannot be used to derive bits", None)),
+	}
+}
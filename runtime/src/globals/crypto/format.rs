@@ -0,0 +1,436 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use p256::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey};
+use rsa::{BigUint, RsaPrivateKey, RsaPublicKey};
+use rsa::traits::{PrivateKeyParts, PublicKeyParts};
+
+use ion::{Context, Error, ErrorKind, Result, Value};
+use ion::conversions::{FromValue, ToValue};
+use ion::typedarray::ArrayBuffer;
+
+use crate::globals::crypto::BufferSource;
+use crate::globals::crypto::algorithm::{AlgorithmName, Curve, DigestAlgorithm, ImportAlgorithm};
+use crate::globals::crypto::jwk::JsonWebKey;
+use crate::globals::crypto::key::{CryptoKey, EcPrivateKey, EcPublicKey, KeyAlgorithm, KeyMaterial, KeyUsage};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyFormat {
+	Raw,
+	Pkcs8,
+	Spki,
+	Jwk,
+}
+
+impl Display for KeyFormat {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			KeyFormat::Raw => "raw",
+			KeyFormat::Pkcs8 => "pkcs8",
+			KeyFormat::Spki => "spki",
+			KeyFormat::Jwk => "jwk",
+		})
+	}
+}
+
+impl FromStr for KeyFormat {
+	type Err = Error;
+
+	fn from_str(format: &str) -> Result<KeyFormat> {
+		match format {
+			"raw" => Ok(KeyFormat::Raw),
+			"pkcs8" => Ok(KeyFormat::Pkcs8),
+			"spki" => Ok(KeyFormat::Spki),
+			"jwk" => Ok(KeyFormat::Jwk),
+			_ => Err(Error::new(&format!("Invalid Key Format: {}", format), ErrorKind::Type)),
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for KeyFormat {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, strict: bool, _: ()) -> Result<KeyFormat>
+	where
+		'cx: 'v,
+	{
+		let format = String::from_value(cx, value, strict, ())?;
+		KeyFormat::from_str(&format)
+	}
+}
+
+/// Data of a key being imported, which is a [JsonWebKey] for the `jwk` format, and bytes for other formats.
+pub enum KeyData {
+	Bytes(Vec<u8>),
+	Jwk(JsonWebKey),
+}
+
+impl<'cx> FromValue<'cx> for KeyData {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, strict: bool, _: ()) -> Result<KeyData>
+	where
+		'cx: 'v,
+	{
+		if let Ok(BufferSource(bytes)) = BufferSource::from_value(cx, value, strict, ()) {
+			Ok(KeyData::Bytes(bytes))
+		} else {
+			Ok(KeyData::Jwk(JsonWebKey::from_value(cx, value, strict, ())?))
+		}
+	}
+}
+
+/// Key exported by `exportKey`, which is an [ArrayBuffer] for all formats other than `jwk`.
+pub enum ExportedKey {
+	Bytes(Vec<u8>),
+	Jwk(JsonWebKey),
+}
+
+impl<'cx> ToValue<'cx> for ExportedKey {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		match self {
+			ExportedKey::Bytes(bytes) => ArrayBuffer::from(bytes.clone()).to_value(cx, value),
+			ExportedKey::Jwk(jwk) => jwk.to_value(cx, value),
+		}
+	}
+}
+
+fn invalid_key_data(format: KeyFormat) -> Error {
+	Error::new(&format!("Invalid key data for format {}", format), None)
+}
+
+fn unsupported_format(format: KeyFormat, algorithm: AlgorithmName) -> Error {
+	Error::new(&format!("Format {} is not supported for {} keys", format, algorithm), None)
+}
+
+/// Returns the `alg` member of JSON Web Keys for an algorithm, if it has one.
+fn jwk_alg(algorithm: &KeyAlgorithm) -> Option<String> {
+	let hash = match algorithm {
+		KeyAlgorithm::Hmac { hash, .. } | KeyAlgorithm::Rsa { hash, .. } => Some(*hash),
+		_ => None,
+	};
+	let suffix = match hash {
+		Some(DigestAlgorithm::Sha1) => "1",
+		Some(DigestAlgorithm::Sha256) => "256",
+		Some(DigestAlgorithm::Sha384) => "384",
+		Some(DigestAlgorithm::Sha512) => "512",
+		Some(_) => return None,
+		None => "",
+	};
+
+	match algorithm {
+		KeyAlgorithm::Hmac { .. } => Some(format!("HS{}", suffix)),
+		KeyAlgorithm::Aes { name, length } => match name {
+			AlgorithmName::AesGcm => Some(format!("A{}GCM", length)),
+			_ => Some(format!("A{}CBC", length)),
+		},
+		KeyAlgorithm::Rsa { name, .. } => match name {
+			AlgorithmName::RsassaPkcs1 => Some(format!("RS{}", suffix)),
+			AlgorithmName::RsaPss => Some(format!("PS{}", suffix)),
+			_ if suffix == "1" => Some(String::from("RSA-OAEP")),
+			_ => Some(format!("RSA-OAEP-{}", suffix)),
+		},
+		KeyAlgorithm::Ed25519 => Some(String::from("EdDSA")),
+		KeyAlgorithm::Ec { .. } | KeyAlgorithm::Kdf(_) => None,
+	}
+}
+
+fn secret_bytes(format: KeyFormat, data: KeyData, usages: &[KeyUsage], extractable: bool) -> Result<(Vec<u8>, Option<JsonWebKey>)> {
+	match (format, data) {
+		(KeyFormat::Raw, KeyData::Bytes(bytes)) => Ok((bytes, None)),
+		(KeyFormat::Jwk, KeyData::Jwk(jwk)) => {
+			jwk.check("oct", usages, extractable)?;
+			Ok((JsonWebKey::decode(&jwk.k, "k")?, Some(jwk)))
+		}
+		(format, _) => Err(invalid_key_data(format)),
+	}
+}
+
+fn ec_public_key(curve: Curve, format: KeyFormat, bytes: &[u8]) -> Result<EcPublicKey> {
+	let key = match (curve, format) {
+		(Curve::P256, KeyFormat::Spki) => p256::PublicKey::from_public_key_der(bytes).ok().map(EcPublicKey::P256),
+		(Curve::P384, KeyFormat::Spki) => p384::PublicKey::from_public_key_der(bytes).ok().map(EcPublicKey::P384),
+		(Curve::P256, _) => p256::PublicKey::from_sec1_bytes(bytes).ok().map(EcPublicKey::P256),
+		(Curve::P384, _) => p384::PublicKey::from_sec1_bytes(bytes).ok().map(EcPublicKey::P384),
+	};
+	key.ok_or_else(|| invalid_key_data(format))
+}
+
+fn ec_private_key(curve: Curve, format: KeyFormat, bytes: &[u8]) -> Result<EcPrivateKey> {
+	let key = match (curve, format) {
+		(Curve::P256, KeyFormat::Pkcs8) => p256::SecretKey::from_pkcs8_der(bytes).ok().map(EcPrivateKey::P256),
+		(Curve::P384, KeyFormat::Pkcs8) => p384::SecretKey::from_pkcs8_der(bytes).ok().map(EcPrivateKey::P384),
+		(Curve::P256, _) => p256::SecretKey::from_slice(bytes).ok().map(EcPrivateKey::P256),
+		(Curve::P384, _) => p384::SecretKey::from_slice(bytes).ok().map(EcPrivateKey::P384),
+	};
+	key.ok_or_else(|| invalid_key_data(format))
+}
+
+fn ec_material(curve: Curve, format: KeyFormat, data: KeyData, usages: &[KeyUsage], extractable: bool) -> Result<KeyMaterial> {
+	match (format, data) {
+		(KeyFormat::Raw | KeyFormat::Spki, KeyData::Bytes(bytes)) => Ok(KeyMaterial::EcPublic(ec_public_key(curve, format, &bytes)?)),
+		(KeyFormat::Pkcs8, KeyData::Bytes(bytes)) => Ok(KeyMaterial::EcPrivate(ec_private_key(curve, format, &bytes)?)),
+		(KeyFormat::Jwk, KeyData::Jwk(jwk)) => {
+			jwk.check("EC", usages, extractable)?;
+			if jwk.crv.as_deref() != Some(&curve.to_string()) {
+				return Err(Error::new(&format!("Expected JSON Web Key with curve {}", curve), ErrorKind::Syntax));
+			}
+
+			let mut point = vec![0x04];
+			point.extend(JsonWebKey::decode(&jwk.x, "x")?);
+			point.extend(JsonWebKey::decode(&jwk.y, "y")?);
+			let public = ec_public_key(curve, format, &point)?;
+			if jwk.d.is_none() {
+				return Ok(KeyMaterial::EcPublic(public));
+			}
+
+			let private = ec_private_key(curve, format, &JsonWebKey::decode(&jwk.d, "d")?)?;
+			if private.public_key().to_sec1() != public.to_sec1() {
+				return Err(invalid_key_data(format));
+			}
+			Ok(KeyMaterial::EcPrivate(private))
+		}
+		(format, _) => Err(invalid_key_data(format)),
+	}
+}
+
+fn ed25519_material(format: KeyFormat, data: KeyData, usages: &[KeyUsage], extractable: bool) -> Result<KeyMaterial> {
+	let material = match (format, data) {
+		(KeyFormat::Raw, KeyData::Bytes(bytes)) => bytes
+			.as_slice()
+			.try_into()
+			.ok()
+			.and_then(|bytes| VerifyingKey::from_bytes(bytes).ok())
+			.map(KeyMaterial::Ed25519Public),
+		(KeyFormat::Spki, KeyData::Bytes(bytes)) => VerifyingKey::from_public_key_der(&bytes).ok().map(KeyMaterial::Ed25519Public),
+		(KeyFormat::Pkcs8, KeyData::Bytes(bytes)) => SigningKey::from_pkcs8_der(&bytes).ok().map(KeyMaterial::Ed25519Private),
+		(KeyFormat::Jwk, KeyData::Jwk(jwk)) => {
+			jwk.check("OKP", usages, extractable)?;
+			jwk.check_alg("EdDSA")?;
+			if jwk.crv.as_deref() != Some("Ed25519") {
+				return Err(Error::new("Expected JSON Web Key with curve Ed25519", ErrorKind::Syntax));
+			}
+
+			let public: [u8; 32] = JsonWebKey::decode(&jwk.x, "x")?.try_into().map_err(|_| invalid_key_data(format))?;
+			let public = VerifyingKey::from_bytes(&public).map_err(|_| invalid_key_data(format))?;
+			if jwk.d.is_none() {
+				return Ok(KeyMaterial::Ed25519Public(public));
+			}
+
+			let private: [u8; 32] = JsonWebKey::decode(&jwk.d, "d")?.try_into().map_err(|_| invalid_key_data(format))?;
+			let private = SigningKey::from_bytes(&private);
+			(private.verifying_key() == public).then_some(KeyMaterial::Ed25519Private(private))
+		}
+		(format, _) => return Err(invalid_key_data(format)),
+	};
+	material.ok_or_else(|| invalid_key_data(format))
+}
+
+fn rsa_material(format: KeyFormat, data: KeyData, usages: &[KeyUsage], extractable: bool, alg: Option<String>) -> Result<KeyMaterial> {
+	let material = match (format, data) {
+		(KeyFormat::Spki, KeyData::Bytes(bytes)) => RsaPublicKey::from_public_key_der(&bytes).ok().map(KeyMaterial::RsaPublic),
+		(KeyFormat::Pkcs8, KeyData::Bytes(bytes)) => RsaPrivateKey::from_pkcs8_der(&bytes).ok().map(KeyMaterial::RsaPrivate),
+		(KeyFormat::Jwk, KeyData::Jwk(jwk)) => {
+			jwk.check("RSA", usages, extractable)?;
+			if let Some(alg) = alg {
+				jwk.check_alg(&alg)?;
+			}
+
+			let integer = |member: &Option<String>, name: &str| Ok::<_, Error>(BigUint::from_bytes_be(&JsonWebKey::decode(member, name)?));
+			let n = integer(&jwk.n, "n")?;
+			let e = integer(&jwk.e, "e")?;
+			if jwk.d.is_none() {
+				RsaPublicKey::new(n, e).ok().map(KeyMaterial::RsaPublic)
+			} else {
+				let d = integer(&jwk.d, "d")?;
+				let primes = vec![integer(&jwk.p, "p")?, integer(&jwk.q, "q")?];
+				RsaPrivateKey::from_components(n, e, d, primes).ok().map(KeyMaterial::RsaPrivate)
+			}
+		}
+		(format, _) => return Err(invalid_key_data(format)),
+	};
+	material.ok_or_else(|| invalid_key_data(format))
+}
+
+/// Imports a key from the key data in a format.
+pub fn import_key(format: KeyFormat, data: KeyData, algorithm: ImportAlgorithm, extractable: bool, usages: &[KeyUsage]) -> Result<CryptoKey> {
+	match algorithm {
+		ImportAlgorithm::Hmac(params) => {
+			if !matches!(format, KeyFormat::Raw | KeyFormat::Jwk) {
+				return Err(unsupported_format(format, AlgorithmName::Hmac));
+			}
+			let (bytes, jwk) = secret_bytes(format, data, usages, extractable)?;
+			let bits = bytes.len() as u32 * 8;
+			let length = params.length.unwrap_or(bits);
+			if bits == 0 || length > bits || length <= bits - 8 {
+				return Err(invalid_key_data(format));
+			}
+
+			let algorithm = KeyAlgorithm::Hmac { hash: params.hash, length };
+			if let (Some(jwk), Some(alg)) = (jwk, jwk_alg(&algorithm)) {
+				jwk.check_alg(&alg)?;
+			}
+			CryptoKey::new(algorithm, KeyMaterial::Secret(bytes), extractable, usages)
+		}
+		ImportAlgorithm::Aes(name) => {
+			if !matches!(format, KeyFormat::Raw | KeyFormat::Jwk) {
+				return Err(unsupported_format(format, name));
+			}
+			let (bytes, jwk) = secret_bytes(format, data, usages, extractable)?;
+			if !matches!(bytes.len(), 16 | 24 | 32) {
+				return Err(Error::new("AES keys must be 128, 192 or 256 bits", None));
+			}
+
+			let algorithm = KeyAlgorithm::Aes { name, length: bytes.len() as u16 * 8 };
+			if let (Some(jwk), Some(alg)) = (jwk, jwk_alg(&algorithm)) {
+				jwk.check_alg(&alg)?;
+			}
+			CryptoKey::new(algorithm, KeyMaterial::Secret(bytes), extractable, usages)
+		}
+		ImportAlgorithm::Kdf(name) => {
+			if format != KeyFormat::Raw {
+				return Err(unsupported_format(format, name));
+			}
+			if extractable {
+				return Err(Error::new(&format!("{} keys cannot be extractable", name), ErrorKind::Syntax));
+			}
+			let (bytes, _) = secret_bytes(format, data, usages, extractable)?;
+			CryptoKey::new(KeyAlgorithm::Kdf(name), KeyMaterial::Secret(bytes), extractable, usages)
+		}
+		ImportAlgorithm::Ec(name, curve) => {
+			let material = ec_material(curve, format, data, usages, extractable)?;
+			CryptoKey::new(KeyAlgorithm::Ec { name, curve }, material, extractable, usages)
+		}
+		ImportAlgorithm::Ed25519 => {
+			let material = ed25519_material(format, data, usages, extractable)?;
+			CryptoKey::new(KeyAlgorithm::Ed25519, material, extractable, usages)
+		}
+		ImportAlgorithm::Rsa(name, hash) => {
+			if format == KeyFormat::Raw {
+				return Err(unsupported_format(format, name));
+			}
+
+			let alg = jwk_alg(&KeyAlgorithm::Rsa {
+				name,
+				modulus_length: 0,
+				public_exponent: Vec::new(),
+				hash,
+			});
+			let material = rsa_material(format, data, usages, extractable, alg)?;
+			let public = match &material {
+				KeyMaterial::RsaPrivate(key) => key.to_public_key(),
+				KeyMaterial::RsaPublic(key) => key.clone(),
+				_ => unreachable!(),
+			};
+			let algorithm = KeyAlgorithm::Rsa {
+				name,
+				modulus_length: public.n().bits() as u32,
+				public_exponent: public.e().to_bytes_be(),
+				hash,
+			};
+			CryptoKey::new(algorithm, material, extractable, usages)
+		}
+	}
+}
+
+/// Exports the key in a format.
+pub fn export_key(format: KeyFormat, key: &CryptoKey) -> Result<ExportedKey> {
+	if !key.extractable {
+		return Err(Error::new("Key is not extractable", None));
+	}
+
+	let exported = match (format, &key.material) {
+		(KeyFormat::Raw, KeyMaterial::Secret(bytes)) if !matches!(key.algorithm, KeyAlgorithm::Kdf(_)) => Some(bytes.clone()),
+		(KeyFormat::Raw, KeyMaterial::EcPublic(public)) => Some(public.to_sec1()),
+		(KeyFormat::Raw, KeyMaterial::Ed25519Public(public)) => Some(public.to_bytes().to_vec()),
+		(KeyFormat::Spki, KeyMaterial::EcPublic(EcPublicKey::P256(public))) => public.to_public_key_der().ok().map(|der| der.as_bytes().to_vec()),
+		(KeyFormat::Spki, KeyMaterial::EcPublic(EcPublicKey::P384(public))) => public.to_public_key_der().ok().map(|der| der.as_bytes().to_vec()),
+		(KeyFormat::Spki, KeyMaterial::Ed25519Public(public)) => public.to_public_key_der().ok().map(|der| der.as_bytes().to_vec()),
+		(KeyFormat::Spki, KeyMaterial::RsaPublic(public)) => public.to_public_key_der().ok().map(|der| der.as_bytes().to_vec()),
+		(KeyFormat::Pkcs8, KeyMaterial::EcPrivate(EcPrivateKey::P256(private))) => private.to_pkcs8_der().ok().map(|der| der.as_bytes().to_vec()),
+		(KeyFormat::Pkcs8, KeyMaterial::EcPrivate(EcPrivateKey::P384(private))) => private.to_pkcs8_der().ok().map(|der| der.as_bytes().to_vec()),
+		(KeyFormat::Pkcs8, KeyMaterial::Ed25519Private(private)) => private.to_pkcs8_der().ok().map(|der| der.as_bytes().to_vec()),
+		(KeyFormat::Pkcs8, KeyMaterial::RsaPrivate(private)) => private.to_pkcs8_der().ok().map(|der| der.as_bytes().to_vec()),
+		(KeyFormat::Jwk, _) => return export_jwk(key).map(ExportedKey::Jwk),
+		_ => return Err(unsupported_format(format, key.algorithm.name())),
+	};
+	exported
+		.map(ExportedKey::Bytes)
+		.ok_or_else(|| Error::new(&format!("Failed to export key in format {}", format), None))
+}
+
+fn export_jwk(key: &CryptoKey) -> Result<JsonWebKey> {
+	let mut jwk = JsonWebKey {
+		alg: jwk_alg(&key.algorithm),
+		key_ops: Some(key.usages.clone()),
+		ext: Some(key.extractable),
+		..JsonWebKey::default()
+	};
+
+	match &key.material {
+		KeyMaterial::Secret(_) if matches!(key.algorithm, KeyAlgorithm::Kdf(_)) => {
+			return Err(unsupported_format(KeyFormat::Jwk, key.algorithm.name()));
+		}
+		KeyMaterial::Secret(bytes) => {
+			jwk.kty = Some(String::from("oct"));
+			jwk.k = JsonWebKey::encode(bytes);
+		}
+		KeyMaterial::EcPrivate(_) | KeyMaterial::EcPublic(_) => {
+			let (public, private) = match &key.material {
+				KeyMaterial::EcPrivate(private) => (private.public_key(), Some(private.to_bytes())),
+				KeyMaterial::EcPublic(public) => (public.clone(), None),
+				_ => unreachable!(),
+			};
+			let point = public.to_sec1();
+			let size = (point.len() - 1) / 2;
+
+			jwk.kty = Some(String::from("EC"));
+			jwk.crv = Some(public.curve().to_string());
+			jwk.x = JsonWebKey::encode(&point[1..=size]);
+			jwk.y = JsonWebKey::encode(&point[size + 1..]);
+			jwk.d = private.as_deref().and_then(JsonWebKey::encode);
+		}
+		KeyMaterial::Ed25519Private(_) | KeyMaterial::Ed25519Public(_) => {
+			let (public, private) = match &key.material {
+				KeyMaterial::Ed25519Private(private) => (private.verifying_key(), Some(private.to_bytes())),
+				KeyMaterial::Ed25519Public(public) => (*public, None),
+				_ => unreachable!(),
+			};
+
+			jwk.kty = Some(String::from("OKP"));
+			jwk.crv = Some(String::from("Ed25519"));
+			jwk.x = JsonWebKey::encode(public.as_bytes());
+			jwk.d = private.as_ref().and_then(|private| JsonWebKey::encode(private));
+		}
+		KeyMaterial::RsaPrivate(private) => {
+			jwk.kty = Some(String::from("RSA"));
+			jwk.n = JsonWebKey::encode(&private.n().to_bytes_be());
+			jwk.e = JsonWebKey::encode(&private.e().to_bytes_be());
+			jwk.d = JsonWebKey::encode(&private.d().to_bytes_be());
+
+			let primes = private.primes();
+			if primes.len() != 2 {
+				return Err(Error::new("Keys with more than 2 primes cannot be exported", None));
+			}
+			jwk.p = JsonWebKey::encode(&primes[0].to_bytes_be());
+			jwk.q = JsonWebKey::encode(&primes[1].to_bytes_be());
+			jwk.dp = private.dp().and_then(|dp| JsonWebKey::encode(&dp.to_bytes_be()));
+			jwk.dq = private.dq().and_then(|dq| JsonWebKey::encode(&dq.to_bytes_be()));
+			jwk.qi = private.crt_coefficient().and_then(|qi| JsonWebKey::encode(&qi.to_bytes_be()));
+		}
+		KeyMaterial::RsaPublic(public) => {
+			jwk.kty = Some(String::from("RSA"));
+			jwk.n = JsonWebKey::encode(&public.n().to_bytes_be());
+			jwk.e = JsonWebKey::encode(&public.e().to_bytes_be());
+		}
+	}
+	Ok(jwk)
+}
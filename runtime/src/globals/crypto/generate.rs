@@ -0,0 +1,95 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::ops::RangeInclusive;
+
+use ed25519_dalek::SigningKey;
+use rsa::{BigUint, RsaPrivateKey};
+
+use ion::{Error, ErrorKind, Result};
+
+use crate::globals::crypto::algorithm::{Curve, KeyGenAlgorithm};
+use crate::globals::crypto::crypto::{fill_random, SystemRng};
+use crate::globals::crypto::key::{allowed_usages, CryptoKey, CryptoKeyPair, EcPrivateKey, GeneratedKey, KeyAlgorithm, KeyMaterial, KeyType, KeyUsage};
+
+/// Range of supported RSA modulus lengths in bits, which bounds the time taken to generate a key.
+const RSA_MODULUS_LENGTHS: RangeInclusive<u32> = 1024..=16384;
+
+fn random_bytes(length: usize) -> Result<Vec<u8>> {
+	let mut bytes = vec![0; length];
+	fill_random(&mut bytes)?;
+	Ok(bytes)
+}
+
+/// Creates a key pair, where the public key receives the usages allowed for public keys, and the private key receives the rest.
+/// Public keys are always extractable.
+fn key_pair(algorithm: KeyAlgorithm, private: KeyMaterial, public: KeyMaterial, extractable: bool, usages: &[KeyUsage]) -> Result<CryptoKeyPair> {
+	let allowed = allowed_usages(algorithm.name(), KeyType::Public);
+	let (public_usages, private_usages): (Vec<_>, Vec<_>) = usages.iter().copied().partition(|usage| allowed.contains(usage));
+	Ok(CryptoKeyPair {
+		public_key: CryptoKey::new(algorithm.clone(), public, true, &public_usages)?,
+		private_key: CryptoKey::new(algorithm, private, extractable, &private_usages)?,
+	})
+}
+
+/// Generates a new key, or a key pair for asymmetric algorithms.
+pub fn generate_key(algorithm: KeyGenAlgorithm, extractable: bool, usages: &[KeyUsage]) -> Result<GeneratedKey> {
+	match algorithm {
+		KeyGenAlgorithm::Hmac(params) => {
+			let length = params.length.unwrap_or_else(|| params.hash.block_size());
+			if length == 0 {
+				return Err(Error::new("HMAC keys must not be empty", None));
+			}
+			let secret = random_bytes((length as usize + 7) / 8)?;
+			let algorithm = KeyAlgorithm::Hmac { hash: params.hash, length };
+			CryptoKey::new(algorithm, KeyMaterial::Secret(secret), extractable, usages).map(GeneratedKey::Key)
+		}
+		KeyGenAlgorithm::Aes(name, length) => {
+			if !matches!(length, 128 | 192 | 256) {
+				return Err(Error::new("AES keys must be 128, 192 or 256 bits", None));
+			}
+			let secret = random_bytes(length as usize / 8)?;
+			CryptoKey::new(KeyAlgorithm::Aes { name, length }, KeyMaterial::Secret(secret), extractable, usages).map(GeneratedKey::Key)
+		}
+		KeyGenAlgorithm::Ec(name, curve) => {
+			let private = match curve {
+				Curve::P256 => EcPrivateKey::P256(p256::SecretKey::random(&mut SystemRng)),
+				Curve::P384 => EcPrivateKey::P384(p384::SecretKey::random(&mut SystemRng)),
+			};
+			let public = KeyMaterial::EcPublic(private.public_key());
+			let algorithm = KeyAlgorithm::Ec { name, curve };
+			key_pair(algorithm, KeyMaterial::EcPrivate(private), public, extractable, usages).map(GeneratedKey::Pair)
+		}
+		KeyGenAlgorithm::Ed25519 => {
+			let private = SigningKey::generate(&mut SystemRng);
+			let public = KeyMaterial::Ed25519Public(private.verifying_key());
+			key_pair(KeyAlgorithm::Ed25519, KeyMaterial::Ed25519Private(private), public, extractable, usages).map(GeneratedKey::Pair)
+		}
+		KeyGenAlgorithm::Rsa(name, params) => {
+			if !RSA_MODULUS_LENGTHS.contains(&params.modulus_length) {
+				return Err(Error::new(
+					&format!(
+						"RSA modulus length must be between {} and {} bits",
+						RSA_MODULUS_LENGTHS.start(),
+						RSA_MODULUS_LENGTHS.end()
+					),
+					ErrorKind::Range,
+				));
+			}
+			let exponent = BigUint::from_bytes_be(&params.public_exponent.0);
+			let private = RsaPrivateKey::new_with_exp(&mut SystemRng, params.modulus_length as usize, &exponent)
+				.map_err(|error| Error::new(&format!("Failed to generate RSA key: {}", error), None))?;
+			let public = KeyMaterial::RsaPublic(private.to_public_key());
+			let algorithm = KeyAlgorithm::Rsa {
+				name,
+				modulus_length: params.modulus_length,
+				public_exponent: params.public_exponent.0,
+				hash: params.hash,
+			};
+			key_pair(algorithm, KeyMaterial::RsaPrivate(private), public, extractable, usages).map(GeneratedKey::Pair)
+		}
+	}
+}
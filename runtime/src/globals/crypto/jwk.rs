@@ -0,0 +1,109 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ion::{Context, Error, ErrorKind, Object, Result, Value};
+use ion::conversions::ToValue;
+
+use crate::globals::crypto::key::KeyUsage;
+
+/// Represents a JSON Web Key, as defined in [RFC 7517](https://datatracker.ietf.org/doc/html/rfc7517).
+/// Binary members are encoded as unpadded base64url.
+#[derive(Clone, Debug, Default, FromValue)]
+pub struct JsonWebKey {
+	pub kty: Option<String>,
+	#[ion(name = "use")]
+	pub key_use: Option<String>,
+	#[ion(name = "key_ops")]
+	pub key_ops: Option<Vec<KeyUsage>>,
+	pub alg: Option<String>,
+	pub ext: Option<bool>,
+
+	pub crv: Option<String>,
+	pub x: Option<String>,
+	pub y: Option<String>,
+	pub d: Option<String>,
+	pub n: Option<String>,
+	pub e: Option<String>,
+	pub p: Option<String>,
+	pub q: Option<String>,
+	pub dp: Option<String>,
+	pub dq: Option<String>,
+	pub qi: Option<String>,
+	pub k: Option<String>,
+}
+
+impl JsonWebKey {
+	/// Decodes a required base64url member of the key.
+	pub fn decode(member: &Option<String>, name: &str) -> Result<Vec<u8>> {
+		let member = member
+			.as_ref()
+			.ok_or_else(|| Error::new(&format!("JSON Web Key is missing member {}", name), ErrorKind::Syntax))?;
+		base64_url::decode(member).map_err(|_| Error::new(&format!("JSON Web Key member {} is not valid base64url", name), ErrorKind::Syntax))
+	}
+
+	pub fn encode(bytes: &[u8]) -> Option<String> {
+		Some(base64_url::encode(bytes))
+	}
+
+	/// Checks that the key type matches, and that the key is compatible with the usages and extractability.
+	pub fn check(&self, kty: &str, usages: &[KeyUsage], extractable: bool) -> Result<()> {
+		if self.kty.as_deref() != Some(kty) {
+			return Err(Error::new(&format!("Expected JSON Web Key of type {}", kty), ErrorKind::Syntax));
+		}
+		if let Some(key_ops) = &self.key_ops {
+			if let Some(usage) = usages.iter().find(|usage| !key_ops.contains(usage)) {
+				return Err(Error::new(&format!("JSON Web Key does not allow usage {}", usage), ErrorKind::Syntax));
+			}
+		}
+		if self.ext == Some(false) && extractable {
+			return Err(Error::new("JSON Web Key is not extractable", ErrorKind::Syntax));
+		}
+		Ok(())
+	}
+
+	/// Checks that the `alg` member, if present, matches the expected algorithm.
+	pub fn check_alg(&self, alg: &str) -> Result<()> {
+		match &self.alg {
+			Some(actual) if actual != alg => Err(Error::new(&format!("Expected JSON Web Key algorithm {}", alg), ErrorKind::Syntax)),
+			_ => Ok(()),
+		}
+	}
+}
+
+impl<'cx> ToValue<'cx> for JsonWebKey {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		let members = [
+			("kty", &self.kty),
+			("use", &self.key_use),
+			("alg", &self.alg),
+			("crv", &self.crv),
+			("x", &self.x),
+			("y", &self.y),
+			("d", &self.d),
+			("n", &self.n),
+			("e", &self.e),
+			("p", &self.p),
+			("q", &self.q),
+			("dp", &self.dp),
+			("dq", &self.dq),
+			("qi", &self.qi),
+			("k", &self.k),
+		];
+		for (key, member) in members {
+			if let Some(member) = member {
+				object.set_as(cx, key, member);
+			}
+		}
+		if let Some(key_ops) = &self.key_ops {
+			object.set_as(cx, "key_ops", key_ops);
+		}
+		if let Some(ext) = &self.ext {
+			object.set_as(cx, "ext", ext);
+		}
+		object.to_value(cx, value);
+	}
+}
@@ -0,0 +1,369 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use rsa::{RsaPrivateKey, RsaPublicKey};
+
+pub use class::CryptoKey;
+use ion::{Context, Error, ErrorKind, Object, Result, Value};
+use ion::conversions::{FromValue, ToValue};
+use ion::typedarray::Uint8Array;
+
+use crate::globals::crypto::algorithm::{AlgorithmName, Curve, DigestAlgorithm};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyType {
+	Secret,
+	Private,
+	Public,
+}
+
+impl Display for KeyType {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			KeyType::Secret => "secret",
+			KeyType::Private => "private",
+			KeyType::Public => "public",
+		})
+	}
+}
+
+impl<'cx> ToValue<'cx> for KeyType {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		self.to_string().to_value(cx, value);
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyUsage {
+	Encrypt,
+	Decrypt,
+	Sign,
+	Verify,
+	DeriveKey,
+	DeriveBits,
+	WrapKey,
+	UnwrapKey,
+}
+
+impl Display for KeyUsage {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		f.write_str(match self {
+			KeyUsage::Encrypt => "encrypt",
+			KeyUsage::Decrypt => "decrypt",
+			KeyUsage::Sign => "sign",
+			KeyUsage::Verify => "verify",
+			KeyUsage::DeriveKey => "deriveKey",
+			KeyUsage::DeriveBits => "deriveBits",
+			KeyUsage::WrapKey => "wrapKey",
+			KeyUsage::UnwrapKey => "unwrapKey",
+		})
+	}
+}
+
+impl FromStr for KeyUsage {
+	type Err = Error;
+
+	fn from_str(usage: &str) -> Result<KeyUsage> {
+		match usage {
+			"encrypt" => Ok(KeyUsage::Encrypt),
+			"decrypt" => Ok(KeyUsage::Decrypt),
+			"sign" => Ok(KeyUsage::Sign),
+			"verify" => Ok(KeyUsage::Verify),
+			"deriveKey" => Ok(KeyUsage::DeriveKey),
+			"deriveBits" => Ok(KeyUsage::DeriveBits),
+			"wrapKey" => Ok(KeyUsage::WrapKey),
+			"unwrapKey" => Ok(KeyUsage::UnwrapKey),
+			_ => Err(Error::new(&format!("Invalid Key Usage: {}", usage), ErrorKind::Type)),
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for KeyUsage {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, strict: bool, _: ()) -> Result<KeyUsage>
+	where
+		'cx: 'v,
+	{
+		let usage = String::from_value(cx, value, strict, ())?;
+		KeyUsage::from_str(&usage)
+	}
+}
+
+impl<'cx> ToValue<'cx> for KeyUsage {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		self.to_string().to_value(cx, value);
+	}
+}
+
+/// Algorithm of a [CryptoKey], with the parameters it was generated or imported with.
+#[derive(Clone, Debug)]
+pub enum KeyAlgorithm {
+	Hmac {
+		hash: DigestAlgorithm,
+		length: u32,
+	},
+	Aes {
+		name: AlgorithmName,
+		length: u16,
+	},
+	Ec {
+		name: AlgorithmName,
+		curve: Curve,
+	},
+	Ed25519,
+	Rsa {
+		name: AlgorithmName,
+		modulus_length: u32,
+		public_exponent: Vec<u8>,
+		hash: DigestAlgorithm,
+	},
+	Kdf(AlgorithmName),
+}
+
+impl KeyAlgorithm {
+	pub fn name(&self) -> AlgorithmName {
+		match self {
+			KeyAlgorithm::Hmac { .. } => AlgorithmName::Hmac,
+			KeyAlgorithm::Aes { name, .. } | KeyAlgorithm::Ec { name, .. } | KeyAlgorithm::Rsa { name, .. } | KeyAlgorithm::Kdf(name) => *name,
+			KeyAlgorithm::Ed25519 => AlgorithmName::Ed25519,
+		}
+	}
+}
+
+impl<'cx> ToValue<'cx> for KeyAlgorithm {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "name", &self.name().to_string());
+		match self {
+			KeyAlgorithm::Hmac { hash, length } => {
+				object.set_as(cx, "hash", hash);
+				object.set_as(cx, "length", length);
+			}
+			KeyAlgorithm::Aes { length, .. } => {
+				object.set_as(cx, "length", length);
+			}
+			KeyAlgorithm::Ec { curve, .. } => {
+				object.set_as(cx, "namedCurve", curve);
+			}
+			KeyAlgorithm::Rsa {
+				modulus_length, public_exponent, hash, ..
+			} => {
+				object.set_as(cx, "modulusLength", modulus_length);
+				object.set_as(cx, "publicExponent", &Uint8Array::from(public_exponent.clone()));
+				object.set_as(cx, "hash", hash);
+			}
+			KeyAlgorithm::Ed25519 | KeyAlgorithm::Kdf(_) => {}
+		}
+		object.to_value(cx, value);
+	}
+}
+
+#[derive(Clone, Debug)]
+pub enum EcPrivateKey {
+	P256(p256::SecretKey),
+	P384(p384::SecretKey),
+}
+
+#[derive(Clone, Debug)]
+pub enum EcPublicKey {
+	P256(p256::PublicKey),
+	P384(p384::PublicKey),
+}
+
+impl EcPrivateKey {
+	pub fn curve(&self) -> Curve {
+		match self {
+			EcPrivateKey::P256(_) => Curve::P256,
+			EcPrivateKey::P384(_) => Curve::P384,
+		}
+	}
+
+	pub fn public_key(&self) -> EcPublicKey {
+		match self {
+			EcPrivateKey::P256(key) => EcPublicKey::P256(key.public_key()),
+			EcPrivateKey::P384(key) => EcPublicKey::P384(key.public_key()),
+		}
+	}
+
+	/// Returns the private scalar of the key.
+	pub fn to_bytes(&self) -> Vec<u8> {
+		match self {
+			EcPrivateKey::P256(key) => key.to_bytes().to_vec(),
+			EcPrivateKey::P384(key) => key.to_bytes().to_vec(),
+		}
+	}
+}
+
+impl EcPublicKey {
+	pub fn curve(&self) -> Curve {
+		match self {
+			EcPublicKey::P256(_) => Curve::P256,
+			EcPublicKey::P384(_) => Curve::P384,
+		}
+	}
+
+	/// Returns the uncompressed SEC1 encoding of the public point.
+	pub fn to_sec1(&self) -> Vec<u8> {
+		match self {
+			EcPublicKey::P256(key) => key.to_encoded_point(false).as_bytes().to_vec(),
+			EcPublicKey::P384(key) => key.to_encoded_point(false).as_bytes().to_vec(),
+		}
+	}
+}
+
+/// Material of a [CryptoKey], which determines its type.
+#[derive(Clone, Debug)]
+pub enum KeyMaterial {
+	Secret(Vec<u8>),
+	EcPrivate(EcPrivateKey),
+	EcPublic(EcPublicKey),
+	Ed25519Private(ed25519_dalek::SigningKey),
+	Ed25519Public(ed25519_dalek::VerifyingKey),
+	RsaPrivate(RsaPrivateKey),
+	RsaPublic(RsaPublicKey),
+}
+
+impl KeyMaterial {
+	pub fn key_type(&self) -> KeyType {
+		match self {
+			KeyMaterial::Secret(_) => KeyType::Secret,
+			KeyMaterial::EcPrivate(_) | KeyMaterial::Ed25519Private(_) | KeyMaterial::RsaPrivate(_) => KeyType::Private,
+			KeyMaterial::EcPublic(_) | KeyMaterial::Ed25519Public(_) | KeyMaterial::RsaPublic(_) => KeyType::Public,
+		}
+	}
+}
+
+/// Returns the usages which are allowed for a key of an algorithm and type.
+pub(crate) fn allowed_usages(algorithm: AlgorithmName, kind: KeyType) -> &'static [KeyUsage] {
+	use AlgorithmName as AN;
+	use KeyUsage as KU;
+	match (algorithm, kind) {
+		(AN::Hmac, _) => &[KU::Sign, KU::Verify],
+		(AN::Ecdsa | AN::Ed25519 | AN::RsassaPkcs1 | AN::RsaPss, KeyType::Private) => &[KU::Sign],
+		(AN::Ecdsa | AN::Ed25519 | AN::RsassaPkcs1 | AN::RsaPss, _) => &[KU::Verify],
+		(AN::RsaOaep, KeyType::Private) => &[KU::Decrypt, KU::UnwrapKey],
+		(AN::RsaOaep, _) => &[KU::Encrypt, KU::WrapKey],
+		(AN::AesGcm | AN::AesCbc, _) => &[KU::Encrypt, KU::Decrypt, KU::WrapKey, KU::UnwrapKey],
+		(AN::Ecdh, KeyType::Private) => &[KU::DeriveKey, KU::DeriveBits],
+		(AN::Ecdh, _) => &[],
+		(AN::Pbkdf2 | AN::Hkdf, _) => &[KU::DeriveKey, KU::DeriveBits],
+	}
+}
+
+#[js_class]
+mod class {
+	use ion::{Error, ErrorKind, Result};
+
+	use crate::globals::crypto::algorithm::AlgorithmName;
+	use crate::globals::crypto::key::{allowed_usages, KeyAlgorithm, KeyMaterial, KeyType, KeyUsage};
+
+	#[derive(Clone, Debug)]
+	#[ion(from_value, to_value, no_constructor)]
+	pub struct CryptoKey {
+		pub(crate) algorithm: KeyAlgorithm,
+		pub(crate) material: KeyMaterial,
+		pub(crate) extractable: bool,
+		pub(crate) usages: Vec<KeyUsage>,
+	}
+
+	impl CryptoKey {
+		/// Creates a key, after checking that the usages are allowed for its algorithm and type.
+		#[ion(skip)]
+		pub fn new(algorithm: KeyAlgorithm, material: KeyMaterial, extractable: bool, usages: &[KeyUsage]) -> Result<CryptoKey> {
+			let allowed = allowed_usages(algorithm.name(), material.key_type());
+			if let Some(usage) = usages.iter().find(|usage| !allowed.contains(usage)) {
+				return Err(Error::new(
+					&format!("Usage {} is not allowed for {} {} keys", usage, algorithm.name(), material.key_type()),
+					ErrorKind::Syntax,
+				));
+			}
+			if usages.is_empty() && material.key_type() != KeyType::Public {
+				return Err(Error::new("Usages must not be empty for secret and private keys", ErrorKind::Syntax));
+			}
+
+			let mut key_usages = Vec::with_capacity(usages.len());
+			for usage in usages {
+				if !key_usages.contains(usage) {
+					key_usages.push(*usage);
+				}
+			}
+			Ok(CryptoKey {
+				algorithm,
+				material,
+				extractable,
+				usages: key_usages,
+			})
+		}
+
+		/// Checks that the key can be used for an operation with an algorithm.
+		#[ion(skip)]
+		pub fn check(&self, algorithm: AlgorithmName, usage: KeyUsage) -> Result<()> {
+			if self.algorithm.name() != algorithm {
+				Err(Error::new(&format!("Key is for {}, not {}", self.algorithm.name(), algorithm), None))
+			} else if !self.usages.contains(&usage) {
+				Err(Error::new(&format!("Key does not support the {} operation", usage), None))
+			} else {
+				Ok(())
+			}
+		}
+
+		#[ion(get)]
+		pub fn get_type(&self) -> KeyType {
+			self.material.key_type()
+		}
+
+		#[ion(get)]
+		pub fn get_extractable(&self) -> bool {
+			self.extractable
+		}
+
+		#[ion(get)]
+		pub fn get_algorithm(&self) -> KeyAlgorithm {
+			self.algorithm.clone()
+		}
+
+		#[ion(get)]
+		pub fn get_usages(&self) -> Vec<KeyUsage> {
+			self.usages.clone()
+		}
+	}
+}
+
+/// Pair of keys created by `generateKey` for asymmetric algorithms.
+pub struct CryptoKeyPair {
+	pub public_key: CryptoKey,
+	pub private_key: CryptoKey,
+}
+
+impl<'cx> ToValue<'cx> for CryptoKeyPair {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "publicKey", &self.public_key);
+		object.set_as(cx, "privateKey", &self.private_key);
+		object.to_value(cx, value);
+	}
+}
+
+/// Result of `generateKey`, which is a single key for symmetric algorithms.
+pub enum GeneratedKey {
+	Key(CryptoKey),
+	Pair(CryptoKeyPair),
+}
+
+impl<'cx> ToValue<'cx> for GeneratedKey {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		match self {
+			GeneratedKey::Key(key) => key.to_value(cx, value),
+			GeneratedKey::Pair(pair) => pair.to_value(cx, value),
+		}
+	}
+}
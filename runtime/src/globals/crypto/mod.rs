@@ -0,0 +1,75 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsapi::Heap;
+use mozjs::typedarray::{ArrayBuffer, ArrayBufferView};
+
+pub use algorithm::DigestAlgorithm;
+pub use crypto::Crypto;
+pub use key::CryptoKey;
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result, Value};
+use ion::conversions::FromValue;
+use ion::flags::PropertyFlags;
+pub use subtle::SubtleCrypto;
+
+mod algorithm;
+mod cipher;
+#[allow(clippy::module_inception)]
+mod crypto;
+mod derive;
+mod format;
+mod generate;
+mod jwk;
+mod key;
+mod sign;
+mod subtle;
+
+/// Represents the bytes of an [ArrayBuffer] or [ArrayBufferView], which are copied when converted.
+#[derive(Clone, Debug, Default)]
+pub struct BufferSource(pub Vec<u8>);
+
+impl<'cx> FromValue<'cx> for BufferSource {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, _: bool, _: ()) -> Result<BufferSource>
+	where
+		'cx: 'v,
+	{
+		if !value.handle().is_object() {
+			return Err(Error::new("Expected BufferSource", ErrorKind::Type));
+		}
+
+		let object = value.to_object(cx).handle().get();
+		if let Ok(buffer) = ArrayBuffer::from(object) {
+			Ok(BufferSource(unsafe { buffer.as_slice() }.to_vec()))
+		} else if let Ok(view) = ArrayBufferView::from(object) {
+			Ok(BufferSource(unsafe { view.as_slice() }.to_vec()))
+		} else {
+			Err(Error::new("Expected BufferSource", ErrorKind::Type))
+		}
+	}
+}
+
+/// Creates an error with the name `NotSupportedError`, for algorithms and parameters which are valid, but not implemented.
+pub(crate) fn not_supported_error(cx: &Context, message: &str) -> Error {
+	let mut error = Error::new(message, None);
+	if let Some(mut object) = error.to_object(cx) {
+		object.set_as(cx, "name", "NotSupportedError");
+		error.object = Some(object.handle().get());
+	}
+	error
+}
+
+pub fn define(cx: &Context, global: &mut Object) -> bool {
+	if !(Crypto::init_class(cx, global).0 && SubtleCrypto::init_class(cx, global).0 && CryptoKey::init_class(cx, global).0) {
+		return false;
+	}
+
+	let subtle = SubtleCrypto::new_object(cx, SubtleCrypto);
+	let crypto = Crypto { subtle: Heap::boxed(subtle) };
+	let crypto = cx.root_object(Crypto::new_object(cx, crypto));
+	global.define_as(cx, "crypto", &Object::from(crypto), PropertyFlags::CONSTANT_ENUMERATED)
+}
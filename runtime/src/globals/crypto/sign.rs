@@ -0,0 +1,101 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use ed25519_dalek::{Signer, Verifier};
+use hmac::{Hmac, Mac};
+use p256::ecdsa::signature::hazmat::{PrehashSigner, PrehashVerifier};
+use rsa::{Pkcs1v15Sign, Pss};
+
+use ion::{Error, Result};
+
+use crate::globals::crypto::algorithm::{DigestAlgorithm, SignAlgorithm, with_digest};
+use crate::globals::crypto::crypto::SystemRng;
+use crate::globals::crypto::key::{CryptoKey, EcPrivateKey, EcPublicKey, KeyAlgorithm, KeyMaterial, KeyUsage};
+
+fn signing_error() -> Error {
+	Error::new("Failed to sign data", None)
+}
+
+fn hmac(hash: DigestAlgorithm, key: &[u8], data: &[u8]) -> Vec<u8> {
+	with_digest!(hash, D => {
+		let mut mac = Hmac::<D>::new_from_slice(key).unwrap();
+		mac.update(data);
+		mac.finalize().into_bytes().to_vec()
+	})
+}
+
+fn hmac_verify(hash: DigestAlgorithm, key: &[u8], data: &[u8], signature: &[u8]) -> bool {
+	with_digest!(hash, D => {
+		let mut mac = Hmac::<D>::new_from_slice(key).unwrap();
+		mac.update(data);
+		mac.verify_slice(signature).is_ok()
+	})
+}
+
+fn ecdsa_sign(key: &EcPrivateKey, prehash: &[u8]) -> Result<Vec<u8>> {
+	match key {
+		EcPrivateKey::P256(key) => {
+			let signature: p256::ecdsa::Signature = p256::ecdsa::SigningKey::from(key).sign_prehash(prehash).map_err(|_| signing_error())?;
+			Ok(signature.to_bytes().to_vec())
+		}
+		EcPrivateKey::P384(key) => {
+			let signature: p384::ecdsa::Signature = p384::ecdsa::SigningKey::from(key).sign_prehash(prehash).map_err(|_| signing_error())?;
+			Ok(signature.to_bytes().to_vec())
+		}
+	}
+}
+
+fn ecdsa_verify(key: &EcPublicKey, prehash: &[u8], signature: &[u8]) -> bool {
+	match key {
+		EcPublicKey::P256(key) => p256::ecdsa::Signature::from_slice(signature).map_or(false, |signature| {
+			p256::ecdsa::VerifyingKey::from(key).verify_prehash(prehash, &signature).is_ok()
+		}),
+		EcPublicKey::P384(key) => p384::ecdsa::Signature::from_slice(signature).map_or(false, |signature| {
+			p384::ecdsa::VerifyingKey::from(key).verify_prehash(prehash, &signature).is_ok()
+		}),
+	}
+}
+
+/// Signs the data with a private or secret key.
+pub fn sign(algorithm: &SignAlgorithm, key: &CryptoKey, data: &[u8]) -> Result<Vec<u8>> {
+	key.check(algorithm.name(), KeyUsage::Sign)?;
+	match (algorithm, &key.material, &key.algorithm) {
+		(SignAlgorithm::Hmac, KeyMaterial::Secret(secret), KeyAlgorithm::Hmac { hash, .. }) => Ok(hmac(*hash, secret, data)),
+		(SignAlgorithm::Ecdsa(hash), KeyMaterial::EcPrivate(private), _) => ecdsa_sign(private, &hash.digest(data)),
+		(SignAlgorithm::Ed25519, KeyMaterial::Ed25519Private(private), _) => Ok(private.sign(data).to_bytes().to_vec()),
+		(SignAlgorithm::RsassaPkcs1, KeyMaterial::RsaPrivate(private), KeyAlgorithm::Rsa { hash, .. }) => {
+			let hashed = hash.digest(data);
+			with_digest!(hash, D => private.sign(Pkcs1v15Sign::new::<D>(), &hashed)).map_err(|_| signing_error())
+		}
+		(SignAlgorithm::RsaPss(salt_length), KeyMaterial::RsaPrivate(private), KeyAlgorithm::Rsa { hash, .. }) => {
+			let hashed = hash.digest(data);
+			with_digest!(hash, D => private.sign_with_rng(&mut SystemRng, Pss::new_with_salt::<D>(*salt_length), &hashed))
+				.map_err(|_| signing_error())
+		}
+		_ => Err(Error::new("Key cannot be used to sign data", None)),
+	}
+}
+
+/// Verifies the signature of the data with a public or secret key.
+pub fn verify(algorithm: &SignAlgorithm, key: &CryptoKey, signature: &[u8], data: &[u8]) -> Result<bool> {
+	key.check(algorithm.name(), KeyUsage::Verify)?;
+	match (algorithm, &key.material, &key.algorithm) {
+		(SignAlgorithm::Hmac, KeyMaterial::Secret(secret), KeyAlgorithm::Hmac { hash, .. }) => Ok(hmac_verify(*hash, secret, data, signature)),
+		(SignAlgorithm::Ecdsa(hash), KeyMaterial::EcPublic(public), _) => Ok(ecdsa_verify(public, &hash.digest(data), signature)),
+		(SignAlgorithm::Ed25519, KeyMaterial::Ed25519Public(public), _) => {
+			Ok(ed25519_dalek::Signature::from_slice(signature).map_or(false, |signature| public.verify(data, &signature).is_ok()))
+		}
+		(SignAlgorithm::RsassaPkcs1, KeyMaterial::RsaPublic(public), KeyAlgorithm::Rsa { hash, .. }) => {
+			let hashed = hash.digest(data);
+			Ok(with_digest!(hash, D => public.verify(Pkcs1v15Sign::new::<D>(), &hashed, signature)).is_ok())
+		}
+		(SignAlgorithm::RsaPss(salt_length), KeyMaterial::RsaPublic(public), KeyAlgorithm::Rsa { hash, .. }) => {
+			let hashed = hash.digest(data);
+			Ok(with_digest!(hash, D => public.verify(Pss::new_with_salt::<D>(*salt_length), &hashed, signature)).is_ok())
+		}
+		_ => Err(Error::new("Key cannot be used to verify signatures", None)),
+	}
+}
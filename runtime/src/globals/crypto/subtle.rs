@@ -0,0 +1,74 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+pub use class::SubtleCrypto;
+
+#[js_class]
+mod class {
+	use mozjs::conversions::ConversionBehavior::EnforceRange;
+	use tokio::task::spawn_blocking;
+
+	use ion::Result;
+	use ion::typedarray::ArrayBuffer;
+
+	use crate::globals::crypto::{BufferSource, DigestAlgorithm};
+	use crate::globals::crypto::algorithm::{CipherAlgorithm, DeriveAlgorithm, ImportAlgorithm, KeyGenAlgorithm, SignAlgorithm};
+	use crate::globals::crypto::cipher::cipher;
+	use crate::globals::crypto::derive::derive_bits;
+	use crate::globals::crypto::format::{export_key, ExportedKey, import_key, KeyData, KeyFormat};
+	use crate::globals::crypto::generate::generate_key;
+	use crate::globals::crypto::key::{CryptoKey, GeneratedKey, KeyUsage};
+	use crate::globals::crypto::sign;
+
+	#[ion(no_constructor)]
+	pub struct SubtleCrypto;
+
+	impl SubtleCrypto {
+		pub async fn digest(&self, algorithm: DigestAlgorithm, data: BufferSource) -> Result<ArrayBuffer> {
+			Ok(ArrayBuffer::from(algorithm.digest(&data.0)))
+		}
+
+		pub async fn importKey(
+			&self, format: KeyFormat, key_data: KeyData, algorithm: ImportAlgorithm, extractable: bool, usages: Vec<KeyUsage>,
+		) -> Result<CryptoKey> {
+			import_key(format, key_data, algorithm, extractable, &usages)
+		}
+
+		pub async fn exportKey(&self, format: KeyFormat, key: CryptoKey) -> Result<ExportedKey> {
+			export_key(format, &key)
+		}
+
+		pub async fn generateKey(&self, algorithm: KeyGenAlgorithm, extractable: bool, usages: Vec<KeyUsage>) -> Result<GeneratedKey> {
+			spawn_blocking(move || generate_key(algorithm, extractable, &usages)).await?
+		}
+
+		pub async fn sign(&self, algorithm: SignAlgorithm, key: CryptoKey, data: BufferSource) -> Result<ArrayBuffer> {
+			let signature = spawn_blocking(move || sign::sign(&algorithm, &key, &data.0)).await??;
+			Ok(ArrayBuffer::from(signature))
+		}
+
+		pub async fn verify(&self, algorithm: SignAlgorithm, key: CryptoKey, signature: BufferSource, data: BufferSource) -> Result<bool> {
+			spawn_blocking(move || sign::verify(&algorithm, &key, &signature.0, &data.0)).await?
+		}
+
+		pub async fn encrypt(&self, algorithm: CipherAlgorithm, key: CryptoKey, data: BufferSource) -> Result<ArrayBuffer> {
+			let encrypted = spawn_blocking(move || cipher(&algorithm, &key, &data.0, true)).await??;
+			Ok(ArrayBuffer::from(encrypted))
+		}
+
+		pub async fn decrypt(&self, algorithm: CipherAlgorithm, key: CryptoKey, data: BufferSource) -> Result<ArrayBuffer> {
+			let decrypted = spawn_blocking(move || cipher(&algorithm, &key, &data.0, false)).await??;
+			Ok(ArrayBuffer::from(decrypted))
+		}
+
+		pub async fn deriveBits(
+			&self, algorithm: DeriveAlgorithm, base_key: CryptoKey, #[ion(convert = EnforceRange)] length: Option<u32>,
+		) -> Result<ArrayBuffer> {
+			let bits = spawn_blocking(move || derive_bits(&algorithm, &base_key, length)).await??;
+			Ok(ArrayBuffer::from(bits))
+		}
+	}
+}
@@ -0,0 +1,65 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsval::JSVal;
+
+pub use class::CustomEvent;
+
+use crate::globals::events::EventInit;
+
+#[derive(Default, FromValue)]
+pub struct CustomEventInit {
+	#[ion(inherit)]
+	init: EventInit,
+	detail: Option<JSVal>,
+}
+
+#[js_class]
+mod class {
+	use mozjs::gc::Traceable;
+	use mozjs::jsapi::{Heap, JSTracer};
+	use mozjs::jsval::{JSVal, NullValue};
+
+	use crate::globals::events::{Event, EventClass};
+	use crate::globals::events::custom::CustomEventInit;
+
+	pub struct CustomEvent {
+		event: Event,
+		detail: Box<Heap<JSVal>>,
+	}
+
+	impl CustomEvent {
+		#[ion(constructor)]
+		pub fn constructor(kind: String, init: Option<CustomEventInit>) -> CustomEvent {
+			let CustomEventInit { init, detail } = init.unwrap_or_default();
+			CustomEvent {
+				event: Event::new(&kind, init),
+				detail: Heap::boxed(detail.unwrap_or_else(NullValue)),
+			}
+		}
+
+		#[ion(get)]
+		pub fn get_detail(&self) -> JSVal {
+			self.detail.get()
+		}
+	}
+
+	impl EventClass for CustomEvent {
+		fn event(&mut self) -> &mut Event {
+			&mut self.event
+		}
+	}
+
+	unsafe impl Traceable for CustomEvent {
+		#[inline]
+		unsafe fn trace(&self, trc: *mut JSTracer) {
+			unsafe {
+				self.event.trace(trc);
+				self.detail.trace(trc);
+			}
+		}
+	}
+}
@@ -0,0 +1,226 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::any::TypeId;
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use chrono::Utc;
+
+pub use class::Event;
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result};
+
+pub const NONE: u16 = 0;
+pub const CAPTURING_PHASE: u16 = 1;
+pub const AT_TARGET: u16 = 2;
+pub const BUBBLING_PHASE: u16 = 3;
+
+pub(crate) const PHASES: [(&str, u16); 4] = [
+	("NONE", NONE),
+	("CAPTURING_PHASE", CAPTURING_PHASE),
+	("AT_TARGET", AT_TARGET),
+	("BUBBLING_PHASE", BUBBLING_PHASE),
+];
+
+type EventAccessor = for<'a> fn(&Context, &'a Object) -> Option<&'a mut Event>;
+
+thread_local!(static EVENTS: RefCell<HashMap<TypeId, EventAccessor>> = RefCell::new(HashMap::new()));
+
+/// Represents native classes which inherit from [Event].
+pub trait EventClass: ClassDefinition + Sized + 'static {
+	fn event(&mut self) -> &mut Event;
+}
+
+fn event_of<'a, T: EventClass>(cx: &Context, object: &'a Object) -> Option<&'a mut Event> {
+	T::instance_of(cx, object, None).then(|| T::get_private(object).event())
+}
+
+/// Registers a native class as an [Event], so that methods of [Event] can be used on its instances.
+pub fn register_event<T: EventClass>() {
+	EVENTS.with(|events| {
+		events.borrow_mut().insert(TypeId::of::<T>(), event_of::<T>);
+	});
+}
+
+/// Gets the [Event] of an object, which can be an instance of any registered [EventClass].
+pub fn get_event<'a>(cx: &Context, object: &'a Object) -> Result<&'a mut Event> {
+	EVENTS
+		.with(|events| events.borrow().values().find_map(|accessor| accessor(cx, object)))
+		.ok_or_else(|| Error::new("Expected Event", ErrorKind::Type))
+}
+
+#[derive(Default, FromValue)]
+pub struct EventInit {
+	#[ion(default)]
+	pub(crate) bubbles: bool,
+	#[ion(default)]
+	pub(crate) cancelable: bool,
+	#[ion(default)]
+	pub(crate) composed: bool,
+}
+
+#[js_class]
+mod class {
+	use mozjs::gc::Traceable;
+	use mozjs::jsapi::{Heap, JSObject, JSTracer};
+
+	use ion::{Context, Object, Result};
+
+	use crate::globals::events::event::{current_time, EventClass, EventInit, get_event, NONE};
+
+	pub struct Event {
+		pub(crate) kind: String,
+		pub(crate) bubbles: bool,
+		pub(crate) cancelable: bool,
+		pub(crate) composed: bool,
+
+		pub(crate) target: Option<Box<Heap<*mut JSObject>>>,
+		pub(crate) current_target: Option<Box<Heap<*mut JSObject>>>,
+		pub(crate) phase: u16,
+
+		pub(crate) canceled: bool,
+		pub(crate) stop_propagation: bool,
+		pub(crate) stop_immediate_propagation: bool,
+		pub(crate) in_passive_listener: bool,
+		pub(crate) dispatching: bool,
+		pub(crate) trusted: bool,
+		pub(crate) time_stamp: f64,
+	}
+
+	impl Event {
+		pub(crate) fn new(kind: &str, init: EventInit) -> Event {
+			Event {
+				kind: String::from(kind),
+				bubbles: init.bubbles,
+				cancelable: init.cancelable,
+				composed: init.composed,
+
+				target: None,
+				current_target: None,
+				phase: NONE,
+
+				canceled: false,
+				stop_propagation: false,
+				stop_immediate_propagation: false,
+				in_passive_listener: false,
+				dispatching: false,
+				trusted: false,
+				time_stamp: current_time(),
+			}
+		}
+
+		/// Creates an [Event] dispatched by the runtime, rather than from script.
+		pub(crate) fn new_trusted(kind: &str) -> Event {
+			Event {
+				trusted: true,
+				..Event::new(kind, EventInit::default())
+			}
+		}
+
+		#[ion(constructor)]
+		pub fn constructor(kind: String, init: Option<EventInit>) -> Event {
+			Event::new(&kind, init.unwrap_or_default())
+		}
+
+		#[ion(get)]
+		pub fn get_type(#[ion(this)] this: &Object, cx: &Context) -> Result<String> {
+			Ok(get_event(cx, this)?.kind.clone())
+		}
+
+		#[ion(get)]
+		pub fn get_target(#[ion(this)] this: &Object, cx: &Context) -> Result<Option<*mut JSObject>> {
+			Ok(get_event(cx, this)?.target.as_ref().map(|target| target.get()))
+		}
+
+		#[ion(get)]
+		pub fn get_current_target(#[ion(this)] this: &Object, cx: &Context) -> Result<Option<*mut JSObject>> {
+			Ok(get_event(cx, this)?.current_target.as_ref().map(|target| target.get()))
+		}
+
+		#[ion(get)]
+		pub fn get_event_phase(#[ion(this)] this: &Object, cx: &Context) -> Result<u16> {
+			Ok(get_event(cx, this)?.phase)
+		}
+
+		#[ion(get)]
+		pub fn get_bubbles(#[ion(this)] this: &Object, cx: &Context) -> Result<bool> {
+			Ok(get_event(cx, this)?.bubbles)
+		}
+
+		#[ion(get)]
+		pub fn get_cancelable(#[ion(this)] this: &Object, cx: &Context) -> Result<bool> {
+			Ok(get_event(cx, this)?.cancelable)
+		}
+
+		#[ion(get)]
+		pub fn get_composed(#[ion(this)] this: &Object, cx: &Context) -> Result<bool> {
+			Ok(get_event(cx, this)?.composed)
+		}
+
+		#[ion(get)]
+		pub fn get_default_prevented(#[ion(this)] this: &Object, cx: &Context) -> Result<bool> {
+			Ok(get_event(cx, this)?.canceled)
+		}
+
+		#[ion(get)]
+		pub fn get_is_trusted(#[ion(this)] this: &Object, cx: &Context) -> Result<bool> {
+			Ok(get_event(cx, this)?.trusted)
+		}
+
+		#[ion(get)]
+		pub fn get_time_stamp(#[ion(this)] this: &Object, cx: &Context) -> Result<f64> {
+			Ok(get_event(cx, this)?.time_stamp)
+		}
+
+		pub fn composedPath(#[ion(this)] this: &Object, cx: &Context) -> Result<Vec<*mut JSObject>> {
+			Ok(get_event(cx, this)?.current_target.iter().map(|target| target.get()).collect())
+		}
+
+		pub fn preventDefault(#[ion(this)] this: &Object, cx: &Context) -> Result<()> {
+			get_event(cx, this)?.cancel();
+			Ok(())
+		}
+
+		pub fn stopPropagation(#[ion(this)] this: &Object, cx: &Context) -> Result<()> {
+			get_event(cx, this)?.stop_propagation = true;
+			Ok(())
+		}
+
+		pub fn stopImmediatePropagation(#[ion(this)] this: &Object, cx: &Context) -> Result<()> {
+			let event = get_event(cx, this)?;
+			event.stop_propagation = true;
+			event.stop_immediate_propagation = true;
+			Ok(())
+		}
+
+		pub(crate) fn cancel(&mut self) {
+			if self.cancelable && !self.in_passive_listener {
+				self.canceled = true;
+			}
+		}
+	}
+
+	impl EventClass for Event {
+		fn event(&mut self) -> &mut Event {
+			self
+		}
+	}
+
+	unsafe impl Traceable for Event {
+		#[inline]
+		unsafe fn trace(&self, trc: *mut JSTracer) {
+			unsafe {
+				self.target.trace(trc);
+				self.current_target.trace(trc);
+			}
+		}
+	}
+}
+
+/// Returns the current time in milliseconds since the Unix epoch.
+fn current_time() -> f64 {
+	Utc::now().timestamp_micros() as f64 / 1000.0
+}
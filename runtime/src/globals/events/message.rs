@@ -0,0 +1,95 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::jsval::JSVal;
+
+pub use class::MessageEvent;
+
+use crate::globals::events::EventInit;
+
+#[derive(Default, FromValue)]
+pub struct MessageEventInit {
+	#[ion(inherit)]
+	init: EventInit,
+	data: Option<JSVal>,
+	#[ion(default)]
+	origin: String,
+	#[ion(default)]
+	last_event_id: String,
+}
+
+#[js_class]
+mod class {
+	use mozjs::gc::Traceable;
+	use mozjs::jsapi::{Heap, JSTracer};
+	use mozjs::jsval::{JSVal, NullValue};
+
+	use crate::globals::events::{Event, EventClass, EventInit};
+	use crate::globals::events::message::MessageEventInit;
+
+	pub struct MessageEvent {
+		event: Event,
+		data: Box<Heap<JSVal>>,
+		origin: String,
+		last_event_id: String,
+	}
+
+	impl MessageEvent {
+		/// Creates a [MessageEvent] dispatched by the runtime, rather than from script.
+		pub(crate) fn new_trusted(kind: &str, data: JSVal, origin: String, last_event_id: String) -> MessageEvent {
+			let mut event = Event::new(kind, EventInit::default());
+			event.trusted = true;
+			MessageEvent {
+				event,
+				data: Heap::boxed(data),
+				origin,
+				last_event_id,
+			}
+		}
+
+		#[ion(constructor)]
+		pub fn constructor(kind: String, init: Option<MessageEventInit>) -> MessageEvent {
+			let MessageEventInit { init, data, origin, last_event_id } = init.unwrap_or_default();
+			MessageEvent {
+				event: Event::new(&kind, init),
+				data: Heap::boxed(data.unwrap_or_else(NullValue)),
+				origin,
+				last_event_id,
+			}
+		}
+
+		#[ion(get)]
+		pub fn get_data(&self) -> JSVal {
+			self.data.get()
+		}
+
+		#[ion(get)]
+		pub fn get_origin(&self) -> String {
+			self.origin.clone()
+		}
+
+		#[ion(get)]
+		pub fn get_last_event_id(&self) -> String {
+			self.last_event_id.clone()
+		}
+	}
+
+	impl EventClass for MessageEvent {
+		fn event(&mut self) -> &mut Event {
+			&mut self.event
+		}
+	}
+
+	unsafe impl Traceable for MessageEvent {
+		#[inline]
+		unsafe fn trace(&self, trc: *mut JSTracer) {
+			unsafe {
+				self.event.trace(trc);
+				self.data.trace(trc);
+			}
+		}
+	}
+}
@@ -0,0 +1,55 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+pub use custom::CustomEvent;
+pub use event::{Event, EventClass, EventInit, get_event, register_event};
+use ion::{ClassDefinition, Context, Object};
+use ion::flags::PropertyFlags;
+pub use message::MessageEvent;
+pub use target::{dispatch_event, EventTarget, EventTargetClass, get_event_target, register_event_target};
+
+mod custom;
+mod event;
+mod message;
+mod target;
+
+/// Sets the prototype chain of the native class `T` to inherit from the native class `P`.
+/// Both classes must be initialised beforehand.
+pub(crate) fn inherit<T: ClassDefinition + 'static, P: ClassDefinition + 'static>(cx: &Context, global: &mut Object) -> bool {
+	let parent = P::init_class(cx, global).1;
+	let (parent_prototype, parent_constructor) = (parent.prototype(cx), parent.constructor(cx).to_object(cx));
+
+	let class = T::init_class(cx, global).1;
+	let (mut prototype, mut constructor) = (class.prototype(cx), class.constructor(cx).to_object(cx));
+
+	prototype.set_prototype(cx, &parent_prototype) && constructor.set_prototype(cx, &parent_constructor)
+}
+
+/// Defines numeric constants on both the constructor and prototype of the native class `T`.
+pub(crate) fn define_constants<T: ClassDefinition + 'static>(cx: &Context, global: &mut Object, constants: &[(&str, u16)]) -> bool {
+	let class = T::init_class(cx, global).1;
+	let (mut prototype, mut constructor) = (class.prototype(cx), class.constructor(cx).to_object(cx));
+
+	constants.iter().all(|(name, value)| {
+		constructor.define_as(cx, *name, value, PropertyFlags::CONSTANT_ENUMERATED)
+			&& prototype.define_as(cx, *name, value, PropertyFlags::CONSTANT_ENUMERATED)
+	})
+}
+
+pub fn define(cx: &Context, global: &mut Object) -> bool {
+	register_event_target::<EventTarget>();
+	register_event::<Event>();
+	register_event::<CustomEvent>();
+	register_event::<MessageEvent>();
+
+	EventTarget::init_class(cx, global).0
+		&& Event::init_class(cx, global).0
+		&& CustomEvent::init_class(cx, global).0
+		&& MessageEvent::init_class(cx, global).0
+		&& define_constants::<Event>(cx, global, &event::PHASES)
+		&& inherit::<CustomEvent, Event>(cx, global)
+		&& inherit::<MessageEvent, Event>(cx, global)
+}
@@ -0,0 +1,363 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::any::TypeId;
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use mozjs::gc::Traceable;
+use mozjs::jsapi::{Heap, JSObject, JSTracer};
+
+pub use class::EventTarget;
+use ion::{ClassDefinition, Context, Error, ErrorKind, ErrorReport, Exception, Function, Object, Result, Value};
+
+use crate::event_loop::report_error;
+use crate::globals::abort::Signal;
+use crate::globals::events::event::{AT_TARGET, NONE};
+use crate::globals::events::get_event;
+
+type EventTargetAccessor = for<'a> fn(&Context, &'a Object) -> Option<&'a mut EventTarget>;
+
+thread_local!(static EVENT_TARGETS: RefCell<HashMap<TypeId, EventTargetAccessor>> = RefCell::new(HashMap::new()));
+
+/// Represents native classes which inherit from [EventTarget].
+pub trait EventTargetClass: ClassDefinition + Sized + 'static {
+	fn event_target(&mut self) -> &mut EventTarget;
+}
+
+fn event_target_of<'a, T: EventTargetClass>(cx: &Context, object: &'a Object) -> Option<&'a mut EventTarget> {
+	T::instance_of(cx, object, None).then(|| T::get_private(object).event_target())
+}
+
+/// Registers a native class as an [EventTarget], so that methods of [EventTarget] can be used on its instances.
+pub fn register_event_target<T: EventTargetClass>() {
+	EVENT_TARGETS.with(|targets| {
+		targets.borrow_mut().insert(TypeId::of::<T>(), event_target_of::<T>);
+	});
+}
+
+/// Gets the [EventTarget] of an object, which can be an instance of any registered [EventTargetClass].
+pub fn get_event_target<'a>(cx: &Context, object: &'a Object) -> Result<&'a mut EventTarget> {
+	EVENT_TARGETS
+		.with(|targets| targets.borrow().values().find_map(|accessor| accessor(cx, object)))
+		.ok_or_else(|| Error::new("Expected EventTarget", ErrorKind::Type))
+}
+
+#[derive(Default, FromValue)]
+pub struct AddEventListenerOptions {
+	capture: Option<bool>,
+	once: Option<bool>,
+	passive: Option<bool>,
+	signal: Option<Signal>,
+}
+
+#[derive(FromValue)]
+pub enum EventListenerOptions {
+	#[ion(inherit)]
+	Options(AddEventListenerOptions),
+	#[ion(inherit)]
+	Capture(bool),
+}
+
+impl EventListenerOptions {
+	fn into_options(options: Option<EventListenerOptions>) -> AddEventListenerOptions {
+		match options {
+			Some(EventListenerOptions::Options(options)) => options,
+			Some(EventListenerOptions::Capture(capture)) => AddEventListenerOptions {
+				capture: Some(capture),
+				..Default::default()
+			},
+			None => AddEventListenerOptions::default(),
+		}
+	}
+}
+
+pub struct EventListener {
+	callback: Box<Heap<*mut JSObject>>,
+	capture: bool,
+	once: bool,
+	passive: bool,
+	signal: Option<Signal>,
+	handler: bool,
+	removed: Rc<Cell<bool>>,
+}
+
+impl EventListener {
+	fn is_aborted(&self) -> bool {
+		self.removed.get() || self.signal.as_ref().map(Signal::is_aborted).unwrap_or(false)
+	}
+
+	fn remove(&self) {
+		self.removed.set(true);
+	}
+}
+
+/// Listener state copied at the start of dispatch, so that listeners can be added and removed during dispatch.
+/// The callback is rooted, as the listener may be removed and collected before it is called.
+pub(crate) struct ListenerSnapshot<'cx> {
+	callback: Object<'cx>,
+	once: bool,
+	passive: bool,
+	signal: Option<Signal>,
+	handler: bool,
+	removed: Rc<Cell<bool>>,
+}
+
+#[js_class]
+mod class {
+	use std::cell::Cell;
+	use std::collections::HashMap;
+	use std::rc::Rc;
+
+	use mozjs::gc::Traceable;
+	use mozjs::jsapi::{Heap, JSObject, JSTracer};
+
+	use ion::{Context, Error, ErrorKind, Object, Result};
+
+	use crate::globals::abort::Signal;
+	use crate::globals::events::get_event;
+	use crate::globals::events::target::{dispatch_event, EventListener, EventListenerOptions, EventTargetClass, get_event_target, ListenerSnapshot};
+
+	#[derive(Default)]
+	pub struct EventTarget {
+		listeners: HashMap<String, Vec<EventListener>>,
+	}
+
+	impl EventTarget {
+		#[ion(constructor)]
+		pub fn constructor() -> EventTarget {
+			EventTarget::default()
+		}
+
+		pub fn addEventListener(
+			#[ion(this)] this: &Object, cx: &Context, kind: String, callback: Option<Object>, options: Option<EventListenerOptions>,
+		) -> Result<()> {
+			let options = EventListenerOptions::into_options(options);
+			if let Some(callback) = callback {
+				let target = get_event_target(cx, this)?;
+				target.add_listener(
+					&kind,
+					callback.handle().get(),
+					options.capture.unwrap_or(false),
+					options.once.unwrap_or(false),
+					options.passive.unwrap_or(false),
+					options.signal,
+				);
+			}
+			Ok(())
+		}
+
+		pub fn removeEventListener(
+			#[ion(this)] this: &Object, cx: &Context, kind: String, callback: Option<Object>, options: Option<EventListenerOptions>,
+		) -> Result<()> {
+			let options = EventListenerOptions::into_options(options);
+			if let Some(callback) = callback {
+				let target = get_event_target(cx, this)?;
+				target.remove_listener(&kind, callback.handle().get(), options.capture.unwrap_or(false));
+			}
+			Ok(())
+		}
+
+		pub fn dispatchEvent(#[ion(this)] this: &Object, cx: &Context, event: Object) -> Result<bool> {
+			get_event_target(cx, this)?;
+			let inner = get_event(cx, &event)?;
+			if inner.dispatching {
+				return Err(Error::new("Event is already being dispatched", ErrorKind::Normal));
+			}
+			inner.trusted = false;
+			dispatch_event(cx, this, &event)
+		}
+
+		/// Adds an event listener, unless an identical listener has already been added,
+		/// or its signal has already been aborted.
+		pub(crate) fn add_listener(&mut self, kind: &str, callback: *mut JSObject, capture: bool, once: bool, passive: bool, signal: Option<Signal>) {
+			if signal.as_ref().map(Signal::is_aborted).unwrap_or(false) {
+				return;
+			}
+
+			let listeners = self.listeners.entry(String::from(kind)).or_default();
+			listeners.retain(|listener| !listener.is_aborted());
+			if listeners
+				.iter()
+				.any(|listener| !listener.handler && listener.callback.get() == callback && listener.capture == capture)
+			{
+				return;
+			}
+
+			listeners.push(EventListener {
+				callback: Heap::boxed(callback),
+				capture,
+				once,
+				passive,
+				signal,
+				handler: false,
+				removed: Rc::new(Cell::new(false)),
+			});
+		}
+
+		pub(crate) fn remove_listener(&mut self, kind: &str, callback: *mut JSObject, capture: bool) {
+			if let Some(listeners) = self.listeners.get_mut(kind) {
+				listeners.retain(|listener| {
+					let matches = !listener.handler && listener.callback.get() == callback && listener.capture == capture;
+					if matches {
+						listener.remove();
+					}
+					!matches && !listener.is_aborted()
+				});
+			}
+		}
+
+		/// Gets the event handler attribute (such as `onabort`) for the given event type.
+		pub(crate) fn get_event_handler(&self, kind: &str) -> Option<*mut JSObject> {
+			self.listeners
+				.get(kind)
+				.and_then(|listeners| listeners.iter().find(|listener| listener.handler))
+				.map(|listener| listener.callback.get())
+		}
+
+		/// Sets the event handler attribute (such as `onabort`) for the given event type.
+		/// The handler keeps its position among the listeners when replaced, and is removed when set to [None].
+		pub(crate) fn set_event_handler(&mut self, kind: &str, handler: Option<*mut JSObject>) {
+			let listeners = self.listeners.entry(String::from(kind)).or_default();
+			let position = listeners.iter().position(|listener| listener.handler);
+			match (position, handler) {
+				(Some(position), Some(handler)) => listeners[position].callback.set(handler),
+				(Some(position), None) => {
+					listeners.remove(position).remove();
+				}
+				(None, Some(handler)) => listeners.push(EventListener {
+					callback: Heap::boxed(handler),
+					capture: false,
+					once: false,
+					passive: false,
+					signal: None,
+					handler: true,
+					removed: Rc::new(Cell::new(false)),
+				}),
+				(None, None) => {}
+			}
+		}
+
+		pub(crate) fn snapshot<'cx>(&mut self, cx: &'cx Context, kind: &str) -> Vec<ListenerSnapshot<'cx>> {
+			match self.listeners.get_mut(kind) {
+				Some(listeners) => {
+					listeners.retain(|listener| !listener.is_aborted());
+					listeners
+						.iter()
+						.map(|listener| ListenerSnapshot {
+							callback: Object::from(cx.root_object(listener.callback.get())),
+							once: listener.once,
+							passive: listener.passive,
+							signal: listener.signal.clone(),
+							handler: listener.handler,
+							removed: listener.removed.clone(),
+						})
+						.collect()
+				}
+				None => Vec::new(),
+			}
+		}
+
+		/// Removes listeners which have been removed or whose signal has been aborted.
+		pub(crate) fn prune(&mut self, kind: &str) {
+			if let Some(listeners) = self.listeners.get_mut(kind) {
+				listeners.retain(|listener| !listener.is_aborted());
+			}
+		}
+	}
+
+	impl EventTargetClass for EventTarget {
+		fn event_target(&mut self) -> &mut EventTarget {
+			self
+		}
+	}
+
+	unsafe impl Traceable for EventTarget {
+		#[inline]
+		unsafe fn trace(&self, trc: *mut JSTracer) {
+			unsafe {
+				for listener in self.listeners.values().flatten() {
+					listener.trace(trc);
+				}
+			}
+		}
+	}
+}
+
+unsafe impl Traceable for EventListener {
+	#[inline]
+	unsafe fn trace(&self, trc: *mut JSTracer) {
+		unsafe { self.callback.trace(trc) }
+	}
+}
+
+/// Dispatches an event to the listeners of a target, as they were when dispatch began.
+/// Errors thrown by listeners are reported, and do not stop dispatch.
+/// Returns whether the event was not cancelled.
+pub fn dispatch_event(cx: &Context, target: &Object, event: &Object) -> Result<bool> {
+	let kind = {
+		let inner = get_event(cx, event)?;
+		if inner.dispatching {
+			return Err(Error::new("Event is already being dispatched", ErrorKind::Normal));
+		}
+		inner.dispatching = true;
+		inner.target = Some(Heap::boxed(target.handle().get()));
+		inner.current_target = Some(Heap::boxed(target.handle().get()));
+		inner.phase = AT_TARGET;
+		inner.kind.clone()
+	};
+
+	let listeners = get_event_target(cx, target)?.snapshot(cx, &kind);
+
+	for listener in listeners {
+		if listener.removed.get() || listener.signal.as_ref().map(Signal::is_aborted).unwrap_or(false) {
+			continue;
+		}
+		if listener.once {
+			listener.removed.set(true);
+			get_event_target(cx, target)?.prune(&kind);
+		}
+
+		get_event(cx, event)?.in_passive_listener = listener.passive;
+		let callback = &listener.callback;
+		let result = match Function::from_object(cx, callback) {
+			Some(function) => Some(function.call(cx, target, &[Value::object(cx, event)])),
+			None => callback
+				.get_as::<_, Function>(cx, "handleEvent", false, ())
+				.map(|function| function.call(cx, callback, &[Value::object(cx, event)])),
+		};
+
+		let inner = get_event(cx, event)?;
+		inner.in_passive_listener = false;
+		match result {
+			Some(Ok(value)) => {
+				let value = value.get();
+				if listener.handler && value.is_boolean() && !value.to_boolean() {
+					inner.cancel();
+				}
+			}
+			Some(Err(Some(report))) => report_error(cx, report),
+			Some(Err(None)) => {}
+			None => {
+				let error = Error::new("Event listener is not callable", ErrorKind::Type);
+				report_error(cx, ErrorReport::from(Exception::Error(error), None));
+			}
+		}
+
+		if inner.stop_immediate_propagation {
+			break;
+		}
+	}
+
+	let inner = get_event(cx, event)?;
+	inner.phase = NONE;
+	inner.current_target = None;
+	inner.dispatching = false;
+	inner.stop_propagation = false;
+	inner.stop_immediate_propagation = false;
+	Ok(!inner.canceled)
+}
@@ -0,0 +1,137 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use bytes::Bytes;
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use http::{HeaderMap, HeaderValue};
+use hyper::Body;
+
+use ion::{ClassDefinition, Context, Error, ErrorKind, Function, Object, Promise, Result, Value};
+use ion::conversions::FromValue;
+
+use crate::globals::fetch::upload::StreamBody;
+use crate::globals::file::{Blob, BlobPart, BlobPartInit};
+use crate::globals::form_data::FormData;
+use crate::globals::url::UrlSearchParams;
+
+/// Represents the body of a request.
+/// Bodies with a `Content-Type`, such as those created from [FormData] and [UrlSearchParams], are represented as a [Blob].
+/// Bodies created from a [Blob] are streamed, so that files are read lazily.
+/// Bodies created from a `ReadableStream` or an async iterable are sent with chunked encoding, as their length is unknown.
+#[derive(Clone, Debug, Default)]
+pub enum FetchBody {
+	#[default]
+	Empty,
+	Bytes(Bytes),
+	Blob(Blob),
+	Stream(StreamBody),
+}
+
+impl FetchBody {
+	pub fn is_empty(&self) -> bool {
+		match self {
+			FetchBody::Empty => true,
+			FetchBody::Bytes(bytes) => bytes.is_empty(),
+			FetchBody::Blob(blob) => blob.size() == 0,
+			FetchBody::Stream(_) => false,
+		}
+	}
+
+	/// Returns the length of the body, if it is known before it is sent.
+	pub fn len(&self) -> Option<u64> {
+		match self {
+			FetchBody::Empty => Some(0),
+			FetchBody::Bytes(bytes) => Some(bytes.len() as u64),
+			FetchBody::Blob(blob) => Some(blob.size()),
+			FetchBody::Stream(_) => None,
+		}
+	}
+
+	pub fn is_stream(&self) -> bool {
+		matches!(self, FetchBody::Stream(_))
+	}
+
+	/// Adds the `Content-Type` and `Content-Length` headers implied by the body, unless they are already present.
+	pub(crate) fn add_headers(&self, headers: &mut HeaderMap) -> Result<()> {
+		if let FetchBody::Blob(blob) = self {
+			if !blob.kind.is_empty() && !headers.contains_key(CONTENT_TYPE) {
+				headers.insert(CONTENT_TYPE, HeaderValue::from_str(&blob.kind)?);
+			}
+			if !headers.contains_key(CONTENT_LENGTH) {
+				headers.insert(CONTENT_LENGTH, HeaderValue::from(blob.size()));
+			}
+		}
+		Ok(())
+	}
+
+	/// Clones the body, splitting streamed bodies so that both copies can be read.
+	pub(crate) fn tee(&self) -> FetchBody {
+		match self {
+			FetchBody::Stream(stream) => FetchBody::Stream(stream.tee()),
+			body => body.clone(),
+		}
+	}
+
+	/// Reads all the data of the body.
+	pub(crate) async fn into_bytes(self) -> Result<Bytes> {
+		match self {
+			FetchBody::Empty => Ok(Bytes::new()),
+			FetchBody::Bytes(bytes) => Ok(bytes),
+			FetchBody::Blob(blob) => blob.bytes().await,
+			FetchBody::Stream(stream) => Ok(hyper::body::to_bytes(stream.into_http_body()).await?),
+		}
+	}
+
+	pub(crate) fn into_http_body(self) -> Body {
+		match self {
+			FetchBody::Empty => Body::empty(),
+			FetchBody::Bytes(bytes) => Body::from(bytes),
+			FetchBody::Blob(blob) => Body::wrap_stream(blob.stream_bytes()),
+			FetchBody::Stream(stream) => stream.into_http_body(),
+		}
+	}
+}
+
+impl<'cx> FromValue<'cx> for FetchBody {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, strict: bool, _: ()) -> Result<FetchBody>
+	where
+		'cx: 'v,
+	{
+		if value.handle().is_object() {
+			let object = value.to_object(cx);
+			if FormData::instance_of(cx, &object, None) {
+				return Ok(FetchBody::Blob(FormData::get_private(&object).to_multipart()?));
+			} else if UrlSearchParams::instance_of(cx, &object, None) {
+				let search_params = UrlSearchParams::get_private(&object);
+				return Ok(FetchBody::Blob(Blob {
+					parts: vec![BlobPart::Bytes(Bytes::from(search_params.toString()))],
+					kind: String::from("application/x-www-form-urlencoded;charset=UTF-8"),
+				}));
+			} else if let Some(stream) = StreamBody::from_object(cx, &object)? {
+				return Ok(FetchBody::Stream(stream));
+			}
+		}
+
+		match BlobPartInit::from_value(cx, value, strict, ())? {
+			BlobPartInit::Bytes(bytes) => Ok(FetchBody::Bytes(bytes)),
+			BlobPartInit::Blob(blob) => Ok(FetchBody::Blob(blob)),
+		}
+	}
+}
+
+/// Parses the text which a promise resolves to as JSON, with `JSON.parse`.
+pub(crate) fn parse_json<'cx>(cx: &'cx Context, text: &Promise) -> Result<Promise<'cx>> {
+	let error = || Error::new("Failed to parse body as JSON", ErrorKind::Type);
+	let json: Object = Object::global(cx).get_as(cx, "JSON", true, ()).ok_or_else(error)?;
+	let parse = json.get(cx, "parse").ok_or_else(error)?;
+
+	let text = Object::from(cx.root_object(text.handle().get()));
+	let then: Function = text.get_as(cx, "then", true, ()).ok_or_else(error)?;
+	let promise = then.call(cx, &text, &[parse]).map_err(|_| error())?;
+	Promise::from(promise.to_object(cx).into_local()).ok_or_else(error)
+}
@@ -0,0 +1,601 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fs::{create_dir_all, File, read, remove_file, rename, write};
+use std::io;
+use std::io::Write;
+use std::mem::take;
+use std::path::{Path, PathBuf};
+use std::str;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use futures::stream;
+use http::{HeaderMap, HeaderName, HeaderValue, Method, StatusCode};
+use http::header::{
+	AGE, AUTHORIZATION, CACHE_CONTROL, CONTENT_LENGTH, COOKIE, DATE, ETAG, EXPIRES, IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_RANGE,
+	IF_UNMODIFIED_SINCE, LAST_MODIFIED, PRAGMA, SET_COOKIE, VARY,
+};
+use hyper::{Body, Client};
+use hyper::body::HttpBody;
+use tokio::task::spawn_blocking;
+use url::Url;
+
+use ion::{Error, ErrorKind, Result};
+
+use crate::cache::{Cache, hash};
+use crate::globals::fetch::Connector;
+use crate::globals::fetch::request::RequestCache;
+use crate::globals::fetch::timeout::network_error;
+
+static HTTP_CACHE: OnceLock<Option<HttpCache>> = OnceLock::new();
+
+static TEMPORARY_ID: AtomicU64 = AtomicU64::new(0);
+
+static CONDITIONAL_HEADERS: [HeaderName; 5] = [IF_MATCH, IF_MODIFIED_SINCE, IF_NONE_MATCH, IF_RANGE, IF_UNMODIFIED_SINCE];
+
+/// Statuses which are cacheable by default, as specified by [RFC 9110](https://httpwg.org/specs/rfc9110.html#rfc.section.15.1).
+/// `206 Partial Content` is excluded, as partial responses are not stored.
+const HEURISTICALLY_CACHEABLE: [u16; 11] = [200, 203, 204, 300, 301, 308, 404, 405, 410, 414, 501];
+
+/// Maximum size of a response body which is stored in the cache.
+const MAX_BODY_SIZE: u64 = 64 * 1024 * 1024;
+
+type Directives = Vec<(String, Option<String>)>;
+
+/// Parses the directives of all `Cache-Control` headers, with lowercase names and unquoted arguments.
+fn cache_control(headers: &HeaderMap) -> Directives {
+	headers
+		.get_all(CACHE_CONTROL)
+		.iter()
+		.filter_map(|value| value.to_str().ok())
+		.flat_map(|value| value.split(','))
+		.filter_map(|directive| {
+			let (name, argument) = match directive.split_once('=') {
+				Some((name, argument)) => (name, Some(String::from(argument.trim().trim_matches('"')))),
+				None => (directive, None),
+			};
+			let name = name.trim().to_ascii_lowercase();
+			(!name.is_empty()).then_some((name, argument))
+		})
+		.collect()
+}
+
+fn has_directive(directives: &Directives, name: &str) -> bool {
+	directives.iter().any(|(directive, _)| directive == name)
+}
+
+fn directive_seconds(directives: &Directives, name: &str) -> Option<i64> {
+	directives
+		.iter()
+		.find(|(directive, _)| directive == name)
+		.and_then(|(_, argument)| argument.as_ref()?.parse().ok())
+}
+
+fn header_date(headers: &HeaderMap, name: HeaderName) -> Option<i64> {
+	let value = headers.get(name)?.to_str().ok()?;
+	DateTime::parse_from_rfc2822(value).ok().map(|date| date.timestamp())
+}
+
+/// Combines all values of a header into a single value, as specified by [RFC 9110](https://httpwg.org/specs/rfc9110.html#rfc.section.5.3).
+fn combined_header(headers: &HeaderMap, name: &HeaderName) -> Option<Vec<u8>> {
+	let mut values = headers.get_all(name).iter();
+	let mut combined = values.next()?.as_bytes().to_vec();
+	for value in values {
+		combined.extend_from_slice(b", ");
+		combined.extend_from_slice(value.as_bytes());
+	}
+	Some(combined)
+}
+
+fn vary_headers(headers: &HeaderMap) -> Vec<String> {
+	headers
+		.get_all(VARY)
+		.iter()
+		.filter_map(|value| value.to_str().ok())
+		.flat_map(|value| value.split(','))
+		.map(|name| name.trim().to_ascii_lowercase())
+		.filter(|name| !name.is_empty())
+		.collect()
+}
+
+/// Checks if a response can be stored, as specified by [RFC 9111](https://httpwg.org/specs/rfc9111.html#response.cacheability).
+fn is_storable(status: StatusCode, headers: &HeaderMap) -> bool {
+	let directives = cache_control(headers);
+	if has_directive(&directives, "no-store") || vary_headers(headers).iter().any(|name| name == "*") {
+		return false;
+	}
+
+	let explicit = has_directive(&directives, "max-age") || headers.contains_key(EXPIRES);
+	let cacheable = HEURISTICALLY_CACHEABLE.contains(&status.as_u16());
+	cacheable || (explicit && status.as_u16() >= 200 && status != StatusCode::PARTIAL_CONTENT && status != StatusCode::NOT_MODIFIED)
+}
+
+/// Checks if a stored response could be reused, either because it has an explicit freshness lifetime, or because it can be validated.
+/// Responses without either would be stale as soon as they are stored, and could never be used.
+fn is_reusable(headers: &HeaderMap) -> bool {
+	has_directive(&cache_control(headers), "max-age")
+		|| headers.contains_key(EXPIRES)
+		|| headers.contains_key(ETAG)
+		|| headers.contains_key(LAST_MODIFIED)
+}
+
+/// Checks if a response to a request can be shared with other clients.
+/// Responses to requests with `Authorization` or `Cookie` headers are specific to those credentials, unless they are explicitly `public`.
+fn is_shareable(request_headers: &HeaderMap, headers: &HeaderMap) -> bool {
+	let credentials = request_headers.contains_key(AUTHORIZATION) || request_headers.contains_key(COOKIE);
+	!credentials || has_directive(&cache_control(headers), "public")
+}
+
+/// Returns a path for a temporary file next to the given path, which is unique within the process.
+fn temporary_path(path: &Path) -> PathBuf {
+	let id = TEMPORARY_ID.fetch_add(1, Ordering::Relaxed);
+	let extension = path.extension().and_then(|extension| extension.to_str()).unwrap_or_default();
+	path.with_extension(format!("{}.{}.{}.tmp", extension, std::process::id(), id))
+}
+
+#[derive(Clone)]
+struct CachedResponse {
+	status: StatusCode,
+	headers: HeaderMap,
+	vary: Vec<(HeaderName, Option<Vec<u8>>)>,
+	request_time: i64,
+	response_time: i64,
+	body: Bytes,
+}
+
+impl CachedResponse {
+	/// Creates a stored response, without `Set-Cookie` headers, as they must not be replayed from the cache.
+	fn new(
+		status: StatusCode, mut headers: HeaderMap, request_headers: &HeaderMap, request_time: i64, response_time: i64, body: Bytes,
+	) -> CachedResponse {
+		headers.remove(SET_COOKIE);
+		let vary = vary_headers(&headers)
+			.into_iter()
+			.filter_map(|name| HeaderName::from_bytes(name.as_bytes()).ok())
+			.map(|name| {
+				let value = combined_header(request_headers, &name);
+				(name, value)
+			})
+			.collect();
+
+		CachedResponse {
+			status,
+			headers,
+			vary,
+			request_time,
+			response_time,
+			body,
+		}
+	}
+
+	/// Serialises the metadata of the response, with one field per line.
+	fn serialise(&self) -> Vec<u8> {
+		let mut meta = format!(
+			"status {}\nrequest-time {}\nresponse-time {}\n",
+			self.status.as_u16(),
+			self.request_time,
+			self.response_time
+		)
+		.into_bytes();
+
+		for (name, value) in &self.vary {
+			meta.extend_from_slice(format!("vary {}", name).as_bytes());
+			if let Some(value) = value {
+				meta.extend_from_slice(b": ");
+				meta.extend_from_slice(value);
+			}
+			meta.push(b'\n');
+		}
+		for (name, value) in &self.headers {
+			meta.extend_from_slice(format!("header {}: ", name).as_bytes());
+			meta.extend_from_slice(value.as_bytes());
+			meta.push(b'\n');
+		}
+		meta
+	}
+
+	fn deserialise(meta: &[u8], body: Bytes) -> Option<CachedResponse> {
+		let mut status = None;
+		let mut request_time = None;
+		let mut response_time = None;
+		let mut vary = Vec::new();
+		let mut headers = HeaderMap::new();
+
+		for line in meta.split(|&byte| byte == b'\n').filter(|line| !line.is_empty()) {
+			let space = line.iter().position(|&byte| byte == b' ')?;
+			let (field, value) = (&line[..space], &line[space + 1..]);
+			match field {
+				b"status" => status = Some(StatusCode::from_bytes(value).ok()?),
+				b"request-time" => request_time = Some(str::from_utf8(value).ok()?.parse().ok()?),
+				b"response-time" => response_time = Some(str::from_utf8(value).ok()?.parse().ok()?),
+				b"vary" => match value.windows(2).position(|window| window == b": ") {
+					Some(separator) => vary.push((HeaderName::from_bytes(&value[..separator]).ok()?, Some(value[separator + 2..].to_vec()))),
+					None => vary.push((HeaderName::from_bytes(value).ok()?, None)),
+				},
+				b"header" => {
+					let separator = value.windows(2).position(|window| window == b": ")?;
+					let name = HeaderName::from_bytes(&value[..separator]).ok()?;
+					headers.append(name, HeaderValue::from_bytes(&value[separator + 2..]).ok()?);
+				}
+				_ => return None,
+			}
+		}
+
+		Some(CachedResponse {
+			status: status?,
+			headers,
+			vary,
+			request_time: request_time?,
+			response_time: response_time?,
+			body,
+		})
+	}
+
+	/// Checks if the request headers nominated by `Vary` match those of the original request.
+	fn matches(&self, headers: &HeaderMap) -> bool {
+		self.vary.iter().all(|(name, value)| combined_header(headers, name) == *value)
+	}
+
+	/// Calculates the freshness lifetime, as specified by [RFC 9111](https://httpwg.org/specs/rfc9111.html#calculating.freshness.lifetime).
+	fn freshness_lifetime(&self) -> i64 {
+		let directives = cache_control(&self.headers);
+		if let Some(max_age) = directive_seconds(&directives, "max-age") {
+			return max_age;
+		}
+
+		let date = header_date(&self.headers, DATE).unwrap_or(self.response_time);
+		if self.headers.contains_key(EXPIRES) {
+			return header_date(&self.headers, EXPIRES).map(|expires| expires - date).unwrap_or(0);
+		}
+
+		match header_date(&self.headers, LAST_MODIFIED) {
+			Some(last_modified) if HEURISTICALLY_CACHEABLE.contains(&self.status.as_u16()) => ((date - last_modified) / 10).max(0),
+			_ => 0,
+		}
+	}
+
+	/// Calculates the current age, as specified by [RFC 9111](https://httpwg.org/specs/rfc9111.html#age.calculations).
+	fn current_age(&self, now: i64) -> i64 {
+		let age = self
+			.headers
+			.get(AGE)
+			.and_then(|age| age.to_str().ok())
+			.and_then(|age| age.trim().parse().ok())
+			.unwrap_or(0);
+		let date = header_date(&self.headers, DATE).unwrap_or(self.response_time);
+
+		let apparent_age = (self.response_time - date).max(0);
+		let corrected_age = age + (self.response_time - self.request_time);
+		apparent_age.max(corrected_age) + (now - self.response_time)
+	}
+
+	fn is_fresh(&self, now: i64) -> bool {
+		!has_directive(&cache_control(&self.headers), "no-cache") && self.freshness_lifetime() > self.current_age(now)
+	}
+
+	/// Adds validators of the response to a request, returning `false` if the response has none.
+	fn add_validators(&self, headers: &mut HeaderMap) -> bool {
+		let mut validated = false;
+		if let Some(etag) = self.headers.get(ETAG) {
+			headers.insert(IF_NONE_MATCH, etag.clone());
+			validated = true;
+		}
+		if let Some(last_modified) = self.headers.get(LAST_MODIFIED) {
+			headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+			validated = true;
+		}
+		validated
+	}
+
+	/// Updates the stored headers with those of a `304 Not Modified` response, as specified by [RFC 9111](https://httpwg.org/specs/rfc9111.html#freshening.responses).
+	fn freshen(&mut self, headers: &HeaderMap, request_time: i64, response_time: i64) {
+		for name in headers.keys() {
+			if name != CONTENT_LENGTH && name != SET_COOKIE {
+				self.headers.remove(name);
+				for value in headers.get_all(name) {
+					self.headers.append(name, value.clone());
+				}
+			}
+		}
+		self.request_time = request_time;
+		self.response_time = response_time;
+	}
+
+	fn to_response(&self, now: i64) -> Result<hyper::Response<Body>> {
+		let mut response = hyper::Response::builder().status(self.status).body(Body::from(self.body.clone()))?;
+		*response.headers_mut() = self.headers.clone();
+		response.headers_mut().insert(AGE, HeaderValue::from(self.current_age(now).max(0)));
+		Ok(response)
+	}
+}
+
+/// On-disk HTTP cache, stored within the [Cache] directory.
+/// Responses are keyed by URL, and only the latest variant of a response is kept.
+/// The cache is shared by all clients and runs, so responses to requests with credentials are only stored if they are `public`.
+struct HttpCache {
+	dir: PathBuf,
+}
+
+impl HttpCache {
+	fn global() -> Option<&'static HttpCache> {
+		HTTP_CACHE
+			.get_or_init(|| {
+				Cache::new().map(|cache| {
+					let dir = cache.dir().join("http");
+					let _ = create_dir_all(&dir);
+					HttpCache { dir }
+				})
+			})
+			.as_ref()
+	}
+
+	fn paths(&self, url: &Url) -> (PathBuf, PathBuf) {
+		let mut url = url.clone();
+		url.set_fragment(None);
+		let key = hash(url.as_str(), None);
+		(self.dir.join(format!("{}.meta", key)), self.dir.join(format!("{}.body", key)))
+	}
+
+	fn get(&self, url: &Url) -> Option<CachedResponse> {
+		let (meta, body) = self.paths(url);
+		let meta = read(meta).ok()?;
+		let body = read(body).ok()?;
+		CachedResponse::deserialise(&meta, Bytes::from(body))
+	}
+
+	/// Creates a temporary file, which the body of a response is written to before it is committed.
+	fn create_body(&self, url: &Url) -> io::Result<(PathBuf, File)> {
+		let (_, body) = self.paths(url);
+		let temporary = temporary_path(&body);
+		let file = File::create(&temporary)?;
+		Ok((temporary, file))
+	}
+
+	/// Commits a completely written body, and the metadata of its response.
+	/// The body is moved into place before the metadata is written, so that metadata is never read with a missing or partial body.
+	fn commit(&self, url: &Url, temporary: &Path, response: &CachedResponse) -> io::Result<()> {
+		let (meta, body) = self.paths(url);
+		let _ = remove_file(&meta);
+		rename(temporary, body)?;
+		self.put_meta(url, response)
+	}
+
+	/// Writes the metadata of a response, whose body is already stored.
+	fn put_meta(&self, url: &Url, response: &CachedResponse) -> io::Result<()> {
+		let (meta, _) = self.paths(url);
+		let temporary = temporary_path(&meta);
+		write(&temporary, response.serialise())?;
+		rename(temporary, meta)
+	}
+
+	fn remove(&self, url: &Url) {
+		let (meta, body) = self.paths(url);
+		let _ = remove_file(meta);
+		let _ = remove_file(body);
+	}
+}
+
+async fn get(cache: &'static HttpCache, url: &Url) -> Option<CachedResponse> {
+	let url = url.clone();
+	spawn_blocking(move || cache.get(&url)).await.ok().flatten()
+}
+
+async fn put_meta(cache: &'static HttpCache, url: &Url, response: CachedResponse) {
+	let url = url.clone();
+	let _ = spawn_blocking(move || cache.put_meta(&url, &response)).await;
+}
+
+async fn remove(cache: &'static HttpCache, url: &Url) {
+	let url = url.clone();
+	let _ = spawn_blocking(move || cache.remove(&url)).await;
+}
+
+/// Writes the body of a response to the cache as it is read.
+/// The response is committed to the cache once its body has been read completely,
+/// and is discarded if reading fails, the body is too large, or the body is dropped before it is read completely.
+struct CacheWriter {
+	cache: &'static HttpCache,
+	url: Url,
+	response: CachedResponse,
+	temporary: PathBuf,
+	file: Option<File>,
+	size: u64,
+}
+
+impl CacheWriter {
+	async fn write(&mut self, chunk: Bytes) {
+		self.size += chunk.len() as u64;
+		let file = match self.file.take() {
+			Some(file) if self.size <= MAX_BODY_SIZE => file,
+			_ => return,
+		};
+
+		let write = spawn_blocking(move || {
+			let mut file = file;
+			file.write_all(&chunk).map(|_| file)
+		});
+		if let Ok(Ok(file)) = write.await {
+			self.file = Some(file);
+		}
+	}
+
+	async fn commit(mut self) {
+		if let Some(file) = self.file.take() {
+			drop(file);
+			let cache = self.cache;
+			let url = self.url.clone();
+			let temporary = take(&mut self.temporary);
+			let response = self.response.clone();
+			let commit = spawn_blocking(move || {
+				let result = cache.commit(&url, &temporary, &response);
+				if result.is_err() {
+					let _ = remove_file(&temporary);
+				}
+			});
+			let _ = commit.await;
+		}
+	}
+}
+
+impl Drop for CacheWriter {
+	fn drop(&mut self) {
+		if !self.temporary.as_os_str().is_empty() {
+			let _ = remove_file(&self.temporary);
+		}
+	}
+}
+
+/// Passes the chunks of a body through, while writing them to the cache.
+fn tee_body(body: Body, writer: CacheWriter) -> Body {
+	let stream = stream::unfold(Some((body, writer)), |state| async move {
+		let (mut body, mut writer) = state?;
+		match body.data().await {
+			Some(Ok(chunk)) => {
+				writer.write(chunk.clone()).await;
+				Some((Ok(chunk), Some((body, writer))))
+			}
+			Some(Err(error)) => Some((Err(error), None)),
+			None => {
+				writer.commit().await;
+				None
+			}
+		}
+	});
+	Body::wrap_stream(stream)
+}
+
+/// Stores a response in the cache if it is storable and could be reused.
+/// The body is not read here, and is instead written to the cache as it is read by the consumer.
+async fn store(
+	cache: &'static HttpCache, url: &Url, request_headers: &HeaderMap, response: hyper::Response<Body>, request_time: i64,
+) -> hyper::Response<Body> {
+	if !is_storable(response.status(), response.headers()) || !is_reusable(response.headers()) || !is_shareable(request_headers, response.headers()) {
+		return response;
+	}
+	if response.body().size_hint().lower() > MAX_BODY_SIZE {
+		return response;
+	}
+
+	let file = {
+		let url = url.clone();
+		spawn_blocking(move || cache.create_body(&url)).await
+	};
+	let (temporary, file) = match file {
+		Ok(Ok(file)) => file,
+		_ => return response,
+	};
+
+	let (parts, body) = response.into_parts();
+	let response_time = Utc::now().timestamp();
+	let writer = CacheWriter {
+		cache,
+		url: url.clone(),
+		response: CachedResponse::new(
+			parts.status,
+			parts.headers.clone(),
+			request_headers,
+			request_time,
+			response_time,
+			Bytes::new(),
+		),
+		temporary,
+		file: Some(file),
+		size: 0,
+	};
+	hyper::Response::from_parts(parts, tee_body(body, writer))
+}
+
+/// Sends a request through the HTTP cache, as specified by the [Fetch Standard](https://fetch.spec.whatwg.org/#http-network-or-cache-fetch).
+pub(crate) async fn cached_request(
+	client: &Client<Connector>, mut request: hyper::Request<Body>, url: &Url, mut mode: RequestCache,
+) -> Result<hyper::Response<Body>> {
+	let headers = request.headers_mut();
+	if matches!(mode, RequestCache::Default) && CONDITIONAL_HEADERS.iter().any(|name| headers.contains_key(name)) {
+		mode = RequestCache::NoStore;
+	}
+
+	let directives = cache_control(headers);
+	if has_directive(&directives, "no-store") {
+		mode = RequestCache::NoStore;
+	} else if has_directive(&directives, "no-cache") && matches!(mode, RequestCache::Default) {
+		mode = RequestCache::NoCache;
+	}
+
+	if !headers.contains_key(CACHE_CONTROL) {
+		match mode {
+			RequestCache::NoCache => {
+				headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=0"));
+			}
+			RequestCache::NoStore | RequestCache::Reload => {
+				headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+				if !headers.contains_key(PRAGMA) {
+					headers.insert(PRAGMA, HeaderValue::from_static("no-cache"));
+				}
+			}
+			_ => {}
+		}
+	}
+
+	let cache = match HttpCache::global() {
+		Some(cache) => cache,
+		None if matches!(mode, RequestCache::OnlyIfCached) => return Err(Error::new("Response is not in the cache", ErrorKind::Type)),
+		None => return Ok(client.request(request).await.map_err(network_error)?),
+	};
+
+	let method = request.method().clone();
+	if method != Method::GET {
+		let response = client.request(request).await.map_err(network_error)?;
+		let safe = method == Method::HEAD || method == Method::OPTIONS || method == Method::TRACE;
+		if !safe && (response.status().is_success() || response.status().is_redirection()) {
+			remove(cache, url).await;
+		}
+		return Ok(response);
+	}
+
+	if matches!(mode, RequestCache::NoStore) {
+		return Ok(client.request(request).await.map_err(network_error)?);
+	}
+
+	let request_headers = request.headers().clone();
+	let stored = match mode {
+		RequestCache::Reload => None,
+		_ => get(cache, url).await.filter(|stored| stored.matches(&request_headers)),
+	};
+	let now = Utc::now().timestamp();
+
+	let mut stored = match stored {
+		Some(stored) => stored,
+		None if matches!(mode, RequestCache::OnlyIfCached) => return Err(Error::new("Response is not in the cache", ErrorKind::Type)),
+		None => {
+			let response = client.request(request).await.map_err(network_error)?;
+			return Ok(store(cache, url, &request_headers, response, now).await);
+		}
+	};
+
+	match mode {
+		RequestCache::ForceCache | RequestCache::OnlyIfCached => return stored.to_response(now),
+		RequestCache::Default if stored.is_fresh(now) => return stored.to_response(now),
+		_ => {}
+	}
+
+	if !stored.add_validators(request.headers_mut()) {
+		let response = client.request(request).await.map_err(network_error)?;
+		return Ok(store(cache, url, &request_headers, response, now).await);
+	}
+
+	let response = client.request(request).await.map_err(network_error)?;
+	if response.status() == StatusCode::NOT_MODIFIED {
+		let response_time = Utc::now().timestamp();
+		stored.freshen(response.headers(), now, response_time);
+		put_meta(cache, url, stored.clone()).await;
+		stored.to_response(response_time)
+	} else {
+		Ok(store(cache, url, &request_headers, response, now).await)
+	}
+}
@@ -4,16 +4,91 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock, RwLock};
 use std::time::Duration;
 
-use hyper::client::HttpConnector;
 use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
+use rustls::ClientConfig;
 
-pub static GLOBAL_CLIENT: OnceLock<hyper::Client<HttpsConnector<HttpConnector>>> = OnceLock::new();
+use crate::globals::fetch::{
+	ClientStats, CookieJar, DEFAULT_MAX_REDIRECTS, default_client_config, ProxyConfig, ProxyConnector, Resolver, Timeouts, TrackedConnector,
+	Transport,
+};
 
-pub fn default_client() -> hyper::Client<HttpsConnector<HttpConnector>> {
-	let https = HttpsConnectorBuilder::new().with_webpki_roots().https_or_http().enable_http1().build();
+pub type Connector = TrackedConnector<HttpsConnector<ProxyConnector>>;
+
+pub static GLOBAL_CLIENT: OnceLock<FetchClient> = OnceLock::new();
+
+/// HTTP client used to send requests, along with the cookie jar shared between them and the proxies they are sent through.
+/// The statistics of the client are shared with its connector, which counts its connections.
+#[derive(Clone)]
+pub struct FetchClient {
+	pub client: hyper::Client<Connector>,
+	pub cookies: Option<Arc<CookieJar>>,
+	pub proxy: Arc<ProxyConfig>,
+	pub stats: ClientStats,
+	pub timeouts: Timeouts,
+	pub max_redirects: u8,
+	transport: Arc<RwLock<Option<Arc<dyn Transport>>>>,
+	resolver: Arc<RwLock<Option<Arc<dyn Resolver>>>>,
+}
+
+impl FetchClient {
+	pub fn new(client: hyper::Client<Connector>, proxy: Arc<ProxyConfig>, stats: ClientStats) -> FetchClient {
+		FetchClient {
+			client,
+			cookies: Some(Arc::new(CookieJar::default())),
+			proxy,
+			stats,
+			timeouts: Timeouts::default(),
+			max_redirects: DEFAULT_MAX_REDIRECTS,
+			transport: Arc::default(),
+			resolver: Arc::default(),
+		}
+	}
+
+	/// Returns the transport which sends the requests of the client.
+	pub fn transport(&self) -> Arc<dyn Transport> {
+		match &*self.transport.read().unwrap() {
+			Some(transport) => Arc::clone(transport),
+			None => Arc::new(self.client.clone()),
+		}
+	}
+
+	/// Replaces the transport of the client, and all of its clones. Passing [None] restores the network transport.
+	pub fn set_transport(&self, transport: Option<Arc<dyn Transport>>) {
+		*self.transport.write().unwrap() = transport;
+	}
+
+	/// Returns the resolver which the connections of the client use, if it has one.
+	pub fn resolver(&self) -> Option<Arc<dyn Resolver>> {
+		self.resolver.read().unwrap().clone()
+	}
+
+	/// Replaces the resolver of the client, and all of its clones. Passing [None] restores the system resolver.
+	/// Connections which are already open are reused regardless of the resolver.
+	pub fn set_resolver(&self, resolver: Option<Arc<dyn Resolver>>) {
+		*self.resolver.write().unwrap() = resolver;
+	}
+}
+
+/// Creates a TLS connector with the given configuration, which connects through the proxies of a [ProxyConfig].
+/// Its connections are counted in the [ClientStats].
+pub fn connector(proxy: Arc<ProxyConfig>, stats: ClientStats, tls: ClientConfig, http2: bool) -> Connector {
+	let builder = HttpsConnectorBuilder::new().with_tls_config(tls).https_or_http().enable_http1();
+	let connector = ProxyConnector::new(proxy);
+	let connector = if http2 {
+		builder.enable_http2().wrap_connector(connector)
+	} else {
+		builder.wrap_connector(connector)
+	};
+	TrackedConnector::new(connector, stats)
+}
+
+pub fn default_client() -> FetchClient {
+	let proxy = Arc::new(ProxyConfig::from_env());
+	let stats = ClientStats::default();
+	let https = connector(Arc::clone(&proxy), stats.clone(), default_client_config(), true);
 
 	let mut client = hyper::Client::builder();
 
@@ -22,5 +97,5 @@ pub fn default_client() -> hyper::Client<HttpsConnector<HttpConnector>> {
 	client.retry_canceled_requests(true);
 	client.set_host(false);
 
-	client.build(https)
+	FetchClient::new(client.build(https), proxy, stats)
 }
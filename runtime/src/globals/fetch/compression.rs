@@ -0,0 +1,217 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::io;
+use std::io::Write;
+use std::mem::take;
+use std::sync::{Arc, Mutex};
+
+use brotli_decompressor::DecompressorWriter;
+use bytes::Bytes;
+use flate2::write::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use futures::stream;
+use http::{HeaderMap, HeaderValue};
+use http::header::{ACCEPT_ENCODING, CONTENT_ENCODING};
+use hyper::Body;
+use hyper::body::HttpBody;
+
+use crate::globals::fetch::Response;
+
+/// Content codings which can be decoded, in the order they are sent in `Accept-Encoding`.
+const SUPPORTED_CODINGS: &str = "gzip, deflate, br";
+
+const BROTLI_BUFFER_SIZE: usize = 4096;
+
+/// Adds the `Accept-Encoding` header with the supported content codings, unless it is already present.
+pub(crate) fn add_accept_encoding_header(headers: &mut HeaderMap) {
+	if !headers.contains_key(ACCEPT_ENCODING) {
+		headers.insert(ACCEPT_ENCODING, HeaderValue::from_static(SUPPORTED_CODINGS));
+	}
+}
+
+/// Buffer shared between a decoder and the stream which drains its output.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl SharedBuffer {
+	fn take(&self) -> Vec<u8> {
+		take(&mut *self.0.lock().unwrap())
+	}
+}
+
+impl Write for SharedBuffer {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.0.lock().unwrap().extend_from_slice(buf);
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+trait Decode: Write + Send {
+	/// Finishes decoding, failing if the encoded data is incomplete.
+	fn finish(self: Box<Self>) -> io::Result<()>;
+}
+
+impl Decode for GzDecoder<SharedBuffer> {
+	fn finish(mut self: Box<Self>) -> io::Result<()> {
+		self.try_finish()
+	}
+}
+
+impl Decode for ZlibDecoder<SharedBuffer> {
+	fn finish(mut self: Box<Self>) -> io::Result<()> {
+		self.try_finish()
+	}
+}
+
+impl Decode for DeflateDecoder<SharedBuffer> {
+	fn finish(mut self: Box<Self>) -> io::Result<()> {
+		self.try_finish()
+	}
+}
+
+impl Decode for DecompressorWriter<SharedBuffer> {
+	fn finish(self: Box<Self>) -> io::Result<()> {
+		self.into_inner()
+			.map(|_| ())
+			.map_err(|_| io::Error::new(io::ErrorKind::UnexpectedEof, "Incomplete brotli stream"))
+	}
+}
+
+/// Decoder for the `deflate` coding, which should be zlib-wrapped, but is sent as raw deflate by some servers.
+/// The format is detected from the first byte, which is the compression method and info for zlib.
+struct DeflateAutoDecoder {
+	output: SharedBuffer,
+	decoder: Option<Box<dyn Decode>>,
+}
+
+impl Write for DeflateAutoDecoder {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		if buf.is_empty() {
+			return Ok(0);
+		}
+
+		let output = &self.output;
+		let decoder = self.decoder.get_or_insert_with(|| -> Box<dyn Decode> {
+			if buf[0] & 0x0F == 8 && buf[0] >> 4 <= 7 {
+				Box::new(ZlibDecoder::new(output.clone()))
+			} else {
+				Box::new(DeflateDecoder::new(output.clone()))
+			}
+		});
+		decoder.write(buf)
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		match &mut self.decoder {
+			Some(decoder) => decoder.flush(),
+			None => Ok(()),
+		}
+	}
+}
+
+impl Decode for DeflateAutoDecoder {
+	fn finish(self: Box<Self>) -> io::Result<()> {
+		match self.decoder {
+			Some(decoder) => decoder.finish(),
+			None => Ok(()),
+		}
+	}
+}
+
+struct Decoder {
+	decoder: Box<dyn Decode>,
+	output: SharedBuffer,
+}
+
+impl Decoder {
+	fn new(coding: &str) -> Option<Decoder> {
+		let output = SharedBuffer::default();
+		let decoder: Box<dyn Decode> = match coding {
+			"gzip" | "x-gzip" => Box::new(GzDecoder::new(output.clone())),
+			"deflate" => Box::new(DeflateAutoDecoder { output: output.clone(), decoder: None }),
+			"br" => Box::new(DecompressorWriter::new(output.clone(), BROTLI_BUFFER_SIZE)),
+			_ => return None,
+		};
+		Some(Decoder { decoder, output })
+	}
+}
+
+/// Passes a chunk through each decoder in turn, returning the decoded output.
+fn decode_chunk(decoders: &mut [Decoder], chunk: &[u8]) -> io::Result<Vec<u8>> {
+	let mut data = chunk.to_vec();
+	for decoder in decoders {
+		decoder.decoder.write_all(&data)?;
+		data = decoder.output.take();
+	}
+	Ok(data)
+}
+
+fn finish_decoders(decoders: Vec<Decoder>) -> io::Result<Vec<u8>> {
+	let mut data = Vec::new();
+	for mut decoder in decoders {
+		decoder.decoder.write_all(&data)?;
+		decoder.decoder.finish()?;
+		data = decoder.output.take();
+	}
+	Ok(data)
+}
+
+fn decode_body(body: Body, decoders: Vec<Decoder>) -> Body {
+	let stream = stream::unfold(Some((body, decoders, false)), |state| async move {
+		let (mut body, mut decoders, mut received) = state?;
+		loop {
+			match body.data().await {
+				Some(Ok(chunk)) => {
+					received |= !chunk.is_empty();
+					match decode_chunk(&mut decoders, &chunk) {
+						Ok(data) if data.is_empty() => {}
+						Ok(data) => return Some((Ok(Bytes::from(data)), Some((body, decoders, received)))),
+						Err(error) => return Some((Err(error), None)),
+					}
+				}
+				Some(Err(error)) => return Some((Err(io::Error::new(io::ErrorKind::Other, error)), None)),
+				None if !received => return None,
+				None => {
+					return match finish_decoders(decoders) {
+						Ok(data) if data.is_empty() => None,
+						Ok(data) => Some((Ok(Bytes::from(data)), None)),
+						Err(error) => Some((Err(error), None)),
+					};
+				}
+			}
+		}
+	});
+	Body::wrap_stream(stream)
+}
+
+/// Decodes the body of a response according to its `Content-Encoding`, as the body is read.
+/// Codings are decoded in the reverse order to which they were applied.
+/// Bodies with unsupported codings are left encoded.
+pub(crate) fn decode_response(response: &mut Response) {
+	let codings: Vec<_> = response
+		.response
+		.headers()
+		.get_all(CONTENT_ENCODING)
+		.iter()
+		.filter_map(|value| value.to_str().ok())
+		.flat_map(|value| value.split(','))
+		.map(|coding| coding.trim().to_ascii_lowercase())
+		.filter(|coding| !coding.is_empty() && coding != "identity")
+		.collect();
+	if codings.is_empty() {
+		return;
+	}
+
+	let decoders: Option<Vec<_>> = codings.iter().rev().map(|coding| Decoder::new(coding)).collect();
+	if let Some(decoders) = decoders {
+		let body = take(response.response.body_mut());
+		*response.response.body_mut() = decode_body(body, decoders);
+	}
+}
@@ -0,0 +1,316 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt::Write;
+use std::fs::{read_to_string, write};
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use http::{HeaderMap, HeaderValue};
+use http::header::{COOKIE, SET_COOKIE};
+use url::{Host, Url};
+
+use ion::Result;
+
+#[derive(Clone, Debug)]
+struct Cookie {
+	name: String,
+	value: String,
+	domain: String,
+	host_only: bool,
+	path: String,
+	expires: Option<i64>,
+	secure: bool,
+	http_only: bool,
+}
+
+impl Cookie {
+	fn is_expired(&self, now: i64) -> bool {
+		self.expires.is_some_and(|expires| expires <= now)
+	}
+
+	fn matches(&self, url: &Url, now: i64) -> bool {
+		let host = match url.host_str() {
+			Some(host) => host.to_ascii_lowercase(),
+			None => return false,
+		};
+		let domain = if self.host_only {
+			host == self.domain
+		} else {
+			domain_match(url, &host, &self.domain)
+		};
+
+		domain && path_match(url.path(), &self.path) && (!self.secure || is_secure(url)) && !self.is_expired(now)
+	}
+}
+
+fn is_secure(url: &Url) -> bool {
+	matches!(url.scheme(), "https" | "wss") || url.host_str() == Some("localhost")
+}
+
+/// Checks if a host matches a cookie domain, as specified by [RFC 6265](https://httpwg.org/specs/rfc6265.html#cookie-domain).
+fn domain_match(url: &Url, host: &str, domain: &str) -> bool {
+	host == domain
+		|| (matches!(url.host(), Some(Host::Domain(_)))
+			&& host.len() > domain.len()
+			&& host.ends_with(domain)
+			&& host.as_bytes()[host.len() - domain.len() - 1] == b'.')
+}
+
+/// Checks if a domain is a public suffix, such as `com`, `co.uk` or `github.io`, which cookies cannot be set for.
+/// Domains under unknown top-level domains are treated as having the top-level domain as their public suffix.
+fn is_public_suffix(domain: &str) -> bool {
+	psl::suffix(domain.as_bytes()).is_some_and(|suffix| suffix.as_bytes() == domain.as_bytes())
+}
+
+/// Checks if a request path matches a cookie path, as specified by [RFC 6265](https://httpwg.org/specs/rfc6265.html#cookie-path).
+fn path_match(path: &str, cookie: &str) -> bool {
+	path == cookie || (path.starts_with(cookie) && (cookie.ends_with('/') || path.as_bytes()[cookie.len()] == b'/'))
+}
+
+/// Computes the default path of a cookie, as specified by [RFC 6265](https://httpwg.org/specs/rfc6265.html#cookie-path).
+fn default_path(url: &Url) -> String {
+	let path = url.path();
+	match path.rfind('/') {
+		Some(0) | None => String::from("/"),
+		Some(index) => String::from(&path[..index]),
+	}
+}
+
+fn parse_date(date: &str) -> Option<i64> {
+	if let Ok(date) = DateTime::parse_from_rfc2822(date) {
+		return Some(date.timestamp());
+	}
+	["%a, %d-%b-%Y %H:%M:%S GMT", "%A, %d-%b-%y %H:%M:%S GMT", "%a %b %e %H:%M:%S %Y"]
+		.iter()
+		.find_map(|format| NaiveDateTime::parse_from_str(date, format).ok())
+		.map(|date| date.timestamp())
+}
+
+/// Parses a `Set-Cookie` header received from a URL, as specified by [RFC 6265bis](https://httpwg.org/http-extensions/draft-ietf-httpbis-rfc6265bis.html#name-the-set-cookie-header-field).
+/// A cookie without `=` has an empty name, and the whole pair as its value.
+fn parse_cookie(url: &Url, header: &str, now: i64) -> Option<Cookie> {
+	let mut attributes = header.split(';');
+	let pair = attributes.next()?;
+	let (name, value) = pair.split_once('=').unwrap_or(("", pair));
+	let (name, value) = (name.trim(), value.trim());
+	if name.is_empty() && value.is_empty() {
+		return None;
+	}
+
+	let host = url.host_str()?.to_ascii_lowercase();
+	let mut cookie = Cookie {
+		name: String::from(name),
+		value: String::from(value),
+		domain: host.clone(),
+		host_only: true,
+		path: default_path(url),
+		expires: None,
+		secure: false,
+		http_only: false,
+	};
+	let mut max_age = None;
+	let mut expires = None;
+
+	for attribute in attributes {
+		let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+		let value = value.trim();
+		match key.trim().to_ascii_lowercase().as_str() {
+			"expires" => expires = parse_date(value).or(expires),
+			"max-age" => {
+				if let Ok(seconds) = value.parse::<i64>() {
+					max_age = Some(if seconds <= 0 { i64::MIN } else { now.saturating_add(seconds) });
+				}
+			}
+			"domain" => {
+				let domain = value.trim_start_matches('.').to_ascii_lowercase();
+				if !domain.is_empty() {
+					if !domain_match(url, &host, &domain) {
+						return None;
+					}
+					// Cookies for a public suffix are only accepted from the suffix itself, and remain host-only.
+					if is_public_suffix(&domain) {
+						if domain != host {
+							return None;
+						}
+					} else {
+						cookie.domain = domain;
+						cookie.host_only = false;
+					}
+				}
+			}
+			"path" if value.starts_with('/') => cookie.path = String::from(value),
+			"secure" => cookie.secure = true,
+			"httponly" => cookie.http_only = true,
+			_ => {}
+		}
+	}
+	cookie.expires = max_age.or(expires);
+
+	if cookie.secure && !is_secure(url) {
+		return None;
+	}
+	if name.is_empty() && (value.starts_with("__Secure-") || value.starts_with("__Host-")) {
+		return None;
+	}
+	if name.starts_with("__Secure-") && !cookie.secure {
+		return None;
+	}
+	if name.starts_with("__Host-") && (!cookie.secure || !cookie.host_only || cookie.path != "/") {
+		return None;
+	}
+	Some(cookie)
+}
+
+/// Stores cookies received in responses, and attaches them to later requests.
+/// Cookies are kept in creation order, and can be persisted in the Netscape cookie file format.
+#[derive(Debug, Default)]
+pub struct CookieJar {
+	cookies: Mutex<Vec<Cookie>>,
+}
+
+impl CookieJar {
+	fn insert(&self, cookie: Cookie, now: i64) {
+		let mut cookies = self.cookies.lock().unwrap();
+		cookies.retain(|cookie| !cookie.is_expired(now));
+
+		let existing = cookies
+			.iter()
+			.position(|stored| stored.name == cookie.name && stored.domain == cookie.domain && stored.path == cookie.path);
+		match existing {
+			Some(index) if cookie.is_expired(now) => {
+				cookies.remove(index);
+			}
+			Some(index) => cookies[index] = cookie,
+			None if !cookie.is_expired(now) => cookies.push(cookie),
+			None => {}
+		}
+	}
+
+	/// Stores a cookie from a `Set-Cookie` header received from the given URL.
+	/// Invalid cookies, and cookies which the URL cannot set, are ignored.
+	pub fn set(&self, url: &Url, header: &str) {
+		let now = Utc::now().timestamp();
+		if let Some(cookie) = parse_cookie(url, header, now) {
+			self.insert(cookie, now);
+		}
+	}
+
+	/// Stores the cookies from the `Set-Cookie` headers of a response.
+	pub fn set_from_headers(&self, url: &Url, headers: &HeaderMap) {
+		for header in headers.get_all(SET_COOKIE) {
+			if let Ok(header) = header.to_str() {
+				self.set(url, header);
+			}
+		}
+	}
+
+	/// Returns the value of the `Cookie` header for a request to the given URL.
+	/// Cookies with longer paths are listed first, as specified by [RFC 6265](https://httpwg.org/specs/rfc6265.html#cookie).
+	pub fn get(&self, url: &Url) -> Option<String> {
+		let now = Utc::now().timestamp();
+		let cookies = self.cookies.lock().unwrap();
+		let mut cookies: Vec<_> = cookies.iter().filter(|cookie| cookie.matches(url, now)).collect();
+		if cookies.is_empty() {
+			return None;
+		}
+		cookies.sort_by(|a, b| b.path.len().cmp(&a.path.len()));
+
+		let mut header = String::new();
+		for cookie in cookies {
+			if !header.is_empty() {
+				header.push_str("; ");
+			}
+			if cookie.name.is_empty() {
+				header.push_str(&cookie.value);
+			} else {
+				let _ = write!(header, "{}={}", cookie.name, cookie.value);
+			}
+		}
+		Some(header)
+	}
+
+	/// Appends the cookies for a URL to the `Cookie` header of a request.
+	pub(crate) fn add_header(&self, headers: &mut HeaderMap, url: &Url) -> Result<()> {
+		if let Some(cookies) = self.get(url) {
+			let value = match headers.get(COOKIE) {
+				Some(existing) => {
+					let mut value = existing.as_bytes().to_vec();
+					value.extend_from_slice(b"; ");
+					value.extend_from_slice(cookies.as_bytes());
+					HeaderValue::from_bytes(&value)?
+				}
+				None => HeaderValue::from_str(&cookies)?,
+			};
+			headers.insert(COOKIE, value);
+		}
+		Ok(())
+	}
+
+	pub fn clear(&self) {
+		self.cookies.lock().unwrap().clear();
+	}
+
+	/// Saves the cookies to a file. Session cookies are saved with an expiry of 0.
+	pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+		let now = Utc::now().timestamp();
+		let mut file = String::from("# Netscape HTTP Cookie File\n");
+		for cookie in self.cookies.lock().unwrap().iter().filter(|cookie| !cookie.is_expired(now)) {
+			let _ = writeln!(
+				file,
+				"{}{}{}\t{}\t{}\t{}\t{}\t{}\t{}",
+				if cookie.http_only { "#HttpOnly_" } else { "" },
+				if cookie.host_only { "" } else { "." },
+				cookie.domain,
+				if cookie.host_only { "FALSE" } else { "TRUE" },
+				cookie.path,
+				if cookie.secure { "TRUE" } else { "FALSE" },
+				cookie.expires.unwrap_or(0),
+				cookie.name,
+				cookie.value
+			);
+		}
+		write(path, file)
+	}
+
+	/// Loads cookies from a file, replacing existing cookies with the same name, domain and path.
+	pub fn load<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+		let now = Utc::now().timestamp();
+		let file = read_to_string(path)?;
+		for line in file.lines() {
+			let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+				Some(line) => (line, true),
+				None => (line, false),
+			};
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+
+			let fields: Vec<_> = line.split('\t').collect();
+			let [domain, subdomains, path, secure, expires, name, value] = fields[..] else {
+				return Err(io::Error::new(io::ErrorKind::InvalidData, "Invalid cookie file"));
+			};
+			let expires = expires
+				.parse()
+				.map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "Invalid cookie expiry"))?;
+
+			let cookie = Cookie {
+				name: String::from(name),
+				value: String::from(value),
+				domain: domain.trim_start_matches('.').to_ascii_lowercase(),
+				host_only: subdomains != "TRUE",
+				path: String::from(path),
+				expires: (expires != 0).then_some(expires),
+				secure: secure == "TRUE",
+				http_only,
+			};
+			self.insert(cookie, now);
+		}
+		Ok(())
+	}
+}
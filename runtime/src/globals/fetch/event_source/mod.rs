@@ -0,0 +1,407 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
+use http::{HeaderMap, HeaderValue, StatusCode, Uri};
+use http::header::{ACCEPT, CACHE_CONTROL, CONTENT_TYPE};
+use hyper::Body;
+use hyper::body::HttpBody;
+use mozjs::jsapi::JSObject;
+use tokio::sync::{Notify, oneshot};
+use url::Url;
+
+pub use class::EventSource;
+use ion::{ClassDefinition, Context, Function, Object, Result, Value};
+use ion::flags::PropertyFlags;
+use parser::{EventStreamParser, ServerEvent};
+
+use crate::globals::abort::Signal;
+use crate::globals::events::{define_constants, dispatch_event, Event, EventTarget, inherit, MessageEvent, register_event_target};
+use crate::globals::fetch::{FetchBody, GLOBAL_CLIENT, Request, request_internal, Timeouts};
+use crate::globals::fetch::request::{add_host_header, RequestCache, RequestCredentials, RequestRedirect};
+use crate::globals::timers::schedule_signal;
+use crate::promise::future_to_promise;
+
+mod parser;
+
+const DEFAULT_RECONNECTION_TIME: u64 = 3000;
+
+pub const CONNECTING: u16 = 0;
+pub const OPEN: u16 = 1;
+pub const CLOSED: u16 = 2;
+
+#[derive(Default, FromValue)]
+pub struct EventSourceInit {
+	#[ion(default)]
+	with_credentials: bool,
+}
+
+#[derive(Debug)]
+enum StreamMessage {
+	Open,
+	Event(ServerEvent),
+	Error,
+	Fail,
+}
+
+/// Messages passed from the connection task to the [EventSource] on the event loop.
+#[derive(Debug, Default)]
+pub(crate) struct Connection {
+	messages: RefCell<VecDeque<StreamMessage>>,
+	notify: Notify,
+}
+
+impl Connection {
+	fn push(&self, message: StreamMessage) {
+		self.messages.borrow_mut().push_back(message);
+		self.notify.notify_one();
+	}
+
+	fn pop(&self) -> Option<StreamMessage> {
+		self.messages.borrow_mut().pop_front()
+	}
+}
+
+#[js_class]
+mod class {
+	use std::rc::Rc;
+	use std::sync::Arc;
+	use std::sync::atomic::{AtomicBool, Ordering};
+
+	use mozjs::gc::Traceable;
+	use mozjs::jsapi::{JSObject, JSTracer};
+	use tokio::task::{JoinHandle, spawn_local};
+	use url::Url;
+
+	use ion::{Context, Error, ErrorKind, Function, Object, Result};
+
+	use crate::globals::events::{EventTarget, EventTargetClass};
+	use crate::globals::fetch::event_source::{CLOSED, CONNECTING, Connection, EventSourceInit, listen, OPEN, run_connection};
+	use crate::globals::fetch::request::{check_url_scheme, RequestCredentials};
+
+	pub struct EventSource {
+		pub(crate) url: Url,
+		pub(crate) with_credentials: bool,
+		pub(crate) ready_state: u16,
+
+		pub(crate) connection: Rc<Connection>,
+		pub(crate) task: Option<JoinHandle<()>>,
+		pub(crate) terminate: Arc<AtomicBool>,
+
+		pub(crate) event_target: EventTarget,
+	}
+
+	impl EventSource {
+		#[ion(constructor)]
+		pub fn constructor(#[ion(this)] this: &Object, cx: &Context, url: String, init: Option<EventSourceInit>) -> Result<EventSource> {
+			let url = Url::parse(&url).map_err(|error| Error::new(&error.to_string(), ErrorKind::Syntax))?;
+			check_url_scheme(&url)?;
+			let init = init.unwrap_or_default();
+
+			let connection = Rc::new(Connection::default());
+			let terminate = Arc::new(AtomicBool::new(false));
+			let credentials = if init.with_credentials {
+				RequestCredentials::Include
+			} else {
+				RequestCredentials::SameOrigin
+			};
+			let task = spawn_local(run_connection(url.clone(), credentials, connection.clone(), terminate.clone()));
+			listen(cx, this, connection.clone());
+
+			Ok(EventSource {
+				url,
+				with_credentials: init.with_credentials,
+				ready_state: CONNECTING,
+
+				connection,
+				task: Some(task),
+				terminate,
+
+				event_target: EventTarget::default(),
+			})
+		}
+
+		#[ion(get)]
+		pub fn get_url(&self) -> String {
+			String::from(self.url.as_str())
+		}
+
+		#[ion(get)]
+		pub fn get_with_credentials(&self) -> bool {
+			self.with_credentials
+		}
+
+		#[ion(get)]
+		pub fn get_ready_state(&self) -> u16 {
+			self.ready_state
+		}
+
+		pub fn close(&mut self) {
+			if self.ready_state != CLOSED {
+				self.ready_state = CLOSED;
+				self.terminate.store(true, Ordering::SeqCst);
+				if let Some(task) = self.task.take() {
+					task.abort();
+				}
+				self.connection.notify.notify_one();
+			}
+		}
+
+		#[ion(get)]
+		pub fn get_onopen(&self) -> Option<*mut JSObject> {
+			self.event_target.get_event_handler("open")
+		}
+
+		#[ion(set)]
+		pub fn set_onopen<'cx>(&mut self, cx: &'cx Context, handler: Option<Function<'cx>>) {
+			self.event_target
+				.set_event_handler("open", handler.map(|handler| handler.to_object(cx).handle().get()));
+		}
+
+		#[ion(get)]
+		pub fn get_onmessage(&self) -> Option<*mut JSObject> {
+			self.event_target.get_event_handler("message")
+		}
+
+		#[ion(set)]
+		pub fn set_onmessage<'cx>(&mut self, cx: &'cx Context, handler: Option<Function<'cx>>) {
+			self.event_target
+				.set_event_handler("message", handler.map(|handler| handler.to_object(cx).handle().get()));
+		}
+
+		#[ion(get)]
+		pub fn get_onerror(&self) -> Option<*mut JSObject> {
+			self.event_target.get_event_handler("error")
+		}
+
+		#[ion(set)]
+		pub fn set_onerror<'cx>(&mut self, cx: &'cx Context, handler: Option<Function<'cx>>) {
+			self.event_target
+				.set_event_handler("error", handler.map(|handler| handler.to_object(cx).handle().get()));
+		}
+	}
+
+	impl EventTargetClass for EventSource {
+		fn event_target(&mut self) -> &mut EventTarget {
+			&mut self.event_target
+		}
+	}
+
+	unsafe impl Traceable for EventSource {
+		#[inline]
+		unsafe fn trace(&self, trc: *mut JSTracer) {
+			unsafe { self.event_target.trace(trc) }
+		}
+	}
+}
+
+/// Waits for messages from the connection and dispatches them to the [EventSource].
+/// The [EventSource] is kept alive until it is closed.
+fn listen(cx: &Context, this: &Object, connection: Rc<Connection>) {
+	let this: Object<'static> = unsafe { std::mem::transmute(Object::from(cx.root_persistent_object(this.handle().get()))) };
+	arm(cx, Rc::new(this), connection);
+}
+
+fn arm(cx: &Context, this: Rc<Object<'static>>, connection: Rc<Connection>) {
+	let notified = connection.clone();
+	let promise = future_to_promise(cx, async move {
+		notified.notify.notified().await;
+		Ok::<_, ()>(())
+	});
+
+	let callback = move |cx: &Context| -> Result<()> {
+		let result = dispatch_messages(cx, &this, &connection);
+
+		if EventSource::get_private(&this).ready_state == CLOSED {
+			cx.unroot_persistent_object(this.handle().get());
+		} else {
+			if !connection.messages.borrow().is_empty() {
+				connection.notify.notify_one();
+			}
+			arm(cx, this.clone(), connection.clone());
+		}
+		result
+	};
+
+	promise.add_reactions(
+		cx,
+		Some(Function::from_closure(
+			cx,
+			"",
+			Box::new(move |args| {
+				let cx = args.cx();
+				callback(cx).map(|_| Value::undefined(cx)).map_err(Into::into)
+			}),
+			0,
+			PropertyFlags::empty(),
+		)),
+		None,
+	);
+}
+
+fn dispatch_messages(cx: &Context, this: &Object, connection: &Connection) -> Result<()> {
+	// The private is fetched again for each message, as listeners may access the EventSource while events are dispatched.
+	while EventSource::get_private(this).ready_state != CLOSED {
+		let message = match connection.pop() {
+			Some(message) => message,
+			None => break,
+		};
+
+		let event_source = EventSource::get_private(this);
+		match message {
+			StreamMessage::Open => {
+				event_source.ready_state = OPEN;
+				dispatch(cx, this, Event::new_object(cx, Event::new_trusted("open")))?;
+			}
+			StreamMessage::Event(event) => {
+				if event_source.ready_state == OPEN {
+					let origin = event_source.url.origin().ascii_serialization();
+					let data = Value::string(cx, &event.data).get();
+					let message = MessageEvent::new_trusted(&event.event, data, origin, event.last_event_id);
+					dispatch(cx, this, MessageEvent::new_object(cx, message))?;
+				}
+			}
+			StreamMessage::Error => {
+				event_source.ready_state = CONNECTING;
+				dispatch(cx, this, Event::new_object(cx, Event::new_trusted("error")))?;
+			}
+			StreamMessage::Fail => {
+				event_source.close();
+				dispatch(cx, this, Event::new_object(cx, Event::new_trusted("error")))?;
+			}
+		}
+	}
+	Ok(())
+}
+
+fn dispatch(cx: &Context, this: &Object, event: *mut JSObject) -> Result<()> {
+	let event = Object::from(cx.root_object(event));
+	dispatch_event(cx, this, &event).map(|_| ())
+}
+
+pub(crate) async fn run_connection(url: Url, credentials: RequestCredentials, connection: Rc<Connection>, terminate: Arc<AtomicBool>) {
+	let mut parser = EventStreamParser::default();
+	let mut reconnection_time = DEFAULT_RECONNECTION_TIME;
+
+	loop {
+		if !stream_events(&url, credentials, &connection, &mut parser, &mut reconnection_time).await {
+			connection.push(StreamMessage::Fail);
+			return;
+		}
+		connection.push(StreamMessage::Error);
+
+		match reconnect_after(reconnection_time, terminate.clone()) {
+			Some(delay) if delay.await.is_ok() => {}
+			_ => {
+				connection.push(StreamMessage::Fail);
+				return;
+			}
+		}
+	}
+}
+
+/// Streams events from a single connection to the server.
+/// Returns whether the connection should be reestablished.
+async fn stream_events(
+	url: &Url, credentials: RequestCredentials, connection: &Connection, parser: &mut EventStreamParser, reconnection_time: &mut u64,
+) -> bool {
+	let request = match event_stream_request(url, credentials, parser.last_event_id()) {
+		Ok(request) => request,
+		Err(_) => return false,
+	};
+	let client = GLOBAL_CLIENT.get().unwrap().clone();
+
+	let mut response = match request_internal(request, client).await {
+		Ok(response) => response,
+		Err(_) => return true,
+	};
+	if response.response.status() != StatusCode::OK || !is_event_stream(response.response.headers()) {
+		return false;
+	}
+
+	parser.reset();
+	connection.push(StreamMessage::Open);
+
+	let body = response.response.body_mut();
+	while let Some(chunk) = body.data().await {
+		let chunk = match chunk {
+			Ok(chunk) => chunk,
+			Err(_) => return true,
+		};
+		for event in parser.feed(&chunk) {
+			connection.push(StreamMessage::Event(event));
+		}
+		if let Some(time) = parser.take_retry() {
+			*reconnection_time = time;
+		}
+	}
+
+	true
+}
+
+fn event_stream_request(url: &Url, credentials: RequestCredentials, last_event_id: &str) -> Result<Request> {
+	let mut request = hyper::Request::builder().uri(Uri::from_str(url.as_str())?).body(Body::empty())?;
+
+	let headers = request.headers_mut();
+	headers.insert(ACCEPT, HeaderValue::from_static("text/event-stream"));
+	headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+	if !last_event_id.is_empty() {
+		headers.insert("last-event-id", HeaderValue::from_str(last_event_id)?);
+	}
+	add_host_header(headers, url, true)?;
+
+	Ok(Request {
+		request,
+		body: FetchBody::default(),
+		body_used: false,
+
+		credentials,
+		cache: RequestCache::NoStore,
+		redirect: RequestRedirect::Follow,
+		integrity: None,
+		signal: Signal::default(),
+		url: url.clone(),
+		decompress: true,
+		timeouts: Timeouts::default(),
+		upload_progress: None,
+		max_redirects: None,
+		redirect_hook: None,
+		signal_object: None,
+		headers_object: None,
+	})
+}
+
+fn is_event_stream(headers: &HeaderMap) -> bool {
+	headers
+		.get(CONTENT_TYPE)
+		.and_then(|content_type| content_type.to_str().ok())
+		.and_then(|content_type| content_type.split(';').next())
+		.map(|mime| mime.trim().eq_ignore_ascii_case("text/event-stream"))
+		.unwrap_or(false)
+}
+
+/// Schedules reconnection after the reconnection time has elapsed, on the macrotask queue.
+fn reconnect_after(time: u64, terminate: Arc<AtomicBool>) -> Option<oneshot::Receiver<()>> {
+	let (sender, receiver) = oneshot::channel();
+	let callback = Box::new(move || {
+		let _ = sender.send(());
+	});
+
+	schedule_signal(callback, terminate, time).ok().map(|_| receiver)
+}
+
+pub fn define(cx: &Context, global: &mut Object) -> bool {
+	register_event_target::<EventSource>();
+	EventSource::init_class(cx, global).0
+		&& inherit::<EventSource, EventTarget>(cx, global)
+		&& define_constants::<EventSource>(cx, global, &[("CONNECTING", CONNECTING), ("OPEN", OPEN), ("CLOSED", CLOSED)])
+}
@@ -0,0 +1,138 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::mem::take;
+
+const BOM: &str = "\u{FEFF}";
+
+/// Event dispatched from a `text/event-stream`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ServerEvent {
+	pub(crate) event: String,
+	pub(crate) data: String,
+	pub(crate) last_event_id: String,
+}
+
+/// Incremental Parser for the `text/event-stream` format.
+/// Refer to the [HTML Standard](https://html.spec.whatwg.org/multipage/server-sent-events.html#event-stream-interpretation) for more details.
+#[derive(Debug, Default)]
+pub struct EventStreamParser {
+	buffer: Vec<u8>,
+	started: bool,
+	skip_line_feed: bool,
+
+	event: String,
+	data: String,
+	last_event_id_buffer: String,
+	last_event_id: String,
+	retry: Option<u64>,
+}
+
+impl EventStreamParser {
+	pub fn new(last_event_id: String) -> EventStreamParser {
+		EventStreamParser {
+			last_event_id_buffer: last_event_id.clone(),
+			last_event_id,
+			..EventStreamParser::default()
+		}
+	}
+
+	/// Resets the parser for a new connection, keeping the id of the last dispatched event.
+	pub fn reset(&mut self) {
+		let last_event_id = take(&mut self.last_event_id);
+		*self = EventStreamParser::new(last_event_id);
+	}
+
+	/// Returns the id of the last dispatched event, which is sent when reconnecting.
+	pub fn last_event_id(&self) -> &str {
+		&self.last_event_id
+	}
+
+	/// Returns the reconnection time received from the stream, if it has changed.
+	pub fn take_retry(&mut self) -> Option<u64> {
+		self.retry.take()
+	}
+
+	/// Feeds a chunk of the stream into the parser, returning all events completed by the chunk.
+	pub fn feed(&mut self, chunk: &[u8]) -> Vec<ServerEvent> {
+		let mut events = Vec::new();
+		let mut start = 0;
+
+		for (i, byte) in chunk.iter().enumerate() {
+			match byte {
+				b'\n' if self.skip_line_feed => {
+					self.skip_line_feed = false;
+					start = i + 1;
+				}
+				b'\n' | b'\r' => {
+					self.buffer.extend_from_slice(&chunk[start..i]);
+					let line = take(&mut self.buffer);
+					if let Some(event) = self.process_line(&line) {
+						events.push(event);
+					}
+					self.skip_line_feed = *byte == b'\r';
+					start = i + 1;
+				}
+				_ => self.skip_line_feed = false,
+			}
+		}
+		self.buffer.extend_from_slice(&chunk[start..]);
+
+		events
+	}
+
+	fn process_line(&mut self, line: &[u8]) -> Option<ServerEvent> {
+		let line = String::from_utf8_lossy(line);
+		let mut line = &*line;
+		if !self.started {
+			self.started = true;
+			line = line.strip_prefix(BOM).unwrap_or(line);
+		}
+
+		if line.is_empty() {
+			return self.dispatch();
+		} else if line.starts_with(':') {
+			return None;
+		}
+
+		let (field, value) = match line.split_once(':') {
+			Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+			None => (line, ""),
+		};
+
+		match field {
+			"event" => self.event = String::from(value),
+			"data" => {
+				self.data.push_str(value);
+				self.data.push('\n');
+			}
+			"id" if !value.contains('\0') => self.last_event_id_buffer = String::from(value),
+			"retry" if !value.is_empty() && value.bytes().all(|b| b.is_ascii_digit()) => self.retry = value.parse().ok(),
+			_ => {}
+		}
+
+		None
+	}
+
+	fn dispatch(&mut self) -> Option<ServerEvent> {
+		self.last_event_id.clone_from(&self.last_event_id_buffer);
+		let event = take(&mut self.event);
+		let mut data = take(&mut self.data);
+		if data.is_empty() {
+			return None;
+		}
+		if data.ends_with('\n') {
+			data.pop();
+		}
+
+		let event = if event.is_empty() { String::from("message") } else { event };
+		Some(ServerEvent {
+			event,
+			data,
+			last_event_id: self.last_event_id.clone(),
+		})
+	}
+}
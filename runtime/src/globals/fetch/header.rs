@@ -5,13 +5,127 @@
  */
 
 use std::str::FromStr;
+use std::vec;
 
-use hyper::header::{HeaderMap, HeaderName, HeaderValue};
+use hyper::header::{HeaderMap, HeaderName, HeaderValue, SET_COOKIE};
 
 pub use class::*;
-use ion::{Array, Context, Error, ErrorKind, Object, OwnedKey, Result, Value};
+use ion::{Array, Context, Error, ErrorKind, JSIterator, Object, OwnedKey, Result, Value};
 use ion::conversions::{FromValue, ToValue};
 
+const FORBIDDEN_REQUEST_HEADERS: [&str; 21] = [
+	"accept-charset",
+	"accept-encoding",
+	"access-control-request-headers",
+	"access-control-request-method",
+	"connection",
+	"content-length",
+	"cookie",
+	"cookie2",
+	"date",
+	"dnt",
+	"expect",
+	"host",
+	"keep-alive",
+	"origin",
+	"referer",
+	"set-cookie",
+	"te",
+	"trailer",
+	"transfer-encoding",
+	"upgrade",
+	"via",
+];
+
+const METHOD_OVERRIDE_HEADERS: [&str; 3] = ["x-http-method", "x-http-method-override", "x-method-override"];
+
+const FORBIDDEN_RESPONSE_HEADERS: [&str; 2] = ["set-cookie", "set-cookie2"];
+
+/// Guard of a [Headers] object, which determines the headers which can be modified.
+/// Modifications of headers which are forbidden by the guard are ignored.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum HeadersGuard {
+	#[default]
+	None,
+	Request,
+	RequestNoCors,
+	Response,
+	Immutable,
+}
+
+impl HeadersGuard {
+	/// Checks if the header can be modified. The value is [None] when the header is deleted.
+	fn allows(self, name: &HeaderName, value: Option<&[u8]>) -> bool {
+		match self {
+			HeadersGuard::None => true,
+			HeadersGuard::Request => !is_forbidden_request_header(name, value.unwrap_or_default()),
+			HeadersGuard::RequestNoCors => match value {
+				Some(value) => is_no_cors_safelisted_header(name, value),
+				None => is_no_cors_safelisted_name(name),
+			},
+			HeadersGuard::Response => !FORBIDDEN_RESPONSE_HEADERS.contains(&name.as_str()),
+			HeadersGuard::Immutable => false,
+		}
+	}
+
+	fn from_u32(guard: u32) -> Result<HeadersGuard> {
+		use HeadersGuard as HG;
+		[HG::None, HG::Request, HG::RequestNoCors, HG::Response, HG::Immutable]
+			.get(guard as usize)
+			.copied()
+			.ok_or_else(|| Error::new("Invalid Headers Guard", ErrorKind::Type))
+	}
+}
+
+fn trim_whitespace(bytes: &[u8]) -> &[u8] {
+	let start = bytes.iter().position(|byte| !byte.is_ascii_whitespace()).unwrap_or(bytes.len());
+	let end = bytes.iter().rposition(|byte| !byte.is_ascii_whitespace()).map_or(start, |end| end + 1);
+	&bytes[start..end]
+}
+
+fn is_forbidden_request_header(name: &HeaderName, value: &[u8]) -> bool {
+	let name = name.as_str();
+	if FORBIDDEN_REQUEST_HEADERS.contains(&name) || name.starts_with("proxy-") || name.starts_with("sec-") {
+		return true;
+	}
+	METHOD_OVERRIDE_HEADERS.contains(&name)
+		&& value.split(|byte| *byte == b',').any(|method| {
+			let method = trim_whitespace(method);
+			[&b"CONNECT"[..], b"TRACE", b"TRACK"]
+				.iter()
+				.any(|forbidden| method.eq_ignore_ascii_case(forbidden))
+		})
+}
+
+fn is_cors_unsafe_byte(byte: u8) -> bool {
+	(byte < 0x20 && byte != b'\t') || b"\"():<>?@[\\]{}\x7F".contains(&byte)
+}
+
+fn is_no_cors_safelisted_name(name: &HeaderName) -> bool {
+	matches!(name.as_str(), "accept" | "accept-language" | "content-language" | "content-type")
+}
+
+fn is_no_cors_safelisted_header(name: &HeaderName, value: &[u8]) -> bool {
+	if value.len() > 128 {
+		return false;
+	}
+	match name.as_str() {
+		"accept" => !value.iter().copied().any(is_cors_unsafe_byte),
+		"accept-language" | "content-language" => value.iter().all(|byte| byte.is_ascii_alphanumeric() || b" *,-.;=".contains(byte)),
+		"content-type" => {
+			if value.iter().copied().any(is_cors_unsafe_byte) {
+				return false;
+			}
+			let essence = value.split(|byte| *byte == b';').next().unwrap_or_default();
+			let essence = trim_whitespace(essence);
+			[&b"application/x-www-form-urlencoded"[..], b"multipart/form-data", b"text/plain"]
+				.iter()
+				.any(|safelisted| essence.eq_ignore_ascii_case(safelisted))
+		}
+		_ => false,
+	}
+}
+
 #[derive(FromValue)]
 pub enum Header {
 	#[ion(inherit)]
@@ -94,19 +208,62 @@ impl<'cx> FromValue<'cx> for HeadersObject {
 impl HeadersInit {
 	pub(crate) fn into_headers(self) -> Result<Headers> {
 		match self {
-			HeadersInit::Existing(existing) => {
-				let headers = existing.headers;
-				Ok(Headers { headers, readonly: existing.readonly })
+			HeadersInit::Existing(existing) => Ok(Headers::new(existing.headers, HeadersGuard::None)),
+			HeadersInit::Array(vec) => Headers::from_array(vec, HeadersGuard::None),
+			HeadersInit::Object(object) => Ok(Headers::new(object.headers, HeadersGuard::None)),
+		}
+	}
+
+	/// Creates headers with the guard, ignoring the headers which the guard forbids.
+	pub(crate) fn into_guarded_headers(self, guard: HeadersGuard) -> Result<Headers> {
+		let headers = self.into_headers()?;
+		let mut guarded = Headers::new(HeaderMap::new(), guard);
+		for (name, value) in headers.headers {
+			if let Some(name) = name {
+				guarded.append_internal(name, value)?;
 			}
-			HeadersInit::Array(vec) => Headers::from_array(vec, false),
-			HeadersInit::Object(object) => Ok(Headers { headers: object.headers, readonly: false }),
 		}
+		Ok(guarded)
 	}
 }
 
 impl Default for HeadersInit {
 	fn default() -> HeadersInit {
-		HeadersInit::Existing(Headers::new(HeaderMap::new(), false))
+		HeadersInit::Existing(Headers::new(HeaderMap::new(), HeadersGuard::None))
+	}
+}
+
+#[derive(Clone, Copy)]
+enum IteratorKind {
+	Entries,
+	Keys,
+	Values,
+}
+
+/// Iterates over the entries of [Headers], as they were sorted and combined when the iterator was created.
+pub struct HeadersIterator {
+	entries: vec::IntoIter<(String, String)>,
+	kind: IteratorKind,
+}
+
+impl HeadersIterator {
+	fn new(headers: &Headers, kind: IteratorKind) -> HeadersIterator {
+		HeadersIterator {
+			entries: headers.sorted_entries().into_iter(),
+			kind,
+		}
+	}
+}
+
+impl JSIterator for HeadersIterator {
+	fn next_value<'cx>(&mut self, cx: &'cx Context, _: &Value<'cx>) -> Option<Value<'cx>> {
+		let (name, value) = self.entries.next()?;
+
+		match self.kind {
+			IteratorKind::Entries => Some(HeaderEntry { name, value }.as_value(cx)),
+			IteratorKind::Keys => Some(name.as_value(cx)),
+			IteratorKind::Values => Some(value.as_value(cx)),
+		}
 	}
 }
 
@@ -116,23 +273,26 @@ mod class {
 	use std::ops::{Deref, DerefMut};
 	use std::str::FromStr;
 
-	use http::header::{Entry, HeaderMap, HeaderName, HeaderValue};
+	use http::header::{Entry, HeaderMap, HeaderName, HeaderValue, SET_COOKIE};
 
-	use ion::{Error, Result};
+	use ion::{ClassDefinition, Context, Error, ErrorKind, Exception, Function, Object, Result, ResultExc};
+	use ion::clone::{StructuredClone, StructuredCloneReader, StructuredCloneWriter};
+	use ion::conversions::ToValue;
+	use ion::symbol::WellKnownSymbolCode;
 
-	use crate::globals::fetch::header::{Header, HeaderEntry, HeadersInit};
+	use crate::globals::fetch::header::{Header, HeaderEntry, HeadersGuard, HeadersInit, HeadersIterator, IteratorKind};
 
 	#[derive(Clone, Default)]
 	#[ion(from_value, to_value)]
 	pub struct Headers {
 		pub(crate) headers: HeaderMap,
-		pub(crate) readonly: bool,
+		pub(crate) guard: HeadersGuard,
 	}
 
 	impl Headers {
 		#[ion(skip)]
-		pub fn new(headers: HeaderMap, readonly: bool) -> Headers {
-			Headers { headers, readonly }
+		pub fn new(headers: HeaderMap, guard: HeadersGuard) -> Headers {
+			Headers { headers, guard }
 		}
 
 		#[ion(skip)]
@@ -141,7 +301,7 @@ mod class {
 		}
 
 		#[ion(skip)]
-		pub fn from_array(vec: Vec<HeaderEntry>, readonly: bool) -> Result<Headers> {
+		pub fn from_array(vec: Vec<HeaderEntry>, guard: HeadersGuard) -> Result<Headers> {
 			let mut headers = HeaderMap::new();
 			for entry in vec {
 				let mut name = entry.name;
@@ -152,7 +312,7 @@ mod class {
 				let value = HeaderValue::try_from(&value)?;
 				headers.append(name, value);
 			}
-			Ok(Headers { headers, readonly })
+			Ok(Headers { headers, guard })
 		}
 
 		#[ion(skip)]
@@ -168,6 +328,49 @@ mod class {
 			}
 		}
 
+		/// Returns the headers sorted by name, with the values of each header combined, except for `Set-Cookie`.
+		#[ion(skip)]
+		pub fn sorted_entries(&self) -> Vec<(String, String)> {
+			let mut names: Vec<_> = self.headers.keys().collect();
+			names.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+
+			let mut entries = Vec::with_capacity(names.len());
+			for name in names {
+				let values = self.headers.get_all(name).iter().map(|value| String::from_utf8_lossy(value.as_bytes()));
+				if name == SET_COOKIE {
+					entries.extend(values.map(|value| (String::from(name.as_str()), value.into_owned())));
+				} else {
+					entries.push((String::from(name.as_str()), values.collect::<Vec<_>>().join(", ")));
+				}
+			}
+			entries
+		}
+
+		/// Appends a header, unless the guard forbids it.
+		#[ion(skip)]
+		pub fn append_internal(&mut self, name: HeaderName, value: HeaderValue) -> Result<()> {
+			self.check_mutable()?;
+			if self.guard == HeadersGuard::RequestNoCors {
+				let mut combined: Vec<_> = self.headers.get_all(&name).iter().map(HeaderValue::as_bytes).collect();
+				combined.push(value.as_bytes());
+				if !self.guard.allows(&name, Some(&combined.join(&b", "[..])[..])) {
+					return Ok(());
+				}
+			} else if !self.guard.allows(&name, Some(value.as_bytes())) {
+				return Ok(());
+			}
+			self.headers.append(name, value);
+			Ok(())
+		}
+
+		fn check_mutable(&self) -> Result<()> {
+			if self.guard == HeadersGuard::Immutable {
+				Err(Error::new("Cannot Modify Readonly Headers", ErrorKind::Type))
+			} else {
+				Ok(())
+			}
+		}
+
 		#[ion(constructor)]
 		pub fn constructor(init: Option<HeadersInit>) -> Result<Headers> {
 			match init {
@@ -177,28 +380,23 @@ mod class {
 		}
 
 		pub fn append(&mut self, name: String, value: String) -> Result<()> {
-			if !self.readonly {
-				let name = HeaderName::from_str(&name.to_lowercase())?;
-				let value = HeaderValue::from_str(&value)?;
-				self.headers.append(name, value);
-				Ok(())
-			} else {
-				Err(Error::new("Cannot Modify Readonly Headers", None))
-			}
+			let name = HeaderName::from_str(&name.to_lowercase())?;
+			let value = HeaderValue::from_str(&value)?;
+			self.append_internal(name, value)
 		}
 
 		pub fn delete(&mut self, name: String) -> Result<bool> {
-			if !self.readonly {
-				let name = HeaderName::from_str(&name.to_lowercase())?;
-				match self.headers.entry(name) {
-					Entry::Occupied(o) => {
-						o.remove_entry_mult();
-						Ok(true)
-					}
-					Entry::Vacant(_) => Ok(false),
+			self.check_mutable()?;
+			let name = HeaderName::from_str(&name.to_lowercase())?;
+			if !self.guard.allows(&name, None) {
+				return Ok(false);
+			}
+			match self.headers.entry(name) {
+				Entry::Occupied(o) => {
+					o.remove_entry_mult();
+					Ok(true)
 				}
-			} else {
-				Err(Error::new("Cannot Modify Readonly Headers", None))
+				Entry::Vacant(_) => Ok(false),
 			}
 		}
 
@@ -212,15 +410,58 @@ mod class {
 			Ok(self.headers.contains_key(name))
 		}
 
+		/// Returns the values of the `Set-Cookie` headers, which cannot be combined.
+		pub fn getSetCookie(&self) -> Vec<String> {
+			self.headers
+				.get_all(SET_COOKIE)
+				.iter()
+				.map(|value| String::from_utf8_lossy(value.as_bytes()).into_owned())
+				.collect()
+		}
+
 		pub fn set(&mut self, name: String, value: String) -> Result<()> {
-			if !self.readonly {
-				let name = HeaderName::from_str(&name.to_lowercase())?;
-				let value = HeaderValue::from_str(&value)?;
+			self.check_mutable()?;
+			let name = HeaderName::from_str(&name.to_lowercase())?;
+			let value = HeaderValue::from_str(&value)?;
+			if self.guard.allows(&name, Some(value.as_bytes())) {
 				self.headers.insert(name, value);
-				Ok(())
-			} else {
-				Err(Error::new("Cannot Modify Readonly Headers", None))
 			}
+			Ok(())
+		}
+
+		pub fn entries<'cx: 'o, 'o>(cx: &'cx Context, #[ion(this)] this: &Object<'o>) -> ion::Iterator {
+			let iterator = HeadersIterator::new(Headers::get_private(this), IteratorKind::Entries);
+			ion::Iterator::new(iterator, &this.as_value(cx))
+		}
+
+		pub fn keys<'cx: 'o, 'o>(cx: &'cx Context, #[ion(this)] this: &Object<'o>) -> ion::Iterator {
+			let iterator = HeadersIterator::new(Headers::get_private(this), IteratorKind::Keys);
+			ion::Iterator::new(iterator, &this.as_value(cx))
+		}
+
+		pub fn values<'cx: 'o, 'o>(cx: &'cx Context, #[ion(this)] this: &Object<'o>) -> ion::Iterator {
+			let iterator = HeadersIterator::new(Headers::get_private(this), IteratorKind::Values);
+			ion::Iterator::new(iterator, &this.as_value(cx))
+		}
+
+		#[ion(name = WellKnownSymbolCode::Iterator)]
+		pub fn iterator<'cx: 'o, 'o>(cx: &'cx Context, #[ion(this)] this: &Object<'o>) -> ion::Iterator {
+			let iterator = HeadersIterator::new(Headers::get_private(this), IteratorKind::Entries);
+			ion::Iterator::new(iterator, &this.as_value(cx))
+		}
+
+		/// Calls the callback with the value and name of each header, and the headers, in the order they are iterated.
+		pub fn forEach<'cx: 'o, 'o>(
+			cx: &'cx Context, #[ion(this)] this: &Object<'o>, callback: Function<'cx>, this_arg: Option<Object<'cx>>,
+		) -> ResultExc<()> {
+			let this_arg = this_arg.unwrap_or_else(|| Object::null(cx));
+			for (name, value) in Headers::get_private(this).sorted_entries() {
+				let args = [value.as_value(cx), name.as_value(cx), this.as_value(cx)];
+				if let Err(report) = callback.call(cx, &this_arg, &args) {
+					return Err(report.map_or_else(|| Exception::Error(Error::none()), |report| report.exception));
+				}
+			}
+			Ok(())
 		}
 	}
 
@@ -237,6 +478,29 @@ mod class {
 			&mut self.headers
 		}
 	}
+
+	impl StructuredClone for Headers {
+		fn write(&self, _: &Context, writer: &mut StructuredCloneWriter) -> Result<()> {
+			writer.write_pair(self.headers.len() as u32, self.guard as u32)?;
+			for (name, value) in &self.headers {
+				writer.write_string(name.as_str())?;
+				writer.write_bytes(value.as_bytes())?;
+			}
+			Ok(())
+		}
+
+		fn read(_: &Context, reader: &mut StructuredCloneReader) -> Result<Headers> {
+			let (length, guard) = reader.read_pair()?;
+			let guard = HeadersGuard::from_u32(guard)?;
+			let mut headers = HeaderMap::with_capacity(length as usize);
+			for _ in 0..length {
+				let name = HeaderName::from_str(&reader.read_string()?)?;
+				let value = HeaderValue::from_bytes(&reader.read_bytes()?)?;
+				headers.append(name, value);
+			}
+			Ok(Headers { headers, guard })
+		}
+	}
 }
 
 fn append_to_headers<'cx: 'o, 'o>(cx: &'cx Context, headers: &mut HeaderMap, obj: Object<'o>, unique: bool) -> Result<()> {
@@ -0,0 +1,62 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::mem::take;
+
+use hyper::Body;
+use ring::digest::{Algorithm, digest, SHA256, SHA384, SHA512};
+
+use ion::{Error, ErrorKind, Result};
+
+use crate::globals::fetch::Response;
+
+/// Hash algorithms supported in integrity metadata, in order of increasing strength.
+static ALGORITHMS: [(&str, &Algorithm); 3] = [("sha256", &SHA256), ("sha384", &SHA384), ("sha512", &SHA512)];
+
+/// Normalises a base64 digest to the URL-safe alphabet without padding, so both alphabets can be compared.
+fn normalise_digest(digest: &str) -> String {
+	digest.trim_end_matches('=').replace('+', "-").replace('/', "_")
+}
+
+/// Parses integrity metadata, as specified by [Subresource Integrity](https://w3c.github.io/webappsec-subresource-integrity/#parse-metadata).
+/// Returns the index of the algorithm and the normalised digest of each valid item. Unknown algorithms are ignored.
+fn parse_metadata(metadata: &str) -> Vec<(usize, String)> {
+	metadata
+		.split_ascii_whitespace()
+		.filter_map(|item| {
+			let (expression, _) = item.split_once('?').unwrap_or((item, ""));
+			let (algorithm, digest) = expression.split_once('-')?;
+			let index = ALGORITHMS.iter().position(|(name, _)| name.eq_ignore_ascii_case(algorithm))?;
+			Some((index, normalise_digest(digest)))
+		})
+		.collect()
+}
+
+/// Checks if bytes match integrity metadata, as specified by [Subresource Integrity](https://w3c.github.io/webappsec-subresource-integrity/#does-response-match-metadatalist).
+/// Only items with the strongest algorithm are considered, and metadata without any valid items always matches.
+pub(crate) fn matches_metadata(metadata: &str, bytes: &[u8]) -> bool {
+	let metadata = parse_metadata(metadata);
+	let strongest = match metadata.iter().map(|(index, _)| *index).max() {
+		Some(strongest) => strongest,
+		None => return true,
+	};
+
+	let actual = base64_url::encode(digest(ALGORITHMS[strongest].1, bytes).as_ref());
+	metadata.iter().any(|(index, expected)| *index == strongest && *expected == actual)
+}
+
+/// Reads the body of a response and verifies it against integrity metadata.
+/// The body is replaced with the bytes which were read, so it can still be consumed.
+pub(crate) async fn verify_response(mut response: Response, metadata: &str) -> Result<Response> {
+	let body = take(response.response.body_mut());
+	let bytes = hyper::body::to_bytes(body).await?;
+
+	if !matches_metadata(metadata, &bytes) {
+		return Err(Error::new("Response body does not match integrity metadata", ErrorKind::Type));
+	}
+	*response.response.body_mut() = Body::from(bytes);
+	Ok(response)
+}
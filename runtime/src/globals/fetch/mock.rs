@@ -0,0 +1,212 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::sync::Mutex;
+
+use http::{HeaderMap, Method, StatusCode};
+use hyper::Body;
+use url::Url;
+
+pub use class::*;
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result, Value};
+use ion::conversions::ToValue;
+
+use crate::globals::fetch::{FetchBody, Headers, HeadersGuard, Transport, TransportFuture};
+use crate::globals::fetch::header::HeadersInit;
+use crate::globals::fetch::request::{parse_body, RequestCache};
+
+#[derive(FromValue)]
+pub struct MockMatcher {
+	method: Option<String>,
+	url: String,
+	#[ion(default)]
+	headers: HeadersInit,
+}
+
+#[derive(Default, FromValue)]
+pub struct MockResponseInit {
+	status: Option<u16>,
+	#[ion(default)]
+	headers: HeadersInit,
+	#[ion(default, parser = |b| parse_body(cx, b))]
+	body: Option<FetchBody>,
+}
+
+struct MockRoute {
+	method: Option<Method>,
+	pattern: String,
+	headers: HeaderMap,
+	status: StatusCode,
+	response_headers: HeaderMap,
+	body: FetchBody,
+}
+
+impl MockRoute {
+	fn matches(&self, method: &Method, url: &str, headers: &HeaderMap) -> bool {
+		self.method.as_ref().map_or(true, |expected| expected == method)
+			&& matches_pattern(&self.pattern, url)
+			&& self
+				.headers
+				.iter()
+				.all(|(name, value)| headers.get_all(name).iter().any(|actual| actual == value))
+	}
+}
+
+#[derive(Clone)]
+pub struct RecordedRequest {
+	method: Method,
+	url: Url,
+	headers: HeaderMap,
+}
+
+impl<'cx> ToValue<'cx> for RecordedRequest {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let headers = Headers::new_object(cx, Headers::new(self.headers.clone(), HeadersGuard::Immutable));
+
+		let mut object = Object::new(cx);
+		object.set_as(cx, "method", self.method.as_str());
+		object.set_as(cx, "url", self.url.as_str());
+		object.set_as(cx, "headers", &headers);
+		object.to_value(cx, value);
+	}
+}
+
+/// Checks if a URL matches a pattern, where `*` matches any sequence of characters.
+fn matches_pattern(pattern: &str, url: &str) -> bool {
+	let (pattern, url) = (pattern.as_bytes(), url.as_bytes());
+	let (mut p, mut u) = (0, 0);
+	let mut backtrack = None;
+
+	while u < url.len() {
+		if p < pattern.len() && pattern[p] == b'*' {
+			backtrack = Some((p, u));
+			p += 1;
+		} else if p < pattern.len() && pattern[p] == url[u] {
+			p += 1;
+			u += 1;
+		} else if let Some((star, matched)) = backtrack {
+			p = star + 1;
+			u = matched + 1;
+			backtrack = Some((star, matched + 1));
+		} else {
+			return false;
+		}
+	}
+	pattern[p..].iter().all(|&byte| byte == b'*')
+}
+
+/// Routes which requests are matched against, and the requests which did not match any route.
+#[derive(Default)]
+struct MockRegistry {
+	routes: Mutex<Vec<MockRoute>>,
+	unmatched: Mutex<Vec<RecordedRequest>>,
+}
+
+impl MockRegistry {
+	fn respond(&self, request: hyper::Request<Body>, url: &Url) -> Result<hyper::Response<Body>> {
+		let mut target = url.clone();
+		target.set_fragment(None);
+
+		let routes = self.routes.lock().unwrap();
+		let route = routes
+			.iter()
+			.find(|route| route.matches(request.method(), target.as_str(), request.headers()));
+
+		match route {
+			Some(route) => {
+				let mut response = hyper::Response::new(route.body.clone().into_http_body());
+				*response.status_mut() = route.status;
+				*response.headers_mut() = route.response_headers.clone();
+				route.body.add_headers(response.headers_mut())?;
+				Ok(response)
+			}
+			None => {
+				let error = format!("No mock matches {} {}", request.method(), target);
+				self.unmatched.lock().unwrap().push(RecordedRequest {
+					method: request.method().clone(),
+					url: target,
+					headers: request.headers().clone(),
+				});
+				Err(Error::new(&error, ErrorKind::Type))
+			}
+		}
+	}
+}
+
+impl Transport for MockRegistry {
+	fn send<'t>(&'t self, request: hyper::Request<Body>, url: &'t Url, _: RequestCache) -> TransportFuture<'t> {
+		Box::pin(async move { self.respond(request, url) })
+	}
+}
+
+#[js_class]
+#[ion(runtime = crate)]
+pub mod class {
+	use std::str::FromStr;
+	use std::sync::Arc;
+
+	use http::{Method, StatusCode};
+
+	use ion::Result;
+
+	use crate::globals::fetch::Transport;
+	use crate::globals::fetch::mock::{MockMatcher, MockRegistry, MockResponseInit, MockRoute, RecordedRequest};
+
+	/// Transport which responds to requests with canned responses, for testing code which uses `fetch` without a network.
+	/// Requests are matched against routes in the order they were registered.
+	#[derive(Clone)]
+	#[ion(from_value, into_value)]
+	pub struct MockTransport {
+		registry: Arc<MockRegistry>,
+	}
+
+	impl MockTransport {
+		#[ion(constructor)]
+		pub fn constructor() -> MockTransport {
+			MockTransport { registry: Arc::default() }
+		}
+
+		#[ion(skip)]
+		pub fn transport(&self) -> Arc<dyn Transport> {
+			Arc::clone(&self.registry) as Arc<dyn Transport>
+		}
+
+		/// Registers a route, which responds to requests matching the method, URL pattern and headers of the matcher.
+		pub fn on(&self, matcher: MockMatcher, response: Option<MockResponseInit>) -> Result<()> {
+			let response = response.unwrap_or_default();
+			let method = match matcher.method {
+				Some(mut method) => {
+					method.make_ascii_uppercase();
+					Some(Method::from_str(&method)?)
+				}
+				None => None,
+			};
+
+			let route = MockRoute {
+				method,
+				pattern: matcher.url,
+				headers: matcher.headers.into_headers()?.inner(),
+				status: StatusCode::from_u16(response.status.unwrap_or(200))?,
+				response_headers: response.headers.into_headers()?.inner(),
+				body: response.body.unwrap_or_default(),
+			};
+			self.registry.routes.lock().unwrap().push(route);
+			Ok(())
+		}
+
+		/// Requests which did not match any route, in the order they were sent.
+		#[ion(get)]
+		pub fn get_unmatched(&self) -> Vec<RecordedRequest> {
+			self.registry.unmatched.lock().unwrap().clone()
+		}
+
+		/// Removes all routes and recorded requests.
+		pub fn clear(&self) {
+			self.registry.routes.lock().unwrap().clear();
+			self.registry.unmatched.lock().unwrap().clear();
+		}
+	}
+}
@@ -4,19 +4,46 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-pub use client::{default_client, GLOBAL_CLIENT};
-pub use header::Headers;
+pub use body::FetchBody;
+pub use client::{connector, Connector, default_client, FetchClient, GLOBAL_CLIENT};
+pub use cookies::CookieJar;
+pub use event_source::EventSource;
+pub use header::{Headers, HeadersGuard};
 use ion::{ClassDefinition, Context, Object, ResultExc};
+use ion::clone::register_structured_clone;
 use ion::flags::PropertyFlags;
-pub use network::request_internal;
+pub use mock::MockTransport;
+pub use network::{request_attempt, request_internal, Transport, TransportFuture};
+pub use proxy::{Proxy, ProxyConfig, ProxyConnector};
+pub use redirect::{DEFAULT_MAX_REDIRECTS, RedirectAction, RedirectHook};
 pub use request::{Request, RequestBuilderInit, RequestInit, Resource};
+pub use resolver::{ResolveFuture, Resolver};
 pub use response::Response;
+pub use stats::{ClientStats, ResponseTiming, TimedResolver, TrackedConnector, TrackedStream};
+pub use timeout::Timeouts;
+pub use tls::{default_client_config, TlsOptions};
+pub use upload::{StreamBody, UploadProgress};
 
+mod body;
+mod cache;
 mod client;
+mod compression;
+mod cookies;
+mod event_source;
 mod header;
+mod integrity;
+mod mock;
 mod network;
+mod proxy;
+mod redirect;
 mod request;
+mod resolver;
 mod response;
+mod scheme;
+mod stats;
+mod timeout;
+mod tls;
+mod upload;
 
 // TODO: Specification-Compliant Fetch Implementation
 #[js_fn]
@@ -28,6 +55,7 @@ async fn fetch(resource: Resource, init: Option<RequestBuilderInit>) -> ResultEx
 
 pub fn define(cx: &Context, global: &mut Object) -> bool {
 	let _ = GLOBAL_CLIENT.set(default_client());
+	register_structured_clone::<Headers>();
 	global.define_method(cx, "fetch", fetch, 1, PropertyFlags::CONSTANT_ENUMERATED);
-	Headers::init_class(cx, global).0 && Request::init_class(cx, global).0 && Response::init_class(cx, global).0
+	Headers::init_class(cx, global).0 && Request::init_class(cx, global).0 && Response::init_class(cx, global).0 && event_source::define(cx, global)
 }
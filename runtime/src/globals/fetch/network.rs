@@ -4,46 +4,160 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::cell::Cell;
+use std::future::Future;
+use std::mem::take;
+use std::pin::Pin;
 use std::str::FromStr;
 
-use bytes::Bytes;
 use futures::future::{Either, select};
-use http::{Method, StatusCode, Uri};
-use http::header::{CONTENT_ENCODING, CONTENT_LANGUAGE, CONTENT_LOCATION, CONTENT_TYPE, HOST, LOCATION};
-use hyper::{Body, Client};
-use hyper::client::HttpConnector;
-use hyper_rustls::HttpsConnector;
-use url::Url;
-
-use ion::{Error, Exception, ResultExc};
+use http::{HeaderMap, Method, StatusCode, Uri};
+use http::header::{AUTHORIZATION, CONTENT_ENCODING, CONTENT_LANGUAGE, CONTENT_LOCATION, CONTENT_TYPE, COOKIE, HOST, LOCATION, PROXY_AUTHORIZATION};
+use hyper::Body;
+use tokio::time::{Instant, timeout_at};
+use url::{Origin, Url};
+
+use ion::{Error, ErrorKind, Exception, Result, ResultExc};
+
+use crate::globals::fetch::{Connector, CookieJar, FetchClient, RedirectAction, Request, Response};
+use crate::globals::fetch::body::FetchBody;
+use crate::globals::fetch::cache::cached_request;
+use crate::globals::fetch::compression::{add_accept_encoding_header, decode_response};
+use crate::globals::fetch::integrity::verify_response;
+use crate::globals::fetch::redirect::redirect_response;
+use crate::globals::fetch::resolver::RESOLVER;
+use crate::globals::fetch::request::{add_host_header, clone_request, RequestCache, RequestCredentials, RequestRedirect};
+use crate::globals::fetch::scheme::{is_local_scheme, scheme_fetch};
+use crate::globals::fetch::stats::send_tracked;
+use crate::globals::fetch::timeout::{CONNECT_TIMEOUT, set_transient_failure, timeout_body, TimeoutError, TRANSIENT_FAILURE};
+
+/// Future which resolves to the response to a request sent by a [Transport].
+pub type TransportFuture<'t> = Pin<Box<dyn Future<Output = Result<hyper::Response<Body>>> + 't>>;
+
+/// Sends the individual requests of a fetch, including each redirect.
+/// Clients send requests over the network by default, but can be given a transport which intercepts them instead.
+pub trait Transport: Send + Sync {
+	fn send<'t>(&'t self, request: hyper::Request<Body>, url: &'t Url, cache: RequestCache) -> TransportFuture<'t>;
+}
 
-use crate::globals::fetch::{Request, Response};
-use crate::globals::fetch::request::{add_host_header, clone_request, RequestRedirect};
+impl Transport for hyper::Client<Connector> {
+	fn send<'t>(&'t self, request: hyper::Request<Body>, url: &'t Url, cache: RequestCache) -> TransportFuture<'t> {
+		Box::pin(cached_request(self, request, url, cache))
+	}
+}
 
-pub async fn request_internal(request: Request, client: Client<HttpsConnector<HttpConnector>>) -> ResultExc<Response> {
+pub async fn request_internal(request: Request, client: FetchClient) -> ResultExc<Response> {
 	let signal = request.signal.poll();
-	let send = Box::pin(send_requests(request, client));
+	let timeouts = request.timeouts.or(client.timeouts);
+	let deadline = timeouts.total.map(|total| (Instant::now() + total, total));
+
+	let send = async move {
+		let integrity = request.integrity.clone();
+		let decompress = request.decompress;
+		let resolver = client.resolver();
+		let send = RESOLVER.scope(resolver, send_requests(request, client));
+		let mut response = CONNECT_TIMEOUT.scope(timeouts.connect, send).await?;
+
+		let body = take(response.response.body_mut());
+		*response.response.body_mut() = timeout_body(body, timeouts.read, deadline);
+		if decompress {
+			decode_response(&mut response);
+		}
+		match integrity {
+			Some(integrity) if !integrity.is_empty() => Ok(verify_response(response, &integrity).await?),
+			_ => Ok(response),
+		}
+	};
+	let send = Box::pin(async move {
+		match deadline {
+			Some((deadline, total)) => timeout_at(deadline, send).await.unwrap_or_else(|_| {
+				set_transient_failure(true);
+				Err(Error::from(TimeoutError::Total(total)).into())
+			}),
+			None => send.await,
+		}
+	});
 	match select(send, signal).await {
 		Either::Left((response, _)) => response,
 		Either::Right((exception, _)) => Err(Exception::Other(exception)),
 	}
 }
 
-pub(crate) async fn send_requests(mut req: Request, client: Client<HttpsConnector<HttpConnector>>) -> ResultExc<Response> {
+/// Sends a request like [request_internal], and also returns whether it failed with a transient error,
+/// such as a connection failure, reset or timeout, which may not occur if the request is sent again.
+pub async fn request_attempt(request: Request, client: FetchClient) -> (ResultExc<Response>, bool) {
+	let attempt = async {
+		let result = request_internal(request, client).await;
+		let transient = result.is_err() && TRANSIENT_FAILURE.with(Cell::get);
+		(result, transient)
+	};
+	TRANSIENT_FAILURE.scope(Cell::new(false), attempt).await
+}
+
+/// Returns the cookie jar of the client if credentials are included in a request to the URL.
+/// Credentials are only included with `same-origin` while the redirect chain remains on the origin of the initial URL.
+fn cookie_jar<'c>(client: &'c FetchClient, credentials: RequestCredentials, origin: &Origin, url: &Url) -> Option<&'c CookieJar> {
+	match credentials {
+		RequestCredentials::Omit => None,
+		RequestCredentials::SameOrigin if url.origin() != *origin => None,
+		_ => client.cookies.as_deref(),
+	}
+}
+
+/// Sets the headers returned by a redirect hook on the next request, replacing existing values of the same headers.
+fn merge_headers(headers: &mut HeaderMap, overrides: HeaderMap) {
+	let mut name = None;
+	for (next, value) in overrides {
+		if let Some(next) = next {
+			headers.remove(&next);
+			name = Some(next);
+		}
+		if let Some(name) = &name {
+			headers.append(name, value);
+		}
+	}
+}
+
+pub(crate) async fn send_requests(mut req: Request, client: FetchClient) -> ResultExc<Response> {
+	if is_local_scheme(req.url.scheme()) {
+		return Ok(scheme_fetch(&req).await?);
+	}
+
 	let mut redirections = 0;
+	let max_redirects = req.max_redirects.unwrap_or(client.max_redirects);
+	if req.decompress {
+		add_accept_encoding_header(req.request.headers_mut());
+	}
 
 	let mut request = req.clone()?;
-	*request.request.body_mut() = Body::from(request.body.clone());
 
-	*req.request.body_mut() = Body::from(req.body);
-	let mut response = client.request(req.request).await?;
+	let origin = request.url.origin();
+	let jar = cookie_jar(&client, req.credentials, &origin, &request.url);
+	if let Some(jar) = jar {
+		jar.add_header(req.request.headers_mut(), &request.url)?;
+	}
+	client.proxy.add_authorisation_header(req.request.headers_mut(), &request.url);
+
+	let transport = client.transport();
+	let total = req.body.len();
+	*req.request.body_mut() = match &req.upload_progress {
+		Some(progress) => progress.wrap_body(req.body.into_http_body(), total),
+		None => req.body.into_http_body(),
+	};
+	let mut response = send_tracked(&*transport, &client.stats, req.request, &request.url, req.cache).await?;
+	if let Some(jar) = jar {
+		jar.set_from_headers(&request.url, response.headers());
+	}
 	let mut locations = vec![request.url.clone()];
 
 	while response.status().is_redirection() {
-		if redirections >= 20 {
+		if redirections >= max_redirects {
 			return Err(Error::new("Too Many Redirects", None).into());
 		}
 		let status = response.status();
+		if status != StatusCode::SEE_OTHER && request.body.is_stream() {
+			return Err(Error::new("Redirected with a Streamed Body", ErrorKind::Type).into());
+		}
 		if status != StatusCode::SEE_OTHER && !request.body.is_empty() {
 			return Err(Error::new("Redirected with a Body", None).into());
 		}
@@ -60,6 +174,24 @@ pub(crate) async fn send_requests(mut req: Request, client: Client<HttpsConnecto
 						options.parse(location)
 					}?;
 
+					if let Some(hook) = &req.redirect_hook {
+						let redirect = redirect_response(&response, redirections, locations.clone());
+						match hook.call(redirect, &url).await? {
+							RedirectAction::Follow(Some(headers)) => merge_headers(request.request.headers_mut(), headers),
+							RedirectAction::Follow(None) => {}
+							RedirectAction::Stop => return Ok(Response::new(response, redirections, locations)),
+						}
+					}
+
+					// Credentials are removed after the hook, so that it cannot send them to another origin.
+					let previous = locations.last().unwrap_or(&request.url);
+					if url.origin() != previous.origin() {
+						let headers = request.request.headers_mut();
+						headers.remove(AUTHORIZATION);
+						headers.remove(COOKIE);
+						headers.remove(PROXY_AUTHORIZATION);
+					}
+
 					redirections += 1;
 
 					if ((status == StatusCode::MOVED_PERMANENTLY || status == StatusCode::FOUND) && method == Method::POST)
@@ -67,7 +199,7 @@ pub(crate) async fn send_requests(mut req: Request, client: Client<HttpsConnecto
 					{
 						*request.request.method_mut() = Method::GET;
 
-						request.body = Bytes::new();
+						request.body = FetchBody::default();
 						*request.request.body_mut() = Body::empty();
 
 						let headers = request.request.headers_mut();
@@ -83,8 +215,17 @@ pub(crate) async fn send_requests(mut req: Request, client: Client<HttpsConnecto
 					locations.push(url.clone());
 					*request.request.uri_mut() = Uri::from_str(url.as_str())?;
 
-					let request = { clone_request(&request.request) }?;
-					response = client.request(request).await?;
+					let mut request = { clone_request(&request.request) }?;
+					let jar = cookie_jar(&client, req.credentials, &origin, &url);
+					if let Some(jar) = jar {
+						jar.add_header(request.headers_mut(), &url)?;
+					}
+					client.proxy.add_authorisation_header(request.headers_mut(), &url);
+
+					response = send_tracked(&*transport, &client.stats, request, &url, req.cache).await?;
+					if let Some(jar) = jar {
+						jar.set_from_headers(&url, response.headers());
+					}
 				} else {
 					return Ok(Response::new(response, redirections, locations));
 				}
@@ -0,0 +1,141 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::rc::Rc;
+
+use http::HeaderMap;
+use hyper::Body;
+use mozjs::jsapi::{JSContext, JSObject};
+use mozjs::jsval::JSVal;
+use url::Url;
+
+use ion::{ClassDefinition, Context, Error, ErrorReport, Exception, Function, Object, Promise, PromiseFuture, Result, ResultExc, Value};
+use ion::conversions::{FromValue, ToValue};
+
+use crate::globals::fetch::header::HeadersInit;
+use crate::globals::fetch::Response;
+
+/// Maximum number of redirects which are followed, unless the client or request specifies otherwise.
+pub const DEFAULT_MAX_REDIRECTS: u8 = 20;
+
+/// Callback which remains rooted until all clones of the hook are dropped.
+struct RootedCallback {
+	cx: *mut JSContext,
+	callback: *mut JSObject,
+}
+
+impl Drop for RootedCallback {
+	fn drop(&mut self) {
+		let cx = unsafe { Context::new_unchecked(self.cx) };
+		cx.unroot_persistent_object(self.callback);
+	}
+}
+
+/// Decision of a [RedirectHook] about a redirect.
+pub enum RedirectAction {
+	/// Follows the redirect, setting the given headers on the next request.
+	Follow(Option<HeaderMap>),
+	/// Stops following redirects, and uses the redirect as the response.
+	Stop,
+}
+
+/// Hook called with the redirect response and the next URL before a redirect is followed.
+/// Returning `false` stops the redirect, and returning an object with `headers` sets those headers on the next request.
+/// Other headers of the next request are kept, and credentials are still removed when redirected to another origin.
+/// The hook may return a promise for either.
+#[derive(Clone)]
+pub struct RedirectHook {
+	callback: Rc<RootedCallback>,
+}
+
+impl RedirectHook {
+	pub fn new(cx: &Context, callback: &Function) -> RedirectHook {
+		let callback = cx.root_persistent_object(callback.to_object(cx).handle().get()).get();
+		RedirectHook {
+			callback: Rc::new(RootedCallback { cx: cx.as_ptr(), callback }),
+		}
+	}
+
+	pub(crate) async fn call(&self, response: Response, url: &Url) -> ResultExc<RedirectAction> {
+		let promise = {
+			let cx = unsafe { Context::new_unchecked(self.callback.cx) };
+			let callback = cx.root_object(self.callback.callback);
+			let callback = Function::from_object(&cx, &callback).ok_or_else(|| Error::new("Redirect hook is not callable", None))?;
+
+			let response = Object::from(cx.root_object(Response::new_object(&cx, response)));
+			let args = [Value::object(&cx, &response), url.as_str().as_value(&cx)];
+			let result = callback.call(&cx, &Object::null(&cx), &args).map_err(report_exception)?;
+
+			if result.handle().is_object() {
+				match Promise::from(result.to_object(&cx).into_local()) {
+					Some(promise) => PromiseFuture::new(&cx, &promise),
+					None => return Ok(redirect_action(&cx, &result)?),
+				}
+			} else {
+				return Ok(redirect_action(&cx, &result)?);
+			}
+		};
+
+		let result = promise.await;
+		let cx = unsafe { Context::new_unchecked(self.callback.cx) };
+		match result {
+			Ok(result) => Ok(redirect_action(&cx, &Value::from(cx.root_value(result)))?),
+			Err(reason) => Err(rejection(&cx, reason)),
+		}
+	}
+}
+
+impl Debug for RedirectHook {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		f.debug_struct("RedirectHook").finish_non_exhaustive()
+	}
+}
+
+impl<'cx> FromValue<'cx> for RedirectHook {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, strict: bool, _: ()) -> Result<RedirectHook>
+	where
+		'cx: 'v,
+	{
+		let callback = Function::from_value(cx, value, strict, ())?;
+		Ok(RedirectHook::new(cx, &callback))
+	}
+}
+
+fn report_exception(report: Option<ErrorReport>) -> Exception {
+	report.map_or_else(|| Exception::Error(Error::none()), |report| report.exception)
+}
+
+fn rejection(cx: &Context, reason: JSVal) -> Exception {
+	Exception::from_value(cx, &Value::from(cx.root_value(reason)))
+}
+
+fn redirect_action(cx: &Context, result: &Value) -> Result<RedirectAction> {
+	let handle = result.handle();
+	if handle.is_boolean() && !handle.to_boolean() {
+		Ok(RedirectAction::Stop)
+	} else if handle.is_object() {
+		let headers: Option<HeadersInit> = result.to_object(cx).get_as(cx, "headers", true, ());
+		match headers {
+			Some(headers) => Ok(RedirectAction::Follow(Some(headers.into_headers()?.inner()))),
+			None => Ok(RedirectAction::Follow(None)),
+		}
+	} else {
+		Ok(RedirectAction::Follow(None))
+	}
+}
+
+/// Creates the response passed to a redirect hook, which has the status and headers of the redirect but no body.
+pub(crate) fn redirect_response(response: &hyper::Response<Body>, redirections: u8, locations: Vec<Url>) -> Response {
+	let mut head = hyper::Response::new(Body::empty());
+	*head.status_mut() = response.status();
+	*head.version_mut() = response.version();
+	*head.headers_mut() = response.headers().clone();
+	Response::new(head, redirections, locations)
+}
@@ -4,21 +4,23 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
-use bytes::Bytes;
 use http::{HeaderMap, HeaderValue, Method};
 use http::header::HeaderName;
 use hyper::Body;
-use mozjs::jsapi::ESClass;
 use url::Url;
 
 pub use class::*;
-use ion::{Context, Error, ErrorKind, Result, Value};
-use ion::conversions::FromValue;
-use options::{Referrer, ReferrerPolicy, RequestCache, RequestCredentials, RequestMode};
-pub use options::RequestRedirect;
+use ion::{Context, Error, Result, Value};
+use ion::conversions::{ConversionBehavior, FromValue};
+use options::{Referrer, ReferrerPolicy, RequestMode};
+pub use options::{RequestCache, RequestCredentials, RequestRedirect};
 
-use crate::globals::abort::AbortSignal;
+use crate::globals::abort::SignalObject;
+use crate::globals::fetch::body::FetchBody;
 use crate::globals::fetch::header::HeadersInit;
+use crate::globals::fetch::RedirectHook;
+use crate::globals::fetch::scheme::is_local_scheme;
+use crate::globals::fetch::upload::{parse_upload_progress, UploadProgress};
 
 mod options;
 
@@ -37,7 +39,7 @@ pub struct RequestInit {
 	#[ion(default)]
 	pub(crate) headers: HeadersInit,
 	#[ion(default, parser = |b| parse_body(cx, b))]
-	pub(crate) body: Option<Bytes>,
+	pub(crate) body: Option<FetchBody>,
 
 	#[allow(dead_code)]
 	#[ion(default)]
@@ -46,18 +48,14 @@ pub struct RequestInit {
 	#[ion(default)]
 	pub(crate) referrer_policy: ReferrerPolicy,
 
-	#[allow(dead_code)]
 	pub(crate) mode: Option<RequestMode>,
-	#[allow(dead_code)]
 	#[ion(default)]
 	pub(crate) credentials: RequestCredentials,
-	#[allow(dead_code)]
 	#[ion(default)]
 	pub(crate) cache: RequestCache,
 	#[ion(default)]
 	pub(crate) redirect: RequestRedirect,
 
-	#[allow(dead_code)]
 	pub(crate) integrity: Option<String>,
 
 	#[allow(dead_code)]
@@ -73,13 +71,30 @@ pub struct RequestInit {
 	#[ion(default = true)]
 	pub(crate) is_history_navigation: bool,
 
-	#[ion(default)]
-	pub(crate) signal: AbortSignal,
+	pub(crate) signal: Option<SignalObject>,
 
 	pub(crate) auth: Option<String>,
 	#[derivative(Default(value = "true"))]
 	#[ion(default = true)]
 	pub(crate) set_host: bool,
+	#[derivative(Default(value = "true"))]
+	#[ion(default = true)]
+	pub(crate) decompress: bool,
+
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub(crate) connect_timeout: Option<u64>,
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub(crate) read_timeout: Option<u64>,
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub(crate) timeout: Option<u64>,
+
+	#[ion(name = "onUploadProgress", parser = |callback| parse_upload_progress(cx, callback))]
+	pub(crate) upload_progress: Option<UploadProgress>,
+
+	#[ion(convert = ConversionBehavior::EnforceRange)]
+	pub(crate) max_redirects: Option<u8>,
+	#[ion(name = "onRedirect")]
+	pub(crate) redirect_hook: Option<RedirectHook>,
 }
 
 #[derive(Default, FromValue)]
@@ -101,27 +116,48 @@ impl RequestBuilderInit {
 pub mod class {
 	use std::str::FromStr;
 
-	use bytes::Bytes;
+	use http::header::CONTENT_TYPE;
 	use hyper::{Body, Method, Uri};
+	use mozjs::gc::Traceable;
+	use mozjs::jsapi::{Heap, JSObject, JSTracer};
 	use url::Url;
 
-	use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result, Value};
+	use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Promise, Result, Value};
 	use ion::conversions::FromValue;
+	use ion::typedarray::ArrayBuffer;
 
-	use crate::globals::abort::AbortSignal;
-	use crate::globals::fetch::{Headers, Resource};
+	use crate::globals::abort::{AbortSignal, Signal};
+	use crate::globals::fetch::{Headers, HeadersGuard, RedirectHook, Resource, Timeouts, UploadProgress};
+	use crate::globals::fetch::body::{FetchBody, parse_json};
 	use crate::globals::fetch::request::{
-		add_authorisation_header, add_host_header, check_method_with_body, check_url_scheme, clone_request, RequestBuilderInit, RequestRedirect,
+		add_authorisation_header, add_host_header, check_method_with_body, check_url_scheme, clone_request, RequestBuilderInit, RequestCache,
+		RequestCredentials, RequestMode, RequestRedirect,
 	};
+	use crate::globals::file::{Blob, BlobPart};
+	use crate::promise::future_to_promise;
 
 	#[ion(into_value)]
 	pub struct Request {
 		pub(crate) request: hyper::Request<Body>,
-		pub(crate) body: Bytes,
+		pub(crate) body: FetchBody,
+		pub(crate) body_used: bool,
 
+		pub(crate) credentials: RequestCredentials,
+		pub(crate) cache: RequestCache,
 		pub(crate) redirect: RequestRedirect,
-		pub(crate) signal: AbortSignal,
+		pub(crate) integrity: Option<String>,
+		pub(crate) signal: Signal,
 		pub(crate) url: Url,
+		pub(crate) decompress: bool,
+		pub(crate) timeouts: Timeouts,
+		pub(crate) upload_progress: Option<UploadProgress>,
+		pub(crate) max_redirects: Option<u8>,
+		pub(crate) redirect_hook: Option<RedirectHook>,
+
+		/// Signal exposed to JavaScript, which is the signal the request was created with, or is created when it is first accessed.
+		pub(crate) signal_object: Option<Box<Heap<*mut JSObject>>>,
+		/// Headers exposed to JavaScript, which are created when they are first accessed.
+		pub(crate) headers_object: Option<Box<Heap<*mut JSObject>>>,
 	}
 
 	impl Request {
@@ -130,30 +166,86 @@ pub mod class {
 		pub fn clone(&self) -> Result<Request> {
 			let request = clone_request(&self.request)?;
 			let body = self.body.clone();
+			let body_used = self.body_used;
 
+			let credentials = self.credentials;
+			let cache = self.cache;
 			let redirect = self.redirect;
+			let integrity = self.integrity.clone();
 			let signal = self.signal.clone();
 			let url = self.url.clone();
-
-			Ok(Request { request, body, redirect, signal, url })
+			let decompress = self.decompress;
+			let timeouts = self.timeouts;
+			let upload_progress = self.upload_progress.clone();
+			let max_redirects = self.max_redirects;
+			let redirect_hook = self.redirect_hook.clone();
+
+			Ok(Request {
+				request,
+				body,
+				body_used,
+				credentials,
+				cache,
+				redirect,
+				integrity,
+				signal,
+				url,
+				decompress,
+				timeouts,
+				upload_progress,
+				max_redirects,
+				redirect_hook,
+				signal_object: self.signal_object.as_ref().map(|signal| Heap::boxed(signal.get())),
+				headers_object: None,
+			})
 		}
 
 		#[ion(constructor)]
 		pub fn constructor(resource: Resource, init: Option<RequestBuilderInit>) -> Result<Request> {
+			Request::create(resource, init, true)
+		}
+
+		/// Creates a request without the header guards of the Fetch Standard, so that headers such as `Cookie` and `Host` can be set.
+		/// Used by the `http` module, which is not restricted like `fetch` in browsers.
+		#[ion(skip)]
+		pub fn new_unguarded(resource: Resource, init: Option<RequestBuilderInit>) -> Result<Request> {
+			Request::create(resource, init, false)
+		}
+
+		fn create(resource: Resource, init: Option<RequestBuilderInit>, guarded: bool) -> Result<Request> {
 			let mut request = match resource {
-				Resource::Request(request) => request.clone()?,
+				Resource::Request(request) => {
+					if request.body_used {
+						return Err(Error::new("Request body has already been used.", ErrorKind::Type));
+					}
+					request.clone()?
+				}
 				Resource::String(url) => {
-					let uri = Uri::from_str(&url)?;
 					let url = Url::from_str(&url)?;
+					let uri = match url.scheme() {
+						"http" | "https" => Uri::from_str(url.as_str())?,
+						_ => Uri::default(),
+					};
 					let request = hyper::Request::builder().uri(uri).body(Body::empty())?;
 
 					Request {
 						request,
-						body: Bytes::new(),
+						body: FetchBody::default(),
+						body_used: false,
 
+						credentials: RequestCredentials::default(),
+						cache: RequestCache::default(),
 						redirect: RequestRedirect::Follow,
-						signal: AbortSignal::default(),
+						integrity: None,
+						signal: Signal::default(),
 						url,
+						decompress: true,
+						timeouts: Timeouts::default(),
+						upload_progress: None,
+						max_redirects: None,
+						redirect_hook: None,
+						signal_object: None,
+						headers_object: None,
 					}
 				}
 			};
@@ -168,24 +260,192 @@ pub mod class {
 				*request.request.method_mut() = method;
 			}
 
-			*request.request.headers_mut() = init.headers.into_headers()?.inner();
+			let guard = match init.mode {
+				_ if !guarded => HeadersGuard::None,
+				Some(RequestMode::NoCors) => {
+					if !matches!(*request.request.method(), Method::GET | Method::HEAD | Method::POST) {
+						return Err(Error::new("Method must be GET, HEAD or POST in no-cors mode", ErrorKind::Type));
+					}
+					HeadersGuard::RequestNoCors
+				}
+				_ => HeadersGuard::Request,
+			};
+			*request.request.headers_mut() = init.headers.into_guarded_headers(guard)?.inner();
 
 			add_authorisation_header(request.request.headers_mut(), &request.url, init.auth)?;
 			add_host_header(request.request.headers_mut(), &request.url, init.set_host)?;
 
 			if let Some(body) = init.body {
+				body.add_headers(request.request.headers_mut())?;
 				request.body = body;
 				*request.request.body_mut() = Body::empty();
 			}
+			request.credentials = init.credentials;
+			request.cache = init.cache;
 			request.redirect = init.redirect;
-			request.signal = init.signal;
+			if init.integrity.is_some() {
+				request.integrity = init.integrity;
+			}
+			if let Some(signal) = init.signal {
+				request.signal = signal.signal;
+				request.signal_object = Some(Heap::boxed(signal.object));
+			}
+			request.decompress = init.decompress;
+			request.timeouts = Timeouts::from_millis(init.connect_timeout, init.read_timeout, init.timeout).or(request.timeouts);
+			if init.upload_progress.is_some() {
+				request.upload_progress = init.upload_progress;
+			}
+			if init.max_redirects.is_some() {
+				request.max_redirects = init.max_redirects;
+			}
+			if init.redirect_hook.is_some() {
+				request.redirect_hook = init.redirect_hook;
+			}
+
+			Ok(request)
+		}
+
+		/// Uses the redirect hook for the request, unless it already has one.
+		#[ion(skip)]
+		pub fn set_default_redirect_hook(&mut self, hook: Option<RedirectHook>) {
+			if self.redirect_hook.is_none() {
+				self.redirect_hook = hook;
+			}
+		}
 
+		/// Returns the abort signal of the request.
+		#[ion(skip)]
+		pub fn signal(&self) -> Signal {
+			self.signal.clone()
+		}
+
+		/// Creates a copy of the request. Streamed bodies are split, so that both requests can read them.
+		#[ion(name = "clone")]
+		pub fn duplicate(&self) -> Result<Request> {
+			if self.body_used {
+				return Err(Error::new("Request body has already been used.", ErrorKind::Type));
+			}
+			let mut request = self.clone()?;
+			request.body = self.body.tee();
 			Ok(request)
 		}
 
 		#[ion(get)]
-		pub fn get_headers(&self) -> Headers {
-			Headers::new(self.request.headers().clone(), true)
+		pub fn get_method(&self) -> String {
+			String::from(self.request.method().as_str())
+		}
+
+		#[ion(get)]
+		pub fn get_url(&self) -> String {
+			String::from(self.url.as_str())
+		}
+
+		/// Returns the headers of the request. The same object is returned on every access.
+		#[ion(get)]
+		pub fn get_headers(&mut self, cx: &Context) -> *mut JSObject {
+			match &self.headers_object {
+				Some(headers) => headers.get(),
+				None => {
+					let headers = Headers::new_object(cx, Headers::new(self.request.headers().clone(), HeadersGuard::Immutable));
+					self.headers_object = Some(Heap::boxed(headers));
+					headers
+				}
+			}
+		}
+
+		#[ion(get)]
+		pub fn get_credentials(&self) -> RequestCredentials {
+			self.credentials
+		}
+
+		#[ion(get)]
+		pub fn get_cache(&self) -> RequestCache {
+			self.cache
+		}
+
+		#[ion(get)]
+		pub fn get_redirect(&self) -> RequestRedirect {
+			self.redirect
+		}
+
+		#[ion(get)]
+		pub fn get_integrity(&self) -> String {
+			self.integrity.clone().unwrap_or_default()
+		}
+
+		/// Returns the signal the request was created with, or a signal which is never aborted.
+		/// The same signal is returned on every access.
+		#[ion(get)]
+		pub fn get_signal(&mut self, cx: &Context) -> *mut JSObject {
+			match &self.signal_object {
+				Some(signal) => signal.get(),
+				None => {
+					let signal = AbortSignal::new_object(cx, AbortSignal::from_signal(self.signal.clone()));
+					self.signal_object = Some(Heap::boxed(signal));
+					signal
+				}
+			}
+		}
+
+		#[ion(get)]
+		pub fn get_body_used(&self) -> bool {
+			self.body_used
+		}
+
+		fn take_body(&mut self) -> Result<FetchBody> {
+			if self.body_used {
+				return Err(Error::new("Request body has already been used.", ErrorKind::Type));
+			}
+			if !matches!(self.body, FetchBody::Empty) {
+				self.body_used = true;
+			}
+			Ok(self.body.clone())
+		}
+
+		pub fn arrayBuffer<'cx>(&mut self, cx: &'cx Context) -> Result<Promise<'cx>> {
+			let body = self.take_body()?;
+			Ok(future_to_promise(cx, async move {
+				let bytes = body.into_bytes().await?;
+				Ok::<_, Error>(ArrayBuffer::from(bytes.to_vec()))
+			}))
+		}
+
+		pub fn blob<'cx>(&mut self, cx: &'cx Context) -> Result<Promise<'cx>> {
+			let kind = self
+				.request
+				.headers()
+				.get(CONTENT_TYPE)
+				.and_then(|kind| kind.to_str().ok())
+				.unwrap_or("")
+				.to_string();
+			let body = self.take_body()?;
+			Ok(future_to_promise(cx, async move {
+				let bytes = body.into_bytes().await?;
+				Ok::<_, Error>(Blob::new(vec![BlobPart::Bytes(bytes)], &kind))
+			}))
+		}
+
+		pub fn text<'cx>(&mut self, cx: &'cx Context) -> Result<Promise<'cx>> {
+			let body = self.take_body()?;
+			Ok(future_to_promise(cx, async move {
+				let bytes = body.into_bytes().await?;
+				Ok::<_, Error>(String::from_utf8_lossy(&bytes).into_owned())
+			}))
+		}
+
+		pub fn json<'cx>(&mut self, cx: &'cx Context) -> Result<Promise<'cx>> {
+			let text = self.text(cx)?;
+			parse_json(cx, &text)
+		}
+	}
+
+	unsafe impl Traceable for Request {
+		#[inline]
+		unsafe fn trace(&self, trc: *mut JSTracer) {
+			unsafe {
+				self.signal_object.trace(trc);
+				self.headers_object.trace(trc);
+			}
 		}
 	}
 
@@ -197,7 +457,13 @@ pub mod class {
 		{
 			let object = Object::from_value(cx, value, true, ())?;
 			if Request::instance_of(cx, &object, None) {
-				Request::get_private(&object).clone()
+				// Requests are converted when they are consumed by `fetch` or another request, which uses their body.
+				let request = Request::get_private(&object);
+				let clone = request.clone()?;
+				if !matches!(request.body, FetchBody::Empty) {
+					request.body_used = true;
+				}
+				Ok(clone)
 			} else {
 				Err(Error::new("Expected Request", ErrorKind::Type))
 			}
@@ -205,52 +471,8 @@ pub mod class {
 	}
 }
 
-macro_rules! typedarray_to_bytes {
-	($body:expr) => {
-		Err(Error::new("Expected TypedArray or ArrayBuffer", ErrorKind::Type))
-	};
-	($body:expr, [$arr:ident, true]$(, $($rest:tt)*)?) => {
-		paste::paste! {
-			if let Ok(arr) = <::mozjs::typedarray::$arr>::from($body) {
-				Ok(Bytes::copy_from_slice(unsafe { arr.as_slice() }))
-			} else if let Ok(arr) = <::mozjs::typedarray::[<Heap $arr>]>::from($body) {
-				Ok(Bytes::copy_from_slice(unsafe { arr.as_slice() }))
-			} else {
-				typedarray_to_bytes!($body$(, $($rest)*)?)
-			}
-		}
-	};
-	($body:expr, [$arr:ident, false]$(, $($rest:tt)*)?) => {
-		paste::paste! {
-			if let Ok(arr) = <::mozjs::typedarray::$arr>::from($body) {
-				let bytes: &[u8] = cast_slice(arr.as_slice());
-				Ok(Bytes::copy_from_slice(bytes))
-			} else if let Ok(arr) = <::mozjs::typedarray::[<Heap $arr>]>::from($body) {
-				let bytes: &[u8] = cast_slice(arr.as_slice());
-				Ok(Bytes::copy_from_slice(bytes))
-			} else {
-				typedarray_to_bytes!($body$(, $($rest)*)?)
-			}
-		}
-	};
-}
-
-pub(crate) fn parse_body<'cx: 'v, 'v>(cx: &'cx Context, body: Value<'v>) -> Result<Bytes> {
-	if body.handle().is_string() {
-		Ok(Bytes::from(String::from_value(cx, &body, true, ()).unwrap()))
-	} else if body.handle().is_object() {
-		let body = body.to_object(cx);
-
-		let class = body.get_builtin_class(cx);
-		if class == ESClass::String {
-			let string = body.unbox_primitive(cx).unwrap();
-			return Ok(Bytes::from(String::from_value(cx, &string, true, ()).unwrap()));
-		}
-
-		typedarray_to_bytes!(body.handle().get(), [ArrayBuffer, true], [ArrayBufferView, true])
-	} else {
-		Err(Error::new("Expected Body to be String or Object", ErrorKind::Type))
-	}
+pub(crate) fn parse_body<'cx: 'v, 'v>(cx: &'cx Context, body: Value<'v>) -> Result<FetchBody> {
+	FetchBody::from_value(cx, &body, true, ())
 }
 
 pub(crate) fn clone_request(request: &hyper::Request<Body>) -> Result<hyper::Request<Body>> {
@@ -268,7 +490,7 @@ pub(crate) fn clone_request(request: &hyper::Request<Body>) -> Result<hyper::Req
 }
 
 pub(crate) fn check_url_scheme(url: &Url) -> Result<()> {
-	if url.scheme() == "https" || url.scheme() == "http" {
+	if url.scheme() == "https" || url.scheme() == "http" || is_local_scheme(url.scheme()) {
 		Ok(())
 	} else {
 		Err(Error::new("Invalid Scheme", None))
@@ -230,6 +230,23 @@ impl<'cx> FromValue<'cx> for RequestCredentials {
 	}
 }
 
+impl Display for RequestCredentials {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		use RequestCredentials as RC;
+		f.write_str(match self {
+			RC::Omit => "omit",
+			RC::SameOrigin => "same-origin",
+			RC::Include => "include",
+		})
+	}
+}
+
+impl<'cx> ToValue<'cx> for RequestCredentials {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		self.to_string().to_value(cx, value);
+	}
+}
+
 #[derive(Copy, Clone, Debug, Default)]
 pub enum RequestCache {
 	#[default]
@@ -270,6 +287,26 @@ impl<'cx> FromValue<'cx> for RequestCache {
 	}
 }
 
+impl Display for RequestCache {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		use RequestCache as RC;
+		f.write_str(match self {
+			RC::Default => "default",
+			RC::NoStore => "no-store",
+			RC::Reload => "reload",
+			RC::NoCache => "no-cache",
+			RC::ForceCache => "force-cache",
+			RC::OnlyIfCached => "only-if-cached",
+		})
+	}
+}
+
+impl<'cx> ToValue<'cx> for RequestCache {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		self.to_string().to_value(cx, value);
+	}
+}
+
 #[derive(Copy, Clone, Debug, Default)]
 pub enum RequestRedirect {
 	#[default]
@@ -303,3 +340,20 @@ impl<'cx> FromValue<'cx> for RequestRedirect {
 		RequestRedirect::from_str(&redirect)
 	}
 }
+
+impl Display for RequestRedirect {
+	fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+		use RequestRedirect as RR;
+		f.write_str(match self {
+			RR::Follow => "follow",
+			RR::Error => "error",
+			RR::Manual => "manual",
+		})
+	}
+}
+
+impl<'cx> ToValue<'cx> for RequestRedirect {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		self.to_string().to_value(cx, value);
+	}
+}
@@ -0,0 +1,75 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::future::Future;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::pin::Pin;
+use std::sync::Arc;
+
+use http::Uri;
+use http::uri::Scheme;
+use hyper::client::HttpConnector;
+use hyper::service::Service;
+use tokio::net::TcpStream;
+use tokio::time::Instant;
+
+use crate::globals::fetch::stats::{record_dns, TimedResolver};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Future which resolves to the addresses of a host.
+pub type ResolveFuture<'r> = Pin<Box<dyn Future<Output = io::Result<Vec<IpAddr>>> + Send + 'r>>;
+
+/// Resolves the hosts which the connections of a client are made to.
+/// Clients use the system resolver by default, but can be given a resolver to query other servers or override hosts.
+pub trait Resolver: Send + Sync {
+	fn resolve<'r>(&'r self, host: &'r str) -> ResolveFuture<'r>;
+}
+
+tokio::task_local! {
+	/// Resolver of the client sending the request, which is read by the connector when it establishes a connection.
+	pub(crate) static RESOLVER: Option<Arc<dyn Resolver>>;
+}
+
+/// Returns the resolver of the client sending the request, if it has one.
+pub(crate) fn resolver() -> Option<Arc<dyn Resolver>> {
+	RESOLVER.try_with(Option::clone).ok().flatten()
+}
+
+/// Connects to the host of a URI, using the resolver to find its addresses, which are tried in order.
+pub(crate) async fn connect_resolved(http: &mut HttpConnector<TimedResolver>, uri: Uri, resolver: &dyn Resolver) -> Result<TcpStream, BoxError> {
+	let host = uri
+		.host()
+		.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "URI must have a host"))?;
+	let host = host.trim_start_matches('[').trim_end_matches(']');
+	if host.parse::<IpAddr>().is_ok() {
+		return Ok(http.call(uri).await?);
+	}
+
+	let start = Instant::now();
+	let addresses = resolver.resolve(host).await;
+	record_dns(start.elapsed());
+
+	let scheme = uri.scheme().cloned().unwrap_or(Scheme::HTTP);
+	let port = uri.port_u16().unwrap_or(if scheme == Scheme::HTTPS { 443 } else { 80 });
+	let mut error = None;
+	for address in addresses? {
+		let uri = Uri::builder()
+			.scheme(scheme.clone())
+			.authority(SocketAddr::new(address, port).to_string())
+			.path_and_query("/")
+			.build()?;
+		match http.call(uri).await {
+			Ok(stream) => return Ok(stream),
+			Err(err) => error = Some(err),
+		}
+	}
+	match error {
+		Some(error) => Err(error.into()),
+		None => Err(io::Error::new(io::ErrorKind::NotFound, format!("No addresses found for {}", host)).into()),
+	}
+}
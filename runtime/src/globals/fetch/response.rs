@@ -9,15 +9,19 @@ pub use class::*;
 #[js_class]
 #[ion(runtime = crate)]
 pub mod class {
-	use bytes::{Buf, BufMut};
+	use bytes::{Buf, BufMut, Bytes};
+	use http::header::CONTENT_TYPE;
 	use hyper::Body;
 	use hyper::body::HttpBody;
 	use url::Url;
 
-	use ion::{Error, Result};
+	use ion::{Error, ErrorKind, Result};
 	use ion::typedarray::ArrayBuffer;
 
-	use crate::globals::fetch::Headers;
+	use crate::globals::fetch::{Headers, HeadersGuard, ResponseTiming};
+	use crate::globals::fetch::timeout::network_error;
+	use crate::globals::file::{Blob, BlobPart};
+	use crate::globals::form_data::{FormData, FormDataEntryValue, multipart};
 
 	#[ion(no_constructor, into_value)]
 	pub struct Response {
@@ -44,7 +48,7 @@ pub mod class {
 
 		#[ion(get)]
 		pub fn get_headers(&self) -> Headers {
-			Headers::new(self.response.headers().clone(), true)
+			Headers::new(self.response.headers().clone(), HeadersGuard::Immutable)
 		}
 
 		#[ion(get)]
@@ -62,6 +66,12 @@ pub mod class {
 			self.response.status().canonical_reason().map(String::from)
 		}
 
+		/// Returns the HTTP version of the response, such as `HTTP/1.1` or `HTTP/2.0`.
+		#[ion(get)]
+		pub fn get_version(&self) -> String {
+			format!("{:?}", self.response.version())
+		}
+
 		#[ion(get)]
 		pub fn get_redirected(&self) -> bool {
 			self.redirections >= 1
@@ -77,6 +87,13 @@ pub mod class {
 			String::from(self.locations[self.locations.len() - 1].as_str())
 		}
 
+		/// Returns the timing of the connection and the time to the first byte of the response, in milliseconds.
+		/// Responses which were not received over the network have no timing.
+		#[ion(get)]
+		pub fn get_timing(&self) -> Option<ResponseTiming> {
+			self.response.extensions().get::<ResponseTiming>().copied()
+		}
+
 		async fn read_to_bytes(&mut self) -> Result<Vec<u8>> {
 			if self.body_used {
 				return Err(Error::new("Response body has already been used.", None));
@@ -86,13 +103,13 @@ pub mod class {
 			let body = self.response.body_mut();
 
 			let first = if let Some(buf) = body.data().await {
-				buf?
+				buf.map_err(network_error)?
 			} else {
 				return Ok(Vec::new());
 			};
 
 			let second = if let Some(buf) = body.data().await {
-				buf?
+				buf.map_err(network_error)?
 			} else {
 				return Ok(first.to_vec());
 			};
@@ -103,7 +120,7 @@ pub mod class {
 			vec.put(second);
 
 			while let Some(buf) = body.data().await {
-				vec.put(buf?);
+				vec.put(buf.map_err(network_error)?);
 			}
 
 			Ok(vec)
@@ -114,6 +131,46 @@ pub mod class {
 			Ok(ArrayBuffer::from(bytes))
 		}
 
+		pub async fn blob(&mut self) -> Result<Blob> {
+			let kind = self
+				.response
+				.headers()
+				.get(CONTENT_TYPE)
+				.and_then(|kind| kind.to_str().ok())
+				.unwrap_or("")
+				.to_string();
+			let bytes = self.read_to_bytes().await?;
+			Ok(Blob::new(vec![BlobPart::Bytes(Bytes::from(bytes))], &kind))
+		}
+
+		pub async fn formData(&mut self) -> Result<FormData> {
+			let content_type = self
+				.response
+				.headers()
+				.get(CONTENT_TYPE)
+				.and_then(|kind| kind.to_str().ok())
+				.unwrap_or("")
+				.to_string();
+			let bytes = self.read_to_bytes().await?;
+
+			let (essence, parameters) = multipart::parse_parameters(&content_type);
+			let entries = if essence.eq_ignore_ascii_case("multipart/form-data") {
+				let boundary = parameters
+					.iter()
+					.find(|(name, _)| name == "boundary")
+					.ok_or_else(|| Error::new("Missing boundary in multipart/form-data Content-Type", ErrorKind::Type))?;
+				multipart::decode(&bytes, &boundary.1)?
+			} else if essence.eq_ignore_ascii_case("application/x-www-form-urlencoded") {
+				form_urlencoded::parse(&bytes)
+					.into_owned()
+					.map(|(name, value)| (name, FormDataEntryValue::String(value)))
+					.collect()
+			} else {
+				return Err(Error::new("Response body is not form data", ErrorKind::Type));
+			};
+			Ok(FormData { entries })
+		}
+
 		pub async fn text(&mut self) -> Result<String> {
 			let bytes = self.read_to_bytes().await?;
 			String::from_utf8(bytes).map_err(|e| Error::new(&format!("Invalid UTF-8 sequence: {}", e), None))
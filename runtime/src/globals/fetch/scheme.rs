@@ -0,0 +1,188 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::ffi::OsStr;
+use std::fs::metadata;
+use std::path::Path;
+use std::str;
+
+use http::{HeaderValue, Method};
+use http::header::{CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::Body;
+use tokio::task::spawn_blocking;
+use url::Url;
+
+use ion::{Error, ErrorKind, Result};
+
+use crate::config::Config;
+use crate::globals::fetch::{FetchBody, Request, Response};
+use crate::globals::file::{Blob, BlobPart, FileSection};
+
+/// Checks if a URL scheme is fetched locally, rather than over the network.
+/// `file:` URLs can only be fetched if they are enabled in the [Config].
+pub(crate) fn is_local_scheme(scheme: &str) -> bool {
+	scheme == "data" || (scheme == "file" && Config::global().file_urls)
+}
+
+fn is_whitespace(char: char) -> bool {
+	char.is_ascii_whitespace()
+}
+
+pub(crate) fn percent_decode(input: &[u8]) -> Vec<u8> {
+	let mut output = Vec::with_capacity(input.len());
+	let mut i = 0;
+	while i < input.len() {
+		if input[i] == b'%' && i + 2 < input.len() {
+			let byte = str::from_utf8(&input[i + 1..i + 3]).ok().and_then(|hex| u8::from_str_radix(hex, 16).ok());
+			if let Some(byte) = byte {
+				output.push(byte);
+				i += 3;
+				continue;
+			}
+		}
+		output.push(input[i]);
+		i += 1;
+	}
+	output
+}
+
+/// Decodes base64, as specified by the [Infra Standard](https://infra.spec.whatwg.org/#forgiving-base64-decode).
+fn forgiving_base64_decode(input: &[u8]) -> Option<Vec<u8>> {
+	let mut data: Vec<u8> = input.iter().copied().filter(|byte| !byte.is_ascii_whitespace()).collect();
+	if data.len() % 4 == 0 {
+		if data.ends_with(b"==") {
+			data.truncate(data.len() - 2);
+		} else if data.ends_with(b"=") {
+			data.truncate(data.len() - 1);
+		}
+	}
+	if data.len() % 4 == 1 || data.iter().any(|byte| !byte.is_ascii_alphanumeric() && *byte != b'+' && *byte != b'/') {
+		return None;
+	}
+
+	for byte in &mut data {
+		match *byte {
+			b'+' => *byte = b'-',
+			b'/' => *byte = b'_',
+			_ => {}
+		}
+	}
+	base64_url::decode(&data).ok()
+}
+
+fn is_token(token: &str) -> bool {
+	!token.is_empty()
+		&& token
+			.bytes()
+			.all(|byte| byte.is_ascii_graphic() && !b"\"(),/:;<=>?@[\\]{}".contains(&byte))
+}
+
+fn is_valid_mime(mime: &str) -> bool {
+	let essence = mime.split(';').next().unwrap_or("").trim_matches(is_whitespace);
+	match essence.split_once('/') {
+		Some((kind, subtype)) => is_token(kind) && is_token(subtype),
+		None => false,
+	}
+}
+
+/// Processes a `data:` URL into its MIME type and body, as specified by the [Fetch Standard](https://fetch.spec.whatwg.org/#data-url-processor).
+fn process_data_url(url: &Url) -> Option<(String, Vec<u8>)> {
+	let mut url = url.clone();
+	url.set_fragment(None);
+
+	let (mime, body) = url.as_str()["data:".len()..].split_once(',')?;
+	let mut mime = String::from(mime.trim_matches(is_whitespace));
+	let mut body = percent_decode(body.as_bytes());
+
+	if let Some(index) = mime.rfind(';') {
+		if mime[index + 1..].trim_start_matches(' ').eq_ignore_ascii_case("base64") {
+			body = forgiving_base64_decode(&body)?;
+			mime = String::from(mime[..index].trim_end_matches(' '));
+		}
+	}
+
+	if mime.starts_with(';') {
+		mime.insert_str(0, "text/plain");
+	}
+	if !is_valid_mime(&mime) {
+		mime = String::from("text/plain;charset=US-ASCII");
+	}
+	Some((mime, body))
+}
+
+/// Guesses the MIME type of a file from its extension.
+fn guess_type(path: &Path) -> &'static str {
+	let extension = path.extension().and_then(OsStr::to_str).map(str::to_ascii_lowercase);
+	match extension.as_deref() {
+		Some("css") => "text/css",
+		Some("csv") => "text/csv",
+		Some("gif") => "image/gif",
+		Some("htm" | "html") => "text/html",
+		Some("jpeg" | "jpg") => "image/jpeg",
+		Some("js" | "mjs") => "text/javascript",
+		Some("json") => "application/json",
+		Some("md") => "text/markdown",
+		Some("pdf") => "application/pdf",
+		Some("png") => "image/png",
+		Some("svg") => "image/svg+xml",
+		Some("txt") => "text/plain",
+		Some("wasm") => "application/wasm",
+		Some("webp") => "image/webp",
+		Some("xml") => "application/xml",
+		_ => "",
+	}
+}
+
+fn fetch_data(url: &Url) -> Result<hyper::Response<Body>> {
+	let (mime, body) = process_data_url(url).ok_or_else(|| Error::new("Invalid data: URL", ErrorKind::Type))?;
+
+	let mut response = hyper::Response::new(Body::empty());
+	let headers = response.headers_mut();
+	headers.insert(CONTENT_TYPE, HeaderValue::from_str(&mime)?);
+	headers.insert(CONTENT_LENGTH, HeaderValue::from(body.len()));
+	*response.body_mut() = Body::from(body);
+	Ok(response)
+}
+
+async fn fetch_file(url: &Url) -> Result<hyper::Response<Body>> {
+	let path = url.to_file_path().map_err(|_| Error::new("Invalid file: URL", ErrorKind::Type))?;
+	let (path, metadata) = spawn_blocking(move || {
+		let metadata = metadata(&path);
+		(path, metadata)
+	})
+	.await?;
+
+	let metadata = metadata.map_err(|error| Error::new(&format!("Failed to read {}: {}", path.display(), error), ErrorKind::Type))?;
+	if !metadata.is_file() {
+		return Err(Error::new(&format!("{} is not a file", path.display()), ErrorKind::Type));
+	}
+
+	let kind = guess_type(&path);
+	let body = FetchBody::Blob(Blob::new(vec![BlobPart::File(FileSection::new(path, &metadata))], kind));
+
+	let mut response = hyper::Response::new(Body::empty());
+	body.add_headers(response.headers_mut())?;
+	*response.body_mut() = body.into_http_body();
+	Ok(response)
+}
+
+/// Fetches a request with a `data:` or `file:` URL, as specified by the [Fetch Standard](https://fetch.spec.whatwg.org/#scheme-fetch).
+/// Only `GET` requests are supported. File contents are read lazily, as the body is consumed.
+pub(crate) async fn scheme_fetch(request: &Request) -> Result<Response> {
+	if request.request.method() != Method::GET {
+		return Err(Error::new(
+			&format!("{}: URLs can only be fetched with GET", request.url.scheme()),
+			ErrorKind::Type,
+		));
+	}
+
+	let response = match request.url.scheme() {
+		"data" => fetch_data(&request.url)?,
+		"file" if Config::global().file_urls => fetch_file(&request.url).await?,
+		_ => return Err(Error::new("Invalid Scheme", ErrorKind::Type)),
+	};
+	Ok(Response::new(response, 0, vec![request.url.clone()]))
+}
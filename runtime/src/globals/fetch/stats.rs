@@ -0,0 +1,414 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::io;
+use std::io::IoSlice;
+use std::mem::take;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::task::{Context as TaskContext, Poll};
+use std::time::Duration;
+
+use futures::stream;
+use http::Uri;
+use hyper::Body;
+use hyper::body::HttpBody;
+use hyper::client::connect::{Connected, Connection};
+use hyper::client::connect::dns::{GaiAddrs, GaiResolver, Name};
+use hyper::service::Service;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::time::Instant;
+use url::Url;
+
+use ion::{Context, Object, Result, Value};
+use ion::conversions::ToValue;
+
+use crate::globals::fetch::request::RequestCache;
+use crate::globals::fetch::Transport;
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+tokio::task_local! {
+	/// Phases of the connection being established, which are recorded by the resolver and connector as they complete.
+	static CONNECT_PHASES: Arc<Mutex<ConnectPhases>>;
+}
+
+#[derive(Default)]
+struct ConnectPhases {
+	dns: Duration,
+	connected: Option<Instant>,
+}
+
+/// Records the duration of a DNS lookup for the connection being established.
+pub(crate) fn record_dns(duration: Duration) {
+	let _ = CONNECT_PHASES.try_with(|phases| phases.lock().unwrap().dns += duration);
+}
+
+/// Records that the TCP connection, including any proxy tunnel, has been established.
+pub(crate) fn record_connected() {
+	let _ = CONNECT_PHASES.try_with(|phases| phases.lock().unwrap().connected = Some(Instant::now()));
+}
+
+fn host_key(host: &str, port: Option<u16>) -> String {
+	match port {
+		Some(port) => format!("{}:{}", host, port),
+		None => String::from(host),
+	}
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct HostStats {
+	connections: u64,
+	requests: u64,
+}
+
+#[derive(Debug, Default)]
+struct StatsInner {
+	requests: AtomicU64,
+	bytes_sent: AtomicU64,
+	bytes_received: AtomicU64,
+	hosts: Mutex<HashMap<String, HostStats>>,
+}
+
+/// Live statistics of a client, which are shared between the client, its clones and its connector.
+/// Connections are active while a request is in flight to their host, and idle otherwise.
+#[derive(Clone, Debug, Default)]
+pub struct ClientStats {
+	inner: Arc<StatsInner>,
+}
+
+impl ClientStats {
+	/// Returns the number of requests issued, including redirects.
+	pub fn requests(&self) -> u64 {
+		self.inner.requests.load(Ordering::Relaxed)
+	}
+
+	/// Returns the number of bytes written to connections, before TLS encryption, so excluding TLS overhead.
+	pub fn bytes_sent(&self) -> u64 {
+		self.inner.bytes_sent.load(Ordering::Relaxed)
+	}
+
+	/// Returns the number of bytes read from connections, after TLS decryption, so excluding TLS overhead.
+	pub fn bytes_received(&self) -> u64 {
+		self.inner.bytes_received.load(Ordering::Relaxed)
+	}
+
+	/// Returns the number of active and idle connections to each host.
+	pub fn connections(&self) -> Vec<(String, u64, u64)> {
+		let hosts = self.inner.hosts.lock().unwrap();
+		let mut connections: Vec<_> = hosts
+			.iter()
+			.map(|(host, stats)| {
+				let active = stats.requests.min(stats.connections);
+				(host.clone(), active, stats.connections - active)
+			})
+			.collect();
+		connections.sort_by(|(a, ..), (b, ..)| a.cmp(b));
+		connections
+	}
+
+	fn update_host<F: FnOnce(&mut HostStats)>(&self, host: &str, update: F) {
+		let mut hosts = self.inner.hosts.lock().unwrap();
+		let stats = hosts.entry(String::from(host)).or_default();
+		update(stats);
+		if stats.connections == 0 && stats.requests == 0 {
+			hosts.remove(host);
+		}
+	}
+
+	fn start_request(&self, url: &Url) -> ActiveRequest {
+		let host = host_key(url.host_str().unwrap_or_default(), url.port());
+		self.inner.requests.fetch_add(1, Ordering::Relaxed);
+		self.update_host(&host, |stats| stats.requests += 1);
+		ActiveRequest { stats: self.clone(), host }
+	}
+}
+
+impl<'cx> ToValue<'cx> for ClientStats {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut hosts = Object::new(cx);
+		for (host, active, idle) in self.connections() {
+			let mut connections = Object::new(cx);
+			connections.set_as(cx, "active", &active);
+			connections.set_as(cx, "idle", &idle);
+			hosts.set_as(cx, host.as_str(), &connections);
+		}
+
+		let mut object = Object::new(cx);
+		object.set_as(cx, "requests", &self.requests());
+		object.set_as(cx, "bytesSent", &self.bytes_sent());
+		object.set_as(cx, "bytesReceived", &self.bytes_received());
+		object.set_as(cx, "hosts", &hosts);
+		object.to_value(cx, value);
+	}
+}
+
+/// Request in flight to a host, until its response body has been read or is dropped.
+struct ActiveRequest {
+	stats: ClientStats,
+	host: String,
+}
+
+impl Drop for ActiveRequest {
+	fn drop(&mut self) {
+		self.stats.update_host(&self.host, |stats| stats.requests -= 1);
+	}
+}
+
+/// Durations of establishing a connection.
+#[derive(Clone, Copy, Debug, Default)]
+struct ConnectionTiming {
+	dns: Duration,
+	connect: Duration,
+	tls: Duration,
+}
+
+/// Timing of a connection, which is attached to every response received over it.
+#[derive(Clone, Debug)]
+struct ConnectionInfo {
+	timing: ConnectionTiming,
+	used: Arc<AtomicBool>,
+}
+
+/// Timing of a response, in the phases of the connection it was received over and the time to its first byte.
+/// Responses over reused connections have no connection phases.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ResponseTiming {
+	pub dns: Duration,
+	pub connect: Duration,
+	pub tls: Duration,
+	pub ttfb: Duration,
+	pub reused: bool,
+}
+
+impl ResponseTiming {
+	fn new(connection: Option<&ConnectionInfo>, elapsed: Duration) -> ResponseTiming {
+		match connection {
+			Some(info) if !info.used.swap(true, Ordering::Relaxed) => {
+				let ConnectionTiming { dns, connect, tls } = info.timing;
+				ResponseTiming {
+					dns,
+					connect,
+					tls,
+					ttfb: elapsed.saturating_sub(dns + connect + tls),
+					reused: false,
+				}
+			}
+			Some(_) => ResponseTiming {
+				ttfb: elapsed,
+				reused: true,
+				..ResponseTiming::default()
+			},
+			None => ResponseTiming {
+				ttfb: elapsed,
+				..ResponseTiming::default()
+			},
+		}
+	}
+}
+
+impl<'cx> ToValue<'cx> for ResponseTiming {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "dns", &(self.dns.as_secs_f64() * 1000.0));
+		object.set_as(cx, "connect", &(self.connect.as_secs_f64() * 1000.0));
+		object.set_as(cx, "tls", &(self.tls.as_secs_f64() * 1000.0));
+		object.set_as(cx, "ttfb", &(self.ttfb.as_secs_f64() * 1000.0));
+		object.set_as(cx, "reused", &self.reused);
+		object.to_value(cx, value);
+	}
+}
+
+/// Sends a request through a transport, counting it in the statistics of the client and recording its timing.
+/// The request remains active until the body of its response has been read, or is dropped.
+pub(crate) async fn send_tracked(
+	transport: &dyn Transport, stats: &ClientStats, request: hyper::Request<Body>, url: &Url, cache: RequestCache,
+) -> Result<hyper::Response<Body>> {
+	let active = stats.start_request(url);
+	let start = Instant::now();
+	let mut response = transport.send(request, url, cache).await?;
+
+	let timing = ResponseTiming::new(response.extensions().get::<ConnectionInfo>(), start.elapsed());
+	response.extensions_mut().insert(timing);
+
+	let body = take(response.body_mut());
+	let stream = stream::unfold(Some((body, active)), |state| async move {
+		let (mut body, active) = state?;
+		let chunk = body.data().await?;
+		Some((chunk, Some((body, active))))
+	});
+	*response.body_mut() = Body::wrap_stream(stream);
+	Ok(response)
+}
+
+/// Resolver which records the duration of DNS lookups for the connection being established.
+#[derive(Clone, Debug)]
+pub struct TimedResolver {
+	resolver: GaiResolver,
+}
+
+impl TimedResolver {
+	pub fn new() -> TimedResolver {
+		TimedResolver { resolver: GaiResolver::new() }
+	}
+}
+
+impl Default for TimedResolver {
+	fn default() -> TimedResolver {
+		TimedResolver::new()
+	}
+}
+
+impl Service<Name> for TimedResolver {
+	type Response = GaiAddrs;
+	type Error = io::Error;
+	type Future = Pin<Box<dyn Future<Output = io::Result<GaiAddrs>> + Send>>;
+
+	fn poll_ready(&mut self, cx: &mut TaskContext) -> Poll<io::Result<()>> {
+		self.resolver.poll_ready(cx)
+	}
+
+	fn call(&mut self, name: Name) -> Self::Future {
+		let resolve = self.resolver.call(name);
+		Box::pin(async move {
+			let start = Instant::now();
+			let addrs = resolve.await;
+			record_dns(start.elapsed());
+			addrs
+		})
+	}
+}
+
+/// Connector which counts the connections of a client and the bytes sent over them, and records how long they took to establish.
+#[derive(Clone)]
+pub struct TrackedConnector<C> {
+	connector: C,
+	stats: ClientStats,
+}
+
+impl<C> TrackedConnector<C> {
+	pub fn new(connector: C, stats: ClientStats) -> TrackedConnector<C> {
+		TrackedConnector { connector, stats }
+	}
+}
+
+impl<C> Service<Uri> for TrackedConnector<C>
+where
+	C: Service<Uri>,
+	C::Response: AsyncRead + AsyncWrite + Connection + Unpin + Send + 'static,
+	C::Error: Into<BoxError>,
+	C::Future: Send + 'static,
+{
+	type Response = TrackedStream<C::Response>;
+	type Error = BoxError;
+	type Future = Pin<Box<dyn Future<Output = std::result::Result<TrackedStream<C::Response>, BoxError>> + Send>>;
+
+	fn poll_ready(&mut self, cx: &mut TaskContext) -> Poll<std::result::Result<(), BoxError>> {
+		self.connector.poll_ready(cx).map_err(Into::into)
+	}
+
+	fn call(&mut self, uri: Uri) -> Self::Future {
+		let host = host_key(uri.host().unwrap_or_default(), uri.port_u16());
+		let stats = self.stats.clone();
+		let phases = Arc::new(Mutex::new(ConnectPhases::default()));
+
+		let start = Instant::now();
+		let connect = CONNECT_PHASES.scope(Arc::clone(&phases), self.connector.call(uri));
+		Box::pin(async move {
+			let stream = connect.await.map_err(Into::into)?;
+			let end = Instant::now();
+
+			let timing = {
+				let phases = phases.lock().unwrap();
+				let connected = phases.connected.unwrap_or(end);
+				ConnectionTiming {
+					dns: phases.dns,
+					connect: connected.duration_since(start).saturating_sub(phases.dns),
+					tls: end.duration_since(connected),
+				}
+			};
+			Ok(TrackedStream::new(stream, stats, host, timing))
+		})
+	}
+}
+
+/// Connection which is counted in the statistics of a client until it is closed.
+pub struct TrackedStream<S> {
+	stream: S,
+	stats: ClientStats,
+	host: String,
+	info: ConnectionInfo,
+}
+
+impl<S> TrackedStream<S> {
+	fn new(stream: S, stats: ClientStats, host: String, timing: ConnectionTiming) -> TrackedStream<S> {
+		stats.update_host(&host, |stats| stats.connections += 1);
+		TrackedStream {
+			stream,
+			stats,
+			host,
+			info: ConnectionInfo { timing, used: Arc::default() },
+		}
+	}
+
+	fn count_sent(&self, poll: Poll<io::Result<usize>>) -> Poll<io::Result<usize>> {
+		if let Poll::Ready(Ok(written)) = &poll {
+			self.stats.inner.bytes_sent.fetch_add(*written as u64, Ordering::Relaxed);
+		}
+		poll
+	}
+}
+
+impl<S> Drop for TrackedStream<S> {
+	fn drop(&mut self) {
+		self.stats.update_host(&self.host, |stats| stats.connections -= 1);
+	}
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for TrackedStream<S> {
+	fn poll_read(mut self: Pin<&mut Self>, cx: &mut TaskContext, buf: &mut ReadBuf) -> Poll<io::Result<()>> {
+		let filled = buf.filled().len();
+		let poll = Pin::new(&mut self.stream).poll_read(cx, buf);
+		if let Poll::Ready(Ok(())) = &poll {
+			let read = buf.filled().len() - filled;
+			self.stats.inner.bytes_received.fetch_add(read as u64, Ordering::Relaxed);
+		}
+		poll
+	}
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for TrackedStream<S> {
+	fn poll_write(mut self: Pin<&mut Self>, cx: &mut TaskContext, buf: &[u8]) -> Poll<io::Result<usize>> {
+		let poll = Pin::new(&mut self.stream).poll_write(cx, buf);
+		self.count_sent(poll)
+	}
+
+	fn poll_write_vectored(mut self: Pin<&mut Self>, cx: &mut TaskContext, bufs: &[IoSlice]) -> Poll<io::Result<usize>> {
+		let poll = Pin::new(&mut self.stream).poll_write_vectored(cx, bufs);
+		self.count_sent(poll)
+	}
+
+	fn is_write_vectored(&self) -> bool {
+		self.stream.is_write_vectored()
+	}
+
+	fn poll_flush(mut self: Pin<&mut Self>, cx: &mut TaskContext) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.stream).poll_flush(cx)
+	}
+
+	fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut TaskContext) -> Poll<io::Result<()>> {
+		Pin::new(&mut self.stream).poll_shutdown(cx)
+	}
+}
+
+impl<S: Connection> Connection for TrackedStream<S> {
+	fn connected(&self) -> Connected {
+		self.stream.connected().extra(self.info.clone())
+	}
+}
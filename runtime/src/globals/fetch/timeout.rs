@@ -0,0 +1,160 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::Cell;
+use std::error;
+use std::fmt::{Display, Formatter};
+use std::io;
+use std::time::Duration;
+
+use futures::stream;
+use hyper::Body;
+use hyper::body::HttpBody;
+use tokio::time::{Instant, timeout_at};
+
+use ion::Error;
+
+type BoxError = Box<dyn error::Error + Send + Sync>;
+
+tokio::task_local! {
+	/// Connect timeout of the request being sent, which is read by the connector when it establishes a connection.
+	pub(crate) static CONNECT_TIMEOUT: Option<Duration>;
+}
+
+tokio::task_local! {
+	/// Whether the request being sent has failed with a transient error, which is recorded when the error is converted.
+	pub(crate) static TRANSIENT_FAILURE: Cell<bool>;
+}
+
+/// Returns the connect timeout of the request being sent, if any.
+pub(crate) fn connect_timeout() -> Option<Duration> {
+	CONNECT_TIMEOUT.try_with(|timeout| *timeout).ok().flatten()
+}
+
+/// Records whether the request being sent has failed with a transient error.
+pub(crate) fn set_transient_failure(transient: bool) {
+	let _ = TRANSIENT_FAILURE.try_with(|failure| failure.set(transient));
+}
+
+/// Kinds of I/O errors which are transient, as the connection failed or was closed.
+const TRANSIENT_KINDS: &[io::ErrorKind] = &[
+	io::ErrorKind::ConnectionRefused,
+	io::ErrorKind::ConnectionReset,
+	io::ErrorKind::ConnectionAborted,
+	io::ErrorKind::NotConnected,
+	io::ErrorKind::BrokenPipe,
+	io::ErrorKind::TimedOut,
+	io::ErrorKind::UnexpectedEof,
+];
+
+/// Checks if an error is transient, so that sending the request again could succeed.
+/// Connection failures, resets and timeouts are transient, while errors such as failed TLS verification are not.
+fn is_transient(error: &hyper::Error) -> bool {
+	let mut kind = None;
+	let mut source = error::Error::source(error);
+	while let Some(cause) = source {
+		if cause.is::<TimeoutError>() {
+			return true;
+		}
+		if let Some(error) = cause.downcast_ref::<io::Error>() {
+			if error.get_ref().is_some_and(|inner| inner.is::<TimeoutError>()) {
+				return true;
+			}
+			kind = kind.or(Some(error.kind()));
+		}
+		source = cause.source();
+	}
+
+	match kind {
+		Some(kind) => TRANSIENT_KINDS.contains(&kind),
+		None => error.is_incomplete_message() || error.is_closed() || error.is_timeout(),
+	}
+}
+
+/// Timeouts applied to the phases of a request.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Timeouts {
+	/// Maximum time to establish a connection, including tunnelling through a proxy.
+	pub connect: Option<Duration>,
+	/// Maximum time to wait for each chunk of the response body.
+	pub read: Option<Duration>,
+	/// Maximum time for the whole request, until the response body has been read.
+	pub total: Option<Duration>,
+}
+
+impl Timeouts {
+	pub fn from_millis(connect: Option<u64>, read: Option<u64>, total: Option<u64>) -> Timeouts {
+		Timeouts {
+			connect: connect.map(Duration::from_millis),
+			read: read.map(Duration::from_millis),
+			total: total.map(Duration::from_millis),
+		}
+	}
+
+	/// Uses these timeouts, falling back to the timeouts of the client for those which are not set.
+	pub fn or(self, client: Timeouts) -> Timeouts {
+		Timeouts {
+			connect: self.connect.or(client.connect),
+			read: self.read.or(client.read),
+			total: self.total.or(client.total),
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum TimeoutError {
+	Connect(Duration),
+	Read(Duration),
+	Total(Duration),
+}
+
+impl Display for TimeoutError {
+	fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+		match self {
+			TimeoutError::Connect(duration) => write!(f, "Connect Timeout: {}ms", duration.as_millis()),
+			TimeoutError::Read(duration) => write!(f, "Read Timeout: {}ms", duration.as_millis()),
+			TimeoutError::Total(duration) => write!(f, "Request Timeout: {}ms", duration.as_millis()),
+		}
+	}
+}
+
+impl error::Error for TimeoutError {}
+
+/// Converts a [hyper::Error] into an [Error], reporting the timeout which caused it if there was one.
+/// Also records whether the error is transient.
+pub(crate) fn network_error(error: hyper::Error) -> Error {
+	set_transient_failure(is_transient(&error));
+	let mut source = error::Error::source(&error);
+	while let Some(cause) = source {
+		if let Some(timeout) = cause.downcast_ref::<TimeoutError>() {
+			return Error::from(*timeout);
+		}
+		source = cause.source();
+	}
+	Error::from(error)
+}
+
+/// Applies the read timeout to each chunk of a response body, and ends the body with an error at the deadline of the request.
+pub(crate) fn timeout_body(body: Body, read: Option<Duration>, deadline: Option<(Instant, Duration)>) -> Body {
+	if read.is_none() && deadline.is_none() {
+		return body;
+	}
+
+	let stream = stream::unfold(Some(body), move |body| async move {
+		let mut body = body?;
+		let read = read.map(|read| (Instant::now() + read, TimeoutError::Read(read)));
+		let deadline = deadline.map(|(deadline, total)| (deadline, TimeoutError::Total(total)));
+		let (instant, elapsed) = read.into_iter().chain(deadline).min_by_key(|(instant, _)| *instant)?;
+
+		match timeout_at(instant, body.data()).await {
+			Ok(Some(Ok(chunk))) => Some((Ok(chunk), Some(body))),
+			Ok(Some(Err(error))) => Some((Err(BoxError::from(error)), None)),
+			Ok(None) => None,
+			Err(_) => Some((Err(BoxError::from(elapsed)), None)),
+		}
+	});
+	Body::wrap_stream(stream)
+}
@@ -0,0 +1,162 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::str;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use ring::digest::{digest, SHA256};
+use rustls::{Certificate, ClientConfig, OwnedTrustAnchor, PrivateKey, RootCertStore, ServerName};
+use rustls::client::{ServerCertVerified, ServerCertVerifier, WebPkiVerifier};
+use rustls_pemfile::Item;
+
+use ion::{Error, ErrorKind, Result};
+
+/// Options used to configure TLS connections made by a client.
+#[derive(Clone, Debug, Default)]
+pub struct TlsOptions {
+	/// Uses the certificate store of the operating system instead of the bundled Mozilla roots.
+	pub system_roots: bool,
+	/// PEM-encoded certificates which are trusted in addition to the roots.
+	pub ca: Vec<String>,
+	/// PEM-encoded certificate chain presented to servers which request client authentication.
+	pub certificate: Option<String>,
+	/// PEM-encoded private key of the client certificate.
+	pub key: Option<String>,
+	/// SHA-256 fingerprints of server certificates, in hexadecimal. Colons between bytes are allowed.
+	/// If any are specified, servers must present one of these certificates.
+	pub pinned_certificates: Vec<String>,
+}
+
+fn webpki_roots(roots: &mut RootCertStore) {
+	roots.add_trust_anchors(
+		webpki_roots::TLS_SERVER_ROOTS
+			.0
+			.iter()
+			.map(|anchor| OwnedTrustAnchor::from_subject_spki_name_constraints(anchor.subject, anchor.spki, anchor.name_constraints)),
+	);
+}
+
+fn parse_pem(pem: &str) -> Result<Vec<Item>> {
+	rustls_pemfile::read_all(&mut pem.as_bytes()).map_err(|_| Error::new("Invalid PEM data", ErrorKind::Type))
+}
+
+fn parse_certificates(pem: &str) -> Result<Vec<Certificate>> {
+	let certificates: Vec<_> = parse_pem(pem)?
+		.into_iter()
+		.filter_map(|item| match item {
+			Item::X509Certificate(certificate) => Some(Certificate(certificate)),
+			_ => None,
+		})
+		.collect();
+	if certificates.is_empty() {
+		return Err(Error::new("PEM data does not contain any certificates", ErrorKind::Type));
+	}
+	Ok(certificates)
+}
+
+fn parse_private_key(pem: &str) -> Result<PrivateKey> {
+	parse_pem(pem)?
+		.into_iter()
+		.find_map(|item| match item {
+			Item::RSAKey(key) | Item::PKCS8Key(key) | Item::ECKey(key) => Some(PrivateKey(key)),
+			_ => None,
+		})
+		.ok_or_else(|| Error::new("PEM data does not contain a private key", ErrorKind::Type))
+}
+
+fn parse_fingerprint(fingerprint: &str) -> Result<Vec<u8>> {
+	let hex: Vec<_> = fingerprint.bytes().filter(|byte| *byte != b':').collect();
+	let invalid = || Error::new(&format!("Invalid certificate fingerprint: {}", fingerprint), ErrorKind::Type);
+	if hex.len() != 64 {
+		return Err(invalid());
+	}
+
+	hex.chunks(2)
+		.map(|byte| {
+			str::from_utf8(byte)
+				.ok()
+				.and_then(|byte| u8::from_str_radix(byte, 16).ok())
+				.ok_or_else(invalid)
+		})
+		.collect()
+}
+
+/// Verifier which requires servers to present a pinned certificate, in addition to a valid chain.
+struct PinnedVerifier {
+	verifier: WebPkiVerifier,
+	fingerprints: Vec<Vec<u8>>,
+}
+
+impl ServerCertVerifier for PinnedVerifier {
+	fn verify_server_cert(
+		&self, end_entity: &Certificate, intermediates: &[Certificate], server_name: &ServerName, scts: &mut dyn Iterator<Item = &[u8]>,
+		ocsp_response: &[u8], now: SystemTime,
+	) -> std::result::Result<ServerCertVerified, rustls::Error> {
+		let verified = self
+			.verifier
+			.verify_server_cert(end_entity, intermediates, server_name, scts, ocsp_response, now)?;
+
+		let fingerprint = digest(&SHA256, &end_entity.0);
+		if self.fingerprints.iter().any(|pinned| pinned[..] == *fingerprint.as_ref()) {
+			Ok(verified)
+		} else {
+			Err(rustls::Error::General(String::from(
+				"Server certificate does not match pinned fingerprints",
+			)))
+		}
+	}
+}
+
+impl TlsOptions {
+	/// Creates the TLS configuration described by the options.
+	pub fn client_config(&self) -> Result<ClientConfig> {
+		let mut roots = RootCertStore::empty();
+		if self.system_roots {
+			let certificates = rustls_native_certs::load_native_certs()?;
+			let certificates: Vec<_> = certificates.into_iter().map(|certificate| certificate.0).collect();
+			roots.add_parsable_certificates(&certificates);
+		} else {
+			webpki_roots(&mut roots);
+		}
+		for pem in &self.ca {
+			for certificate in parse_certificates(pem)? {
+				roots.add(&certificate)?;
+			}
+		}
+
+		let verifier: Arc<dyn ServerCertVerifier> = if self.pinned_certificates.is_empty() {
+			Arc::new(WebPkiVerifier::new(roots, None))
+		} else {
+			let fingerprints = self
+				.pinned_certificates
+				.iter()
+				.map(|fingerprint| parse_fingerprint(fingerprint))
+				.collect::<Result<_>>()?;
+			Arc::new(PinnedVerifier {
+				verifier: WebPkiVerifier::new(roots, None),
+				fingerprints,
+			})
+		};
+		let builder = ClientConfig::builder().with_safe_defaults().with_custom_certificate_verifier(verifier);
+
+		match (&self.certificate, &self.key) {
+			(Some(certificate), Some(key)) => Ok(builder.with_client_auth_cert(parse_certificates(certificate)?, parse_private_key(key)?)?),
+			(None, None) => Ok(builder.with_no_client_auth()),
+			_ => Err(Error::new("Client certificate and key must be specified together", ErrorKind::Type)),
+		}
+	}
+}
+
+/// Creates the default TLS configuration, which trusts the bundled Mozilla roots.
+pub fn default_client_config() -> ClientConfig {
+	let mut roots = RootCertStore::empty();
+	webpki_roots(&mut roots);
+	ClientConfig::builder()
+		.with_safe_defaults()
+		.with_root_certificates(roots)
+		.with_no_client_auth()
+}
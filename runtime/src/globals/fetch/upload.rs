@@ -0,0 +1,312 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::cell::RefCell;
+use std::fmt;
+use std::fmt::{Debug, Formatter};
+use std::io;
+use std::rc::Rc;
+use std::sync::{Arc, Mutex};
+
+use bytes::Bytes;
+use futures::stream::{self, BoxStream, StreamExt};
+use hyper::Body;
+use mozjs::jsapi::JSObject;
+use tokio::sync::{mpsc, watch};
+use tokio::sync::mpsc::{OwnedPermit, Sender, UnboundedReceiver};
+use tokio::task::spawn_local;
+
+use ion::{Context, Error, ErrorKind, Function, Object, Promise, Result, Value};
+use ion::conversions::{FromValue, ToPropertyKey};
+use ion::flags::PropertyFlags;
+use ion::symbol::WellKnownSymbolCode;
+
+use crate::globals::file::BlobPartInit;
+use crate::promise::future_to_promise;
+
+type Chunk = io::Result<Bytes>;
+
+fn get_method<'cx, K: ToPropertyKey<'cx>>(cx: &'cx Context, object: &Object, key: K) -> Option<Function<'cx>> {
+	object
+		.get(cx, key)
+		.filter(|value| value.handle().is_object())
+		.and_then(|value| Function::from_object(cx, &value.to_object(cx).into_local()))
+}
+
+fn stream_error(message: &str) -> io::Error {
+	io::Error::new(io::ErrorKind::Other, message)
+}
+
+/// Reader of a `ReadableStream`, or iterator of an async iterable, which the chunks of a body are read from.
+struct ChunkSource {
+	object: *mut JSObject,
+	read: &'static str,
+	cancel: &'static str,
+}
+
+impl ChunkSource {
+	/// Stops reading chunks, cancelling the source if the body was abandoned before it was exhausted.
+	fn close(&self, cx: &Context, cancel: bool) {
+		if cancel {
+			let object = Object::from(cx.root_object(self.object));
+			if let Some(cancel) = get_method(cx, &object, self.cancel) {
+				let _ = cancel.call(cx, &object, &[]);
+			}
+		}
+		cx.unroot_persistent_object(self.object);
+	}
+}
+
+/// Calls the handler once the promise settles, with whether it was fulfilled and its result or reason.
+fn on_settled<F>(cx: &Context, promise: &Promise, handler: F)
+where
+	F: Fn(&Context, bool, Option<&Value>) + Clone + 'static,
+{
+	let rejected = handler.clone();
+	promise.add_reactions(
+		cx,
+		Some(Function::from_closure(
+			cx,
+			"",
+			Box::new(move |args| {
+				handler(args.cx(), true, args.value(0));
+				Ok(Value::undefined(args.cx()))
+			}),
+			1,
+			PropertyFlags::empty(),
+		)),
+		Some(Function::from_closure(
+			cx,
+			"",
+			Box::new(move |args| {
+				rejected(args.cx(), false, args.value(0));
+				Ok(Value::undefined(args.cx()))
+			}),
+			1,
+			PropertyFlags::empty(),
+		)),
+	);
+}
+
+/// Waits until the body has room for another chunk, then reads it from the source.
+/// Chunks are only read as the body is sent, so the source is not read ahead of the network.
+fn pump(cx: &Context, source: Rc<ChunkSource>, sender: Sender<Chunk>) {
+	let permit = Rc::new(RefCell::new(None));
+	let reserved = Rc::clone(&permit);
+	let promise = future_to_promise(cx, async move {
+		*reserved.borrow_mut() = sender.reserve_owned().await.ok();
+		Ok::<_, ()>(())
+	});
+
+	on_settled(cx, &promise, move |cx, _, _| match permit.borrow_mut().take() {
+		Some(permit) => read_chunk(cx, Rc::clone(&source), permit),
+		None => source.close(cx, true),
+	});
+}
+
+fn read_chunk(cx: &Context, source: Rc<ChunkSource>, permit: OwnedPermit<Chunk>) {
+	let object = Object::from(cx.root_object(source.object));
+	let promise = get_method(cx, &object, source.read)
+		.and_then(|read| read.call(cx, &object, &[]).ok())
+		.filter(|result| result.handle().is_object())
+		.and_then(|result| Promise::from(result.to_object(cx).into_local()));
+	let promise = match promise {
+		Some(promise) => promise,
+		None => {
+			permit.send(Err(stream_error("Failed to read from request body stream")));
+			return source.close(cx, false);
+		}
+	};
+
+	let permit = Rc::new(RefCell::new(Some(permit)));
+	on_settled(cx, &promise, move |cx, fulfilled, result| {
+		let permit = match permit.borrow_mut().take() {
+			Some(permit) => permit,
+			None => return,
+		};
+		if !fulfilled {
+			permit.send(Err(stream_error("Request body stream errored")));
+			return source.close(cx, false);
+		}
+
+		let result = match result.filter(|result| result.handle().is_object()) {
+			Some(result) => result.to_object(cx),
+			None => {
+				permit.send(Err(stream_error("Request body stream returned an invalid result")));
+				return source.close(cx, true);
+			}
+		};
+
+		let done: Option<bool> = result.get_as(cx, "done", false, ());
+		if done.unwrap_or(false) {
+			drop(permit);
+			return source.close(cx, false);
+		}
+
+		let value = result.get(cx, "value").unwrap_or_else(|| Value::undefined(cx));
+		match BlobPartInit::from_value(cx, &value, true, ()) {
+			Ok(BlobPartInit::Bytes(bytes)) => {
+				let sender = permit.send(Ok(bytes));
+				pump(cx, Rc::clone(&source), sender);
+			}
+			_ => {
+				permit.send(Err(stream_error("Request body stream chunks must be strings or buffers")));
+				source.close(cx, true);
+			}
+		}
+	});
+}
+
+fn receiver_stream<T: Send + 'static>(receiver: UnboundedReceiver<T>) -> BoxStream<'static, T> {
+	stream::unfold(receiver, |mut receiver| async move { receiver.recv().await.map(|item| (item, receiver)) }).boxed()
+}
+
+fn clone_chunk(chunk: &Chunk) -> Chunk {
+	match chunk {
+		Ok(bytes) => Ok(bytes.clone()),
+		Err(error) => Err(io::Error::new(error.kind(), error.to_string())),
+	}
+}
+
+/// Request body which is read from a `ReadableStream` or an async iterable as it is sent.
+/// The chunks can only be read once, so clones of the body share them, unless the body is [teed](StreamBody::tee).
+#[derive(Clone)]
+pub struct StreamBody {
+	stream: Arc<Mutex<Option<BoxStream<'static, Chunk>>>>,
+}
+
+impl StreamBody {
+	/// Creates a body from a `ReadableStream`, or an async iterable. Returns [None] if the object is neither.
+	pub(crate) fn from_object(cx: &Context, object: &Object) -> Result<Option<StreamBody>> {
+		let (source, read, cancel) = if let Some(get_reader) = get_method(cx, object, "getReader") {
+			(get_reader.call(cx, object, &[]), "read", "cancel")
+		} else if let Some(iterator) = get_method(cx, object, WellKnownSymbolCode::AsyncIterator) {
+			(iterator.call(cx, object, &[]), "next", "return")
+		} else {
+			return Ok(None);
+		};
+
+		let source = source
+			.ok()
+			.filter(|source| source.handle().is_object())
+			.ok_or_else(|| Error::new("Failed to read from request body stream", ErrorKind::Type))?;
+		let source = cx.root_persistent_object(source.to_object(cx).handle().get()).get();
+
+		let (sender, receiver) = mpsc::channel(1);
+		pump(cx, Rc::new(ChunkSource { object: source, read, cancel }), sender);
+		let stream = stream::unfold(
+			receiver,
+			|mut receiver| async move { receiver.recv().await.map(|chunk| (chunk, receiver)) },
+		);
+		Ok(Some(StreamBody {
+			stream: Arc::new(Mutex::new(Some(stream.boxed()))),
+		}))
+	}
+
+	/// Splits the body into two independent bodies, which each receive every chunk.
+	/// Chunks are buffered until both bodies have read them.
+	pub(crate) fn tee(&self) -> StreamBody {
+		let mut stream = self.stream.lock().unwrap();
+		let (first, second) = match stream.take() {
+			Some(mut source) => {
+				let (first, first_receiver) = mpsc::unbounded_channel();
+				let (second, second_receiver) = mpsc::unbounded_channel();
+				spawn_local(async move {
+					while let Some(chunk) = source.next().await {
+						let first_sent = first.send(clone_chunk(&chunk)).is_ok();
+						let second_sent = second.send(chunk).is_ok();
+						if !first_sent && !second_sent {
+							break;
+						}
+					}
+				});
+				(Some(receiver_stream(first_receiver)), Some(receiver_stream(second_receiver)))
+			}
+			None => (None, None),
+		};
+		*stream = first;
+		StreamBody { stream: Arc::new(Mutex::new(second)) }
+	}
+
+	/// Creates the body sent over the network. Bodies which have already been sent are empty.
+	pub(crate) fn into_http_body(self) -> Body {
+		match self.stream.lock().unwrap().take() {
+			Some(stream) => Body::wrap_stream(stream),
+			None => Body::empty(),
+		}
+	}
+}
+
+impl Debug for StreamBody {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		f.debug_struct("StreamBody").finish_non_exhaustive()
+	}
+}
+
+/// Reports the progress of sending a request body to a callback, as the bytes sent and the total size, if known.
+#[derive(Clone)]
+pub struct UploadProgress {
+	sender: Arc<watch::Sender<(u64, Option<u64>)>>,
+}
+
+impl UploadProgress {
+	/// Creates a reporter which calls the callback whenever more of the body has been sent.
+	/// The callback is released once the request, and all of its clones, are dropped.
+	pub(crate) fn new(cx: &Context, callback: &Function) -> UploadProgress {
+		let callback = cx.root_persistent_object(callback.to_object(cx).handle().get()).get();
+		let (sender, receiver) = watch::channel((0, None));
+		listen(cx, callback, receiver);
+		UploadProgress { sender: Arc::new(sender) }
+	}
+
+	/// Wraps a body, counting the bytes which are sent.
+	pub(crate) fn wrap_body(&self, body: Body, total: Option<u64>) -> Body {
+		let sender = Arc::clone(&self.sender);
+		sender.send_replace((0, total));
+		Body::wrap_stream(body.inspect(move |chunk| {
+			if let Ok(chunk) = chunk {
+				sender.send_modify(|(loaded, _)| *loaded += chunk.len() as u64);
+			}
+		}))
+	}
+}
+
+impl Debug for UploadProgress {
+	fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+		f.debug_struct("UploadProgress").finish_non_exhaustive()
+	}
+}
+
+fn listen(cx: &Context, callback: *mut JSObject, receiver: watch::Receiver<(u64, Option<u64>)>) {
+	let receiver = Rc::new(RefCell::new(Some(receiver)));
+	let waiting = Rc::clone(&receiver);
+	let promise = future_to_promise(cx, async move {
+		let mut receiver = waiting.borrow_mut().take().unwrap();
+		let changed = receiver.changed().await.is_ok();
+		*waiting.borrow_mut() = changed.then_some(receiver);
+		Ok::<_, ()>(())
+	});
+
+	on_settled(cx, &promise, move |cx, _, _| {
+		let receiver = match receiver.borrow_mut().take() {
+			Some(receiver) => receiver,
+			None => return cx.unroot_persistent_object(callback),
+		};
+
+		let (loaded, total) = *receiver.borrow();
+		let object = cx.root_object(callback);
+		if let Some(function) = Function::from_object(cx, &object) {
+			let total = total.map_or_else(|| Value::undefined(cx), |total| Value::f64(cx, total as f64));
+			let _ = function.call(cx, &Object::global(cx), &[Value::f64(cx, loaded as f64), total]);
+		}
+		listen(cx, callback, receiver);
+	});
+}
+
+pub(crate) fn parse_upload_progress<'cx: 'v, 'v>(cx: &'cx Context, callback: Value<'v>) -> Result<UploadProgress> {
+	let callback = Function::from_value(cx, &callback, true, ())?;
+	Ok(UploadProgress::new(cx, &callback))
+}
@@ -0,0 +1,375 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use bytes::Bytes;
+use futures::stream;
+use futures::stream::{BoxStream, StreamExt};
+use mozjs::jsapi::ESClass;
+use mozjs::typedarray::{ArrayBuffer, ArrayBufferView};
+use os_str_bytes::{OsStrBytes, OsStringBytes};
+use tokio::task::spawn_blocking;
+
+pub use class::Blob;
+use ion::{Context, Error, ErrorKind, Result, Value};
+use ion::clone::{StructuredCloneReader, StructuredCloneWriter};
+use ion::conversions::FromValue;
+
+use crate::globals::file::get_blob;
+
+/// Size of the chunks in which files are read.
+const CHUNK_SIZE: u64 = 64 * 1024;
+
+/// Represents a section of a file, which is only read when needed.
+#[derive(Clone, Debug)]
+pub struct FileSection {
+	path: PathBuf,
+	offset: u64,
+	length: u64,
+	modified: Option<SystemTime>,
+}
+
+impl FileSection {
+	/// Creates a section spanning an entire file, with the metadata of the file.
+	/// Reading the section fails if the file is modified afterwards.
+	pub fn new(path: PathBuf, metadata: &fs::Metadata) -> FileSection {
+		FileSection {
+			path,
+			offset: 0,
+			length: metadata.len(),
+			modified: metadata.modified().ok(),
+		}
+	}
+
+	pub fn modified(&self) -> Option<SystemTime> {
+		self.modified
+	}
+
+	fn open(&self) -> io::Result<fs::File> {
+		let mut file = fs::File::open(&self.path)?;
+		let metadata = file.metadata()?;
+		if metadata.modified().ok() != self.modified || metadata.len() < self.offset + self.length {
+			return Err(io::Error::new(io::ErrorKind::Other, "File has been modified since it was opened"));
+		}
+		file.seek(SeekFrom::Start(self.offset))?;
+		Ok(file)
+	}
+}
+
+/// Represents a contiguous section of the data of a [Blob].
+#[derive(Clone, Debug)]
+pub enum BlobPart {
+	Bytes(Bytes),
+	File(FileSection),
+}
+
+impl BlobPart {
+	pub fn size(&self) -> u64 {
+		match self {
+			BlobPart::Bytes(bytes) => bytes.len() as u64,
+			BlobPart::File(section) => section.length,
+		}
+	}
+
+	fn slice(&self, start: u64, end: u64) -> BlobPart {
+		match self {
+			BlobPart::Bytes(bytes) => BlobPart::Bytes(bytes.slice(start as usize..end as usize)),
+			BlobPart::File(section) => BlobPart::File(FileSection {
+				offset: section.offset + start,
+				length: end - start,
+				..section.clone()
+			}),
+		}
+	}
+
+	/// Creates a stream of the data of the part, which reads files in chunks.
+	fn stream(self) -> BoxStream<'static, io::Result<Bytes>> {
+		match self {
+			BlobPart::Bytes(bytes) => stream::iter((!bytes.is_empty()).then_some(Ok(bytes))).boxed(),
+			BlobPart::File(section) => stream::try_unfold((section, None), |(mut section, file): (FileSection, Option<fs::File>)| async move {
+				if section.length == 0 {
+					return Ok(None);
+				}
+
+				let length = section.length.min(CHUNK_SIZE);
+				let reader = section.clone();
+				let (file, chunk) = spawn_blocking(move || {
+					let mut file = match file {
+						Some(file) => file,
+						None => reader.open()?,
+					};
+					let mut chunk = vec![0; length as usize];
+					file.read_exact(&mut chunk)?;
+					Ok::<_, io::Error>((file, chunk))
+				})
+				.await
+				.map_err(|error| io::Error::new(io::ErrorKind::Other, error))??;
+
+				section.offset += length;
+				section.length -= length;
+				Ok(Some((Bytes::from(chunk), (section, Some(file)))))
+			})
+			.boxed(),
+		}
+	}
+}
+
+#[derive(Default, FromValue)]
+pub struct BlobPropertyBag {
+	#[ion(name = "type")]
+	pub(crate) kind: Option<String>,
+}
+
+/// Represents a value which can be used as a part of a [Blob], or as a body.
+pub enum BlobPartInit {
+	Bytes(Bytes),
+	Blob(Blob),
+}
+
+impl BlobPartInit {
+	pub(crate) fn into_parts(parts: Vec<BlobPartInit>) -> Vec<BlobPart> {
+		let mut result = Vec::with_capacity(parts.len());
+		for part in parts {
+			match part {
+				BlobPartInit::Bytes(bytes) if !bytes.is_empty() => result.push(BlobPart::Bytes(bytes)),
+				BlobPartInit::Bytes(_) => {}
+				BlobPartInit::Blob(blob) => result.extend(blob.parts),
+			}
+		}
+		result
+	}
+}
+
+impl<'cx> FromValue<'cx> for BlobPartInit {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, _: bool, _: ()) -> Result<BlobPartInit>
+	where
+		'cx: 'v,
+	{
+		if value.handle().is_string() {
+			return Ok(BlobPartInit::Bytes(Bytes::from(String::from_value(cx, value, true, ())?)));
+		} else if !value.handle().is_object() {
+			return Err(Error::new("Expected String, BufferSource or Blob", ErrorKind::Type));
+		}
+
+		let object = value.to_object(cx);
+		if let Ok(blob) = get_blob(cx, &object) {
+			return Ok(BlobPartInit::Blob(blob.clone()));
+		}
+		if object.get_builtin_class(cx) == ESClass::String {
+			let string = object.unbox_primitive(cx).unwrap();
+			return Ok(BlobPartInit::Bytes(Bytes::from(String::from_value(cx, &string, true, ())?)));
+		}
+
+		let object = object.handle().get();
+		if let Ok(buffer) = ArrayBuffer::from(object) {
+			Ok(BlobPartInit::Bytes(Bytes::copy_from_slice(unsafe { buffer.as_slice() })))
+		} else if let Ok(view) = ArrayBufferView::from(object) {
+			Ok(BlobPartInit::Bytes(Bytes::copy_from_slice(unsafe { view.as_slice() })))
+		} else {
+			Err(Error::new("Expected String, BufferSource or Blob", ErrorKind::Type))
+		}
+	}
+}
+
+/// Resolves a possibly negative index relative to the size of a blob.
+fn relative_index(index: Option<i64>, size: u64, default: u64) -> u64 {
+	match index {
+		Some(index) if index < 0 => size.saturating_sub(index.unsigned_abs()),
+		Some(index) => (index as u64).min(size),
+		None => default,
+	}
+}
+
+/// Normalises a MIME type, which is replaced with an empty string if it contains characters outside U+0020 to U+007E.
+fn normalise_type(kind: &str) -> String {
+	if kind.bytes().all(|byte| (0x20..=0x7E).contains(&byte)) {
+		kind.to_ascii_lowercase()
+	} else {
+		String::new()
+	}
+}
+
+pub(crate) fn write_blob(blob: &Blob, writer: &mut StructuredCloneWriter) -> Result<()> {
+	writer.write_string(&blob.kind)?;
+	writer.write_pair(blob.parts.len() as u32, 0)?;
+	for part in &blob.parts {
+		match part {
+			BlobPart::Bytes(bytes) => {
+				writer.write_pair(0, 0)?;
+				writer.write_bytes(bytes)?;
+			}
+			BlobPart::File(section) => {
+				let modified = section.modified.and_then(|modified| modified.duration_since(UNIX_EPOCH).ok());
+				writer.write_pair(1, modified.map(|modified| modified.subsec_nanos()).unwrap_or(u32::MAX))?;
+				writer.write_u64(modified.map(|modified| modified.as_secs()).unwrap_or(0))?;
+				writer.write_bytes(&section.path.as_os_str().to_raw_bytes())?;
+				writer.write_u64(section.offset)?;
+				writer.write_u64(section.length)?;
+			}
+		}
+	}
+	Ok(())
+}
+
+pub(crate) fn read_blob(reader: &mut StructuredCloneReader) -> Result<Blob> {
+	let kind = reader.read_string()?;
+	let (length, _) = reader.read_pair()?;
+	let mut parts = Vec::with_capacity(length as usize);
+	for _ in 0..length {
+		match reader.read_pair()? {
+			(0, _) => parts.push(BlobPart::Bytes(Bytes::from(reader.read_bytes()?))),
+			(1, nanos) => {
+				let seconds = reader.read_u64()?;
+				let modified = (nanos != u32::MAX).then(|| UNIX_EPOCH + Duration::new(seconds, nanos));
+				let path = OsString::from_raw_vec(reader.read_bytes()?).map_err(|_| Error::new("Invalid path in structured clone data", None))?;
+				parts.push(BlobPart::File(FileSection {
+					path: PathBuf::from(path),
+					offset: reader.read_u64()?,
+					length: reader.read_u64()?,
+					modified,
+				}));
+			}
+			_ => return Err(Error::new("Invalid blob part in structured clone data", None)),
+		}
+	}
+	Ok(Blob { parts, kind })
+}
+
+#[js_class]
+mod class {
+	use std::io;
+
+	use bytes::{Bytes, BytesMut};
+	use futures::stream;
+	use futures::stream::{BoxStream, StreamExt};
+	use mozjs::conversions::ConversionBehavior::Clamp;
+	use tokio::sync::Mutex;
+
+	use ion::{Context, Error, Object, Promise, Result};
+	use ion::clone::{StructuredClone, StructuredCloneReader, StructuredCloneWriter};
+	use ion::typedarray::ArrayBuffer;
+
+	use crate::globals::file::{BlobStream, get_blob};
+	use crate::globals::file::blob::{BlobPart, BlobPartInit, BlobPropertyBag, normalise_type, read_blob, relative_index, write_blob};
+	use crate::promise::future_to_promise;
+
+	#[derive(Clone, Debug, Default)]
+	#[ion(into_value)]
+	pub struct Blob {
+		pub(crate) parts: Vec<BlobPart>,
+		pub(crate) kind: String,
+	}
+
+	impl Blob {
+		#[ion(constructor)]
+		pub fn constructor(parts: Option<Vec<BlobPartInit>>, options: Option<BlobPropertyBag>) -> Blob {
+			let parts = BlobPartInit::into_parts(parts.unwrap_or_default());
+			Blob::new(parts, &options.unwrap_or_default().kind.unwrap_or_default())
+		}
+
+		/// Creates a [Blob] from its parts, normalising its type.
+		#[ion(skip)]
+		pub fn new(parts: Vec<BlobPart>, kind: &str) -> Blob {
+			Blob { parts, kind: normalise_type(kind) }
+		}
+
+		#[ion(skip)]
+		pub fn size(&self) -> u64 {
+			self.parts.iter().map(BlobPart::size).sum()
+		}
+
+		/// Creates a stream of the data of the blob, which reads files lazily.
+		#[ion(skip)]
+		pub fn stream_bytes(&self) -> BoxStream<'static, io::Result<Bytes>> {
+			stream::iter(self.parts.clone()).flat_map(BlobPart::stream).boxed()
+		}
+
+		/// Reads all the data of the blob.
+		#[ion(skip)]
+		pub async fn bytes(&self) -> Result<Bytes> {
+			let mut bytes = BytesMut::with_capacity(self.size() as usize);
+			let mut stream = self.stream_bytes();
+			while let Some(chunk) = stream.next().await {
+				bytes.extend_from_slice(&chunk?);
+			}
+			Ok(bytes.freeze())
+		}
+
+		#[ion(get)]
+		pub fn get_size(#[ion(this)] this: &Object, cx: &Context) -> Result<u64> {
+			Ok(get_blob(cx, this)?.size())
+		}
+
+		#[ion(get)]
+		pub fn get_type(#[ion(this)] this: &Object, cx: &Context) -> Result<String> {
+			Ok(get_blob(cx, this)?.kind.clone())
+		}
+
+		pub fn slice(
+			#[ion(this)] this: &Object, cx: &Context, #[ion(convert = Clamp)] start: Option<i64>, #[ion(convert = Clamp)] end: Option<i64>,
+			kind: Option<String>,
+		) -> Result<Blob> {
+			let blob = get_blob(cx, this)?;
+			let size = blob.size();
+			let start = relative_index(start, size, 0);
+			let end = relative_index(end, size, size).max(start);
+
+			let mut parts = Vec::new();
+			let mut position = 0;
+			for part in &blob.parts {
+				let (part_start, part_end) = (position, position + part.size());
+				position = part_end;
+				if part_end <= start || part_start >= end {
+					continue;
+				}
+				parts.push(part.slice(start.saturating_sub(part_start), end.min(part_end) - part_start));
+			}
+
+			Ok(Blob::new(parts, &kind.unwrap_or_default()))
+		}
+
+		pub fn text<'cx>(#[ion(this)] this: &Object, cx: &'cx Context) -> Result<Promise<'cx>> {
+			let blob = get_blob(cx, this)?.clone();
+			Ok(future_to_promise(cx, async move {
+				let bytes = blob.bytes().await?;
+				Ok::<_, Error>(String::from_utf8_lossy(&bytes).into_owned())
+			}))
+		}
+
+		pub fn arrayBuffer<'cx>(#[ion(this)] this: &Object, cx: &'cx Context) -> Result<Promise<'cx>> {
+			let blob = get_blob(cx, this)?.clone();
+			Ok(future_to_promise(cx, async move {
+				let bytes = blob.bytes().await?;
+				Ok::<_, Error>(ArrayBuffer::from(bytes.to_vec()))
+			}))
+		}
+
+		pub fn stream(#[ion(this)] this: &Object, cx: &Context) -> Result<BlobStream> {
+			let blob = get_blob(cx, this)?;
+			Ok(BlobStream {
+				stream: Mutex::new(Some(blob.stream_bytes())),
+			})
+		}
+	}
+
+	impl StructuredClone for Blob {
+		fn write(&self, _: &Context, writer: &mut StructuredCloneWriter) -> Result<()> {
+			write_blob(self, writer)
+		}
+
+		fn read(_: &Context, reader: &mut StructuredCloneReader) -> Result<Blob> {
+			read_blob(reader)
+		}
+	}
+}
@@ -0,0 +1,108 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use mozjs::conversions::ConversionBehavior;
+
+pub use class::File;
+
+use crate::globals::file::BlobPropertyBag;
+
+#[derive(Default, FromValue)]
+pub struct FilePropertyBag {
+	#[ion(inherit)]
+	blob: BlobPropertyBag,
+	#[ion(convert = ConversionBehavior::Default)]
+	last_modified: Option<i64>,
+}
+
+#[js_class]
+mod class {
+	use std::fs::Metadata;
+	use std::path::PathBuf;
+	use std::time::UNIX_EPOCH;
+
+	use chrono::Utc;
+
+	use ion::{Context, Result};
+	use ion::clone::{StructuredClone, StructuredCloneReader, StructuredCloneWriter};
+
+	use crate::globals::file::{Blob, BlobPart, BlobPartInit, FileSection};
+	use crate::globals::file::blob::{read_blob, write_blob};
+	use crate::globals::file::file::FilePropertyBag;
+
+	#[derive(Clone, Debug)]
+	#[ion(into_value)]
+	pub struct File {
+		pub(crate) blob: Blob,
+		pub(crate) name: String,
+		pub(crate) last_modified: i64,
+	}
+
+	impl File {
+		#[ion(constructor)]
+		pub fn constructor(parts: Vec<BlobPartInit>, name: String, options: Option<FilePropertyBag>) -> File {
+			let FilePropertyBag { blob, last_modified } = options.unwrap_or_default();
+			File {
+				blob: Blob::new(BlobPartInit::into_parts(parts), &blob.kind.unwrap_or_default()),
+				name,
+				last_modified: last_modified.unwrap_or_else(|| Utc::now().timestamp_millis()),
+			}
+		}
+
+		/// Creates a [File] with the data of a [Blob], which was last modified now.
+		#[ion(skip)]
+		pub fn new(blob: Blob, name: String) -> File {
+			File {
+				blob,
+				name,
+				last_modified: Utc::now().timestamp_millis(),
+			}
+		}
+
+		/// Creates a [File] which lazily reads the file at the given path, using its metadata.
+		#[ion(skip)]
+		pub fn from_metadata(path: PathBuf, metadata: &Metadata, kind: &str) -> File {
+			let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+			let section = FileSection::new(path, metadata);
+			let last_modified = section
+				.modified()
+				.and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+				.map(|modified| modified.as_millis() as i64)
+				.unwrap_or_else(|| Utc::now().timestamp_millis());
+
+			File {
+				blob: Blob::new(vec![BlobPart::File(section)], kind),
+				name,
+				last_modified,
+			}
+		}
+
+		#[ion(get)]
+		pub fn get_name(&self) -> String {
+			self.name.clone()
+		}
+
+		#[ion(get)]
+		pub fn get_last_modified(&self) -> i64 {
+			self.last_modified
+		}
+	}
+
+	impl StructuredClone for File {
+		fn write(&self, _: &Context, writer: &mut StructuredCloneWriter) -> Result<()> {
+			write_blob(&self.blob, writer)?;
+			writer.write_string(&self.name)?;
+			writer.write_u64(self.last_modified as u64)
+		}
+
+		fn read(_: &Context, reader: &mut StructuredCloneReader) -> Result<File> {
+			let blob = read_blob(reader)?;
+			let name = reader.read_string()?;
+			let last_modified = reader.read_u64()? as i64;
+			Ok(File { blob, name, last_modified })
+		}
+	}
+}
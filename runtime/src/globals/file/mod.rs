@@ -0,0 +1,35 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+pub use blob::{Blob, BlobPart, BlobPartInit, BlobPropertyBag, FileSection};
+pub use file::File;
+use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result};
+use ion::clone::register_structured_clone;
+pub use stream::BlobStream;
+
+use crate::globals::events::inherit;
+
+mod blob;
+mod file;
+mod stream;
+
+/// Gets the [Blob] of an object, which can be an instance of [Blob] or [File].
+pub fn get_blob<'a>(cx: &Context, object: &'a Object) -> Result<&'a mut Blob> {
+	if Blob::instance_of(cx, object, None) {
+		Ok(Blob::get_private(object))
+	} else if File::instance_of(cx, object, None) {
+		Ok(&mut File::get_private(object).blob)
+	} else {
+		Err(Error::new("Expected Blob", ErrorKind::Type))
+	}
+}
+
+pub fn define(cx: &Context, global: &mut Object) -> bool {
+	register_structured_clone::<Blob>();
+	register_structured_clone::<File>();
+
+	Blob::init_class(cx, global).0 && File::init_class(cx, global).0 && BlobStream::init_class(cx, global).0 && inherit::<File, Blob>(cx, global)
+}
@@ -0,0 +1,82 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+pub use class::BlobStream;
+use ion::{Context, Object, Value};
+use ion::conversions::ToValue;
+use ion::typedarray::Uint8Array;
+
+pub struct ChunkResult {
+	value: Option<Uint8Array>,
+	done: bool,
+}
+
+impl<'cx> ToValue<'cx> for ChunkResult {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		let mut object = Object::new(cx);
+		object.set_as(cx, "value", &self.value);
+		object.set_as(cx, "done", &self.done);
+		object.to_value(cx, value);
+	}
+}
+
+#[js_class]
+mod class {
+	use std::io;
+
+	use bytes::Bytes;
+	use futures::stream::{BoxStream, StreamExt};
+	use mozjs::jsapi::JSObject;
+	use tokio::sync::Mutex;
+
+	use ion::{Object, Result};
+	use ion::symbol::WellKnownSymbolCode;
+	use ion::typedarray::Uint8Array;
+
+	use crate::globals::file::stream::ChunkResult;
+
+	/// Async iterator over the chunks of a [Blob](crate::globals::file::Blob), returned by `Blob.prototype.stream`.
+	#[ion(no_constructor, into_value)]
+	pub struct BlobStream {
+		pub(crate) stream: Mutex<Option<BoxStream<'static, io::Result<Bytes>>>>,
+	}
+
+	impl BlobStream {
+		pub async fn next(&self) -> Result<ChunkResult> {
+			let mut stream = self.stream.lock().await;
+			let chunk = match stream.as_mut() {
+				Some(chunks) => chunks.next().await,
+				None => None,
+			};
+
+			match chunk {
+				Some(Ok(chunk)) => Ok(ChunkResult {
+					value: Some(Uint8Array::from(chunk.to_vec())),
+					done: false,
+				}),
+				Some(Err(error)) => {
+					*stream = None;
+					Err(error.into())
+				}
+				None => {
+					*stream = None;
+					Ok(ChunkResult { value: None, done: true })
+				}
+			}
+		}
+
+		#[ion(name = "return")]
+		pub async fn finish(&self) -> ChunkResult {
+			*self.stream.lock().await = None;
+			ChunkResult { value: None, done: true }
+		}
+
+		#[ion(name = WellKnownSymbolCode::AsyncIterator)]
+		pub fn iterator(#[ion(this)] this: &Object) -> *mut JSObject {
+			this.handle().get()
+		}
+	}
+}
@@ -0,0 +1,184 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+pub use class::FormData;
+use ion::{Array, ClassDefinition, Context, Error, ErrorKind, JSIterator, Object, Result, Value};
+use ion::conversions::{FromValue, ToValue};
+
+use crate::globals::file::{Blob, File};
+
+pub(crate) mod multipart;
+
+#[derive(Clone, Debug)]
+pub enum FormDataEntryValue {
+	String(String),
+	File(File),
+}
+
+impl FormDataEntryValue {
+	/// Creates an entry value from a string or [Blob]. Blobs are converted to a [File] with the given filename.
+	fn new(cx: &Context, value: &Value, filename: Option<String>) -> Result<FormDataEntryValue> {
+		if value.handle().is_object() {
+			let object = value.to_object(cx);
+			if File::instance_of(cx, &object, None) {
+				let file = File::get_private(&object).clone();
+				return Ok(FormDataEntryValue::File(match filename {
+					Some(name) => File { name, ..file },
+					None => file,
+				}));
+			} else if Blob::instance_of(cx, &object, None) {
+				let blob = Blob::get_private(&object).clone();
+				return Ok(FormDataEntryValue::File(File::new(
+					blob,
+					filename.unwrap_or_else(|| String::from("blob")),
+				)));
+			}
+		}
+
+		if filename.is_some() {
+			return Err(Error::new("Filename can only be given for Blob values", ErrorKind::Type));
+		}
+		Ok(FormDataEntryValue::String(String::from_value(cx, value, false, ())?))
+	}
+}
+
+impl<'cx> ToValue<'cx> for FormDataEntryValue {
+	fn to_value(&self, cx: &'cx Context, value: &mut Value) {
+		match self {
+			FormDataEntryValue::String(string) => string.to_value(cx, value),
+			FormDataEntryValue::File(file) => File::new_object(cx, file.clone()).to_value(cx, value),
+		}
+	}
+}
+
+#[derive(Clone, Copy)]
+enum IteratorKind {
+	Entries,
+	Keys,
+	Values,
+}
+
+pub struct FormDataIterator {
+	index: usize,
+	kind: IteratorKind,
+}
+
+impl JSIterator for FormDataIterator {
+	fn next_value<'cx>(&mut self, cx: &'cx Context, private: &Value<'cx>) -> Option<Value<'cx>> {
+		let object = private.to_object(cx);
+		let form_data = FormData::get_private(&object);
+		let (name, value) = form_data.entries.get(self.index)?;
+		self.index += 1;
+
+		match self.kind {
+			IteratorKind::Entries => {
+				let mut entry = Array::new(cx);
+				entry.set_as(cx, 0, name);
+				entry.set_as(cx, 1, value);
+				Some(entry.as_value(cx))
+			}
+			IteratorKind::Keys => Some(name.as_value(cx)),
+			IteratorKind::Values => Some(value.as_value(cx)),
+		}
+	}
+}
+
+#[js_class]
+mod class {
+	use ion::{Context, Object, Result, Value};
+	use ion::conversions::ToValue;
+	use ion::symbol::WellKnownSymbolCode;
+
+	use crate::globals::file::Blob;
+	use crate::globals::form_data::{FormDataEntryValue, FormDataIterator, IteratorKind, multipart};
+
+	#[derive(Clone, Debug, Default)]
+	#[ion(into_value)]
+	pub struct FormData {
+		pub(crate) entries: Vec<(String, FormDataEntryValue)>,
+	}
+
+	impl FormData {
+		#[ion(constructor)]
+		pub fn constructor() -> FormData {
+			FormData::default()
+		}
+
+		pub fn append<'cx>(&mut self, cx: &'cx Context, name: String, value: Value<'cx>, filename: Option<String>) -> Result<()> {
+			let value = FormDataEntryValue::new(cx, &value, filename)?;
+			self.entries.push((name, value));
+			Ok(())
+		}
+
+		pub fn delete(&mut self, name: String) {
+			self.entries.retain(|(key, _)| key != &name);
+		}
+
+		pub fn get(&self, name: String) -> Option<FormDataEntryValue> {
+			self.entries.iter().find(|(key, _)| key == &name).map(|(_, value)| value.clone())
+		}
+
+		pub fn getAll(&self, name: String) -> Vec<FormDataEntryValue> {
+			self.entries
+				.iter()
+				.filter(|(key, _)| key == &name)
+				.map(|(_, value)| value.clone())
+				.collect()
+		}
+
+		pub fn has(&self, name: String) -> bool {
+			self.entries.iter().any(|(key, _)| key == &name)
+		}
+
+		pub fn set<'cx>(&mut self, cx: &'cx Context, name: String, value: Value<'cx>, filename: Option<String>) -> Result<()> {
+			let value = FormDataEntryValue::new(cx, &value, filename)?;
+			match self.entries.iter().position(|(key, _)| key == &name) {
+				Some(index) => {
+					self.entries[index].1 = value;
+					let mut i = 0;
+					self.entries.retain(|(key, _)| {
+						i += 1;
+						i <= index + 1 || key != &name
+					});
+				}
+				None => self.entries.push((name, value)),
+			}
+			Ok(())
+		}
+
+		pub fn entries<'cx: 'o, 'o>(cx: &'cx Context, #[ion(this)] this: &Object<'o>) -> ion::Iterator {
+			let iterator = FormDataIterator { index: 0, kind: IteratorKind::Entries };
+			ion::Iterator::new(iterator, &this.as_value(cx))
+		}
+
+		pub fn keys<'cx: 'o, 'o>(cx: &'cx Context, #[ion(this)] this: &Object<'o>) -> ion::Iterator {
+			let iterator = FormDataIterator { index: 0, kind: IteratorKind::Keys };
+			ion::Iterator::new(iterator, &this.as_value(cx))
+		}
+
+		pub fn values<'cx: 'o, 'o>(cx: &'cx Context, #[ion(this)] this: &Object<'o>) -> ion::Iterator {
+			let iterator = FormDataIterator { index: 0, kind: IteratorKind::Values };
+			ion::Iterator::new(iterator, &this.as_value(cx))
+		}
+
+		#[ion(name = WellKnownSymbolCode::Iterator)]
+		pub fn iterator<'cx: 'o, 'o>(cx: &'cx Context, #[ion(this)] this: &Object<'o>) -> ion::Iterator {
+			let iterator = FormDataIterator { index: 0, kind: IteratorKind::Entries };
+			ion::Iterator::new(iterator, &this.as_value(cx))
+		}
+
+		/// Encodes the entries as `multipart/form-data`, as a [Blob] with the generated `Content-Type`.
+		/// The data of files is not read until the [Blob] is.
+		#[ion(skip)]
+		pub fn to_multipart(&self) -> Result<Blob> {
+			multipart::encode(&self.entries)
+		}
+	}
+}
+
+pub fn define(cx: &Context, global: &mut Object) -> bool {
+	FormData::init_class(cx, global).0
+}
@@ -0,0 +1,208 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::fmt::Write;
+use std::str;
+
+use bytes::Bytes;
+use ring::rand::{SecureRandom, SystemRandom};
+
+use ion::{Error, ErrorKind, Result};
+
+use crate::globals::file::{Blob, BlobPart, File};
+use crate::globals::form_data::FormDataEntryValue;
+
+/// Generates a random boundary from 128 bits of cryptographically secure random data.
+fn generate_boundary() -> Result<String> {
+	let mut bytes = [0; 16];
+	SystemRandom::new()
+		.fill(&mut bytes)
+		.map_err(|_| Error::new("Failed to generate multipart/form-data boundary", None))?;
+
+	let mut boundary = String::from("----FormBoundary");
+	for byte in bytes {
+		write!(boundary, "{:02x}", byte).unwrap();
+	}
+	Ok(boundary)
+}
+
+/// Checks if the boundary occurs within any of the entries.
+/// The contents of files which have not been read into memory are not checked, as they are read lazily.
+fn occurs_in(boundary: &str, entries: &[(String, FormDataEntryValue)]) -> bool {
+	entries.iter().any(|(name, value)| {
+		name.contains(boundary)
+			|| match value {
+				FormDataEntryValue::String(string) => string.contains(boundary),
+				FormDataEntryValue::File(file) => {
+					file.name.contains(boundary)
+						|| file.blob.parts.iter().any(|part| match part {
+							BlobPart::Bytes(bytes) => find(bytes, boundary.as_bytes(), 0).is_some(),
+							BlobPart::File(_) => false,
+						})
+				}
+			}
+	})
+}
+
+/// Escapes a name or filename, as specified by the [HTML Standard](https://html.spec.whatwg.org/multipage/form-control-infrastructure.html#multipart-form-data).
+fn escape(name: &str) -> String {
+	name.replace('\n', "%0A").replace('\r', "%0D").replace('"', "%22")
+}
+
+fn unescape(name: &str) -> String {
+	name.replace("%0A", "\n").replace("%0D", "\r").replace("%22", "\"")
+}
+
+/// Converts lone carriage returns and line feeds to CRLF pairs.
+fn normalise_newlines(string: &str) -> String {
+	string.replace("\r\n", "\n").replace('\r', "\n").replace('\n', "\r\n")
+}
+
+/// Encodes entries as `multipart/form-data`.
+/// The parts of the returned [Blob] include the parts of files, so that they are read lazily.
+pub(crate) fn encode(entries: &[(String, FormDataEntryValue)]) -> Result<Blob> {
+	let mut boundary = generate_boundary()?;
+	while occurs_in(&boundary, entries) {
+		boundary = generate_boundary()?;
+	}
+	let mut parts = Vec::with_capacity(entries.len() + 1);
+
+	for (name, value) in entries {
+		let name = escape(&normalise_newlines(name));
+		match value {
+			FormDataEntryValue::String(string) => {
+				let part = format!(
+					"--{}\r\nContent-Disposition: form-data; name=\"{}\"\r\n\r\n{}\r\n",
+					boundary,
+					name,
+					normalise_newlines(string)
+				);
+				parts.push(BlobPart::Bytes(Bytes::from(part)));
+			}
+			FormDataEntryValue::File(file) => {
+				let kind = if file.blob.kind.is_empty() {
+					"application/octet-stream"
+				} else {
+					&file.blob.kind
+				};
+				let header = format!(
+					"--{}\r\nContent-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\nContent-Type: {}\r\n\r\n",
+					boundary,
+					name,
+					escape(&file.name),
+					kind
+				);
+				parts.push(BlobPart::Bytes(Bytes::from(header)));
+				parts.extend(file.blob.parts.iter().cloned());
+				parts.push(BlobPart::Bytes(Bytes::from_static(b"\r\n")));
+			}
+		}
+	}
+	parts.push(BlobPart::Bytes(Bytes::from(format!("--{}--\r\n", boundary))));
+
+	Ok(Blob {
+		parts,
+		kind: format!("multipart/form-data; boundary={}", boundary),
+	})
+}
+
+/// Parses the parameters of a header value, such as `Content-Type` or `Content-Disposition`.
+/// Returns the value before the parameters, and the parameters with lowercase names.
+pub(crate) fn parse_parameters(value: &str) -> (&str, Vec<(String, String)>) {
+	let (essence, mut rest) = value.split_once(';').unwrap_or((value, ""));
+	let mut parameters = Vec::new();
+
+	while let Some((name, value)) = rest.split_once('=') {
+		let name = name.trim().to_ascii_lowercase();
+		let value = value.trim_start();
+		let (value, remaining) = if let Some(quoted) = value.strip_prefix('"') {
+			let end = quoted.find('"').unwrap_or(quoted.len());
+			let remaining = &quoted[end..];
+			(&quoted[..end], remaining.split_once(';').map(|(_, remaining)| remaining).unwrap_or(""))
+		} else {
+			let (value, remaining) = value.split_once(';').unwrap_or((value, ""));
+			(value.trim_end(), remaining)
+		};
+
+		parameters.push((name, String::from(value)));
+		rest = remaining;
+	}
+
+	(essence.trim(), parameters)
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+	haystack
+		.get(from..)?
+		.windows(needle.len())
+		.position(|window| window == needle)
+		.map(|position| position + from)
+}
+
+fn parse_part(part: &[u8]) -> Result<(String, FormDataEntryValue)> {
+	let separator = find(part, b"\r\n\r\n", 0).ok_or_else(|| Error::new("Invalid multipart/form-data part", ErrorKind::Type))?;
+	let head = str::from_utf8(&part[..separator]).map_err(|_| Error::new("Invalid multipart/form-data headers", ErrorKind::Type))?;
+	let content = &part[separator + 4..];
+
+	let mut name = None;
+	let mut filename = None;
+	let mut kind = None;
+	for line in head.split("\r\n") {
+		let (header, value) = line
+			.split_once(':')
+			.ok_or_else(|| Error::new("Invalid multipart/form-data header", ErrorKind::Type))?;
+		let header = header.trim();
+		if header.eq_ignore_ascii_case("content-disposition") {
+			let (disposition, parameters) = parse_parameters(value);
+			if !disposition.eq_ignore_ascii_case("form-data") {
+				return Err(Error::new("Expected form-data Content-Disposition", ErrorKind::Type));
+			}
+			for (parameter, value) in parameters {
+				match parameter.as_str() {
+					"name" => name = Some(unescape(&value)),
+					"filename" => filename = Some(unescape(&value)),
+					_ => {}
+				}
+			}
+		} else if header.eq_ignore_ascii_case("content-type") {
+			kind = Some(value.trim());
+		}
+	}
+
+	let name = name.ok_or_else(|| Error::new("Missing name in multipart/form-data part", ErrorKind::Type))?;
+	let value = match filename {
+		Some(filename) => {
+			let blob = Blob::new(vec![BlobPart::Bytes(Bytes::copy_from_slice(content))], kind.unwrap_or("text/plain"));
+			FormDataEntryValue::File(File::new(blob, filename))
+		}
+		None => FormDataEntryValue::String(String::from_utf8_lossy(content).into_owned()),
+	};
+	Ok((name, value))
+}
+
+/// Parses a `multipart/form-data` body with the given boundary.
+pub(crate) fn decode(body: &[u8], boundary: &str) -> Result<Vec<(String, FormDataEntryValue)>> {
+	let delimiter = format!("--{}", boundary);
+	let separator = format!("\r\n--{}", boundary);
+	let error = || Error::new("Invalid multipart/form-data body", ErrorKind::Type);
+
+	let mut entries = Vec::new();
+	let mut position = find(body, delimiter.as_bytes(), 0).ok_or_else(error)? + delimiter.len();
+	loop {
+		let rest = &body[position..];
+		if rest.starts_with(b"--") {
+			break;
+		} else if !rest.starts_with(b"\r\n") {
+			return Err(error());
+		}
+		position += 2;
+
+		let end = find(body, separator.as_bytes(), position).ok_or_else(error)?;
+		entries.push(parse_part(&body[position..end])?);
+		position = end + separator.len();
+	}
+	Ok(entries)
+}
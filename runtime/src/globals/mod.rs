@@ -7,16 +7,29 @@
 use ion::{ClassDefinition, Context, Iterator, Object};
 
 pub mod abort;
+pub mod clone;
 pub mod console;
+pub mod crypto;
 pub mod encoding;
+pub mod events;
 #[cfg(feature = "fetch")]
 pub mod fetch;
+pub mod file;
+pub mod form_data;
 pub mod microtasks;
 pub mod timers;
 pub mod url;
 
 pub fn init_globals<'cx: 'o, 'o>(cx: &'cx Context, global: &mut Object<'o>) -> bool {
-	let result = console::define(cx, global) && encoding::define(cx, global) && url::define(cx, global) && Iterator::init_class(cx, global).0;
+	let result = console::define(cx, global)
+		&& clone::define(cx, global)
+		&& crypto::define(cx, global)
+		&& encoding::define(cx, global)
+		&& events::define(cx, global)
+		&& file::define(cx, global)
+		&& form_data::define(cx, global)
+		&& url::define(cx, global)
+		&& Iterator::init_class(cx, global).0;
 	#[cfg(feature = "fetch")]
 	{
 		result && fetch::define(cx, global)
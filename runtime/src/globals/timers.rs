@@ -4,6 +4,9 @@
  * file, You can obtain one at http://mozilla.org/MPL/2.0/.
  */
 
+use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+
 use chrono::Duration;
 use mozjs::conversions::ConversionBehavior::{Clamp, EnforceRange};
 use mozjs::jsapi::JSFunctionSpec;
@@ -12,7 +15,7 @@ use mozjs::jsval::JSVal;
 use ion::{Context, Error, Function, Object, Result};
 
 use crate::event_loop::EVENT_LOOP;
-use crate::event_loop::macrotasks::{Macrotask, TimerMacrotask, UserMacrotask};
+use crate::event_loop::macrotasks::{Macrotask, SignalMacrotask, TimerMacrotask, UserMacrotask};
 
 const MINIMUM_DELAY: i32 = 1;
 const MINIMUM_DELAY_NESTED: i32 = 4;
@@ -46,6 +49,19 @@ fn clear_timer(id: Option<u32>) -> Result<()> {
 	}
 }
 
+/// Schedules a native callback on the macrotask queue, to be run once `time` milliseconds have elapsed.
+/// The callback is dropped without being run if `terminate` is set beforehand.
+pub fn schedule_signal(callback: Box<dyn FnOnce()>, terminate: Arc<AtomicBool>, time: u64) -> Result<u32> {
+	EVENT_LOOP.with(|event_loop| {
+		if let Some(queue) = event_loop.borrow_mut().macrotasks.as_mut() {
+			let duration = Duration::milliseconds(time as i64);
+			Ok(queue.enqueue(Macrotask::Signal(SignalMacrotask::new(callback, terminate, duration)), None))
+		} else {
+			Err(Error::new("Macrotask Queue has not been initialised.", None))
+		}
+	})
+}
+
 #[js_fn]
 fn setTimeout(callback: Function, #[ion(convert = Clamp)] duration: Option<i32>, #[ion(varargs)] arguments: Vec<JSVal>) -> Result<u32> {
 	set_timer(callback, duration, arguments, false)
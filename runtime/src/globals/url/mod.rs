@@ -5,6 +5,7 @@
  */
 pub use class::Url;
 use ion::{ClassDefinition, Context, Object};
+use ion::clone::register_structured_clone;
 pub use search_params::UrlSearchParams;
 
 mod search_params;
@@ -22,12 +23,14 @@ pub struct FormatOptions {
 #[js_class]
 mod class {
 	use std::cmp::Ordering;
+	use std::ptr;
 
 	use mozjs::conversions::ConversionBehavior::EnforceRange;
 	use mozjs::gc::Traceable;
 	use mozjs::jsapi::{Heap, JSObject, JSTracer};
 
-	use ion::{ClassDefinition, Context, Error, Object, Result};
+	use ion::{ClassDefinition, Context, Error, ErrorKind, Object, Result};
+	use ion::clone::{StructuredClone, StructuredCloneReader, StructuredCloneWriter};
 
 	use super::FormatOptions;
 	use super::search_params::UrlSearchParams;
@@ -230,6 +233,27 @@ mod class {
 		}
 	}
 
+	impl StructuredClone for Url {
+		fn write(&self, _: &Context, writer: &mut StructuredCloneWriter) -> Result<()> {
+			writer.write_string(self.url.as_str())
+		}
+
+		fn read(_: &Context, reader: &mut StructuredCloneReader) -> Result<Url> {
+			let url = url::Url::parse(&reader.read_string()?).map_err(|error| Error::new(&error.to_string(), ErrorKind::Type))?;
+			Ok(Url {
+				url,
+				search_params: Heap::boxed(ptr::null_mut()),
+			})
+		}
+
+		fn initialise(cx: &Context, object: &Object) -> Result<()> {
+			let url = Url::get_private(object);
+			let search_params = UrlSearchParams::new(cx, url.url.query_pairs().into_owned().collect(), object)?;
+			url.search_params = Heap::boxed(UrlSearchParams::new_object(cx, search_params));
+			Ok(())
+		}
+	}
+
 	unsafe impl Traceable for Url {
 		#[inline]
 		unsafe fn trace(&self, trc: *mut JSTracer) {
@@ -241,5 +265,6 @@ mod class {
 }
 
 pub fn define(cx: &Context, global: &mut Object) -> bool {
+	register_structured_clone::<Url>();
 	Url::init_class(cx, global).0 && UrlSearchParams::init_class(cx, global).0
 }
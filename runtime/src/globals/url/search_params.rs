@@ -5,6 +5,56 @@
  */
 
 pub use class::UrlSearchParams;
+use ion::{ClassDefinition, Context, Error, ErrorKind, OwnedKey, Result, Value};
+use ion::conversions::FromValue;
+
+pub struct UrlSearchParamsInit(Vec<(String, String)>);
+
+impl<'cx> FromValue<'cx> for UrlSearchParamsInit {
+	type Config = ();
+
+	fn from_value<'v>(cx: &'cx Context, value: &Value<'v>, _: bool, _: ()) -> Result<UrlSearchParamsInit>
+	where
+		'cx: 'v,
+	{
+		if !value.handle().is_object() {
+			let string = String::from_value(cx, value, false, ())?;
+			let string = string.strip_prefix('?').unwrap_or(&string);
+			return Ok(UrlSearchParamsInit(form_urlencoded::parse(string.as_bytes()).into_owned().collect()));
+		}
+
+		let object = value.to_object(cx);
+		if UrlSearchParams::instance_of(cx, &object, None) {
+			return Ok(UrlSearchParamsInit(UrlSearchParams::get_private(&object).pairs.clone()));
+		}
+
+		if let Ok(pairs) = Vec::<Vec<String>>::from_value(cx, value, false, ()) {
+			pairs
+				.into_iter()
+				.map(|pair| match <[String; 2]>::try_from(pair) {
+					Ok([name, value]) => Ok((name, value)),
+					Err(pair) => Err(Error::new(
+						&format!("Received Pair with Length {}, Expected Length 2", pair.len()),
+						ErrorKind::Type,
+					)),
+				})
+				.collect::<Result<_>>()
+				.map(UrlSearchParamsInit)
+		} else {
+			let mut pairs = Vec::new();
+			for key in object.keys(cx, None).map(|key| key.to_owned_key(cx)) {
+				let key = match key {
+					OwnedKey::Int(i) => i.to_string(),
+					OwnedKey::String(s) => s,
+					_ => continue,
+				};
+				let value = object.get_as::<_, String>(cx, &key, false, ()).unwrap_or_default();
+				pairs.push((key, value));
+			}
+			Ok(UrlSearchParamsInit(pairs))
+		}
+	}
+}
 
 #[js_class]
 mod class {
@@ -16,16 +66,24 @@ mod class {
 	use ion::conversions::ToValue;
 	use ion::symbol::WellKnownSymbolCode;
 
+	use crate::globals::url::search_params::UrlSearchParamsInit;
 	use crate::globals::url::Url;
 
-	#[ion(name = "URLSearchParams", no_constructor, into_value)]
+	#[ion(name = "URLSearchParams", into_value)]
 	pub struct UrlSearchParams {
-		pairs: Vec<(String, String)>,
+		pub(crate) pairs: Vec<(String, String)>,
 		url: Option<Box<Heap<*mut JSObject>>>,
 	}
 
-	// TODO: Implement Constructor for URLSearchParams
 	impl UrlSearchParams {
+		#[ion(constructor)]
+		pub fn constructor(init: Option<UrlSearchParamsInit>) -> UrlSearchParams {
+			UrlSearchParams {
+				pairs: init.map(|init| init.0).unwrap_or_default(),
+				url: None,
+			}
+		}
+
 		pub(crate) fn new(cx: &Context, pairs: Vec<(String, String)>, url_object: &Object) -> Result<UrlSearchParams> {
 			if !Url::instance_of(cx, url_object, None) {
 				return Err(Error::new("Expected URL", ErrorKind::Type));
@@ -8,16 +8,15 @@ use mozjs::jsapi::JSFunctionSpec;
 
 use ion::{Context, ErrorReport, Exception, Function, Promise, Value};
 
-use crate::cache::map::transform_error_report_with_sourcemaps;
+use crate::event_loop::report_error;
 
 #[js_fn]
 fn on_rejected<'cx>(cx: &'cx Context, value: Value<'cx>) {
 	let exception = Exception::from_value(cx, &value);
-	let mut report = ErrorReport::from_exception_with_error_stack(cx, exception);
-	transform_error_report_with_sourcemaps(&mut report);
+	let report = ErrorReport::from_exception_with_error_stack(cx, exception);
 
 	Exception::clear(cx);
-	println!("{}", report.format(cx));
+	report_error(cx, report);
 }
 
 static ON_REJECTED: JSFunctionSpec = function_spec!(on_rejected, "onRejected", 0);
@@ -0,0 +1,15 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+const VALUES: (&str, &str) = ("values", include_str!("scripts/clone/values.js"));
+const HOST: (&str, &str) = ("host", include_str!("scripts/clone/host.js"));
+
+#[tokio::test]
+async fn clone() {
+	common::run_scripts("clone", &[VALUES, HOST]).await;
+}
@@ -0,0 +1,71 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use std::path::Path;
+
+use mozjs::jsapi::PromiseState;
+use mozjs::rust::JSEngine;
+use mozjs::rust::Runtime as RustRuntime;
+use tokio::task::LocalSet;
+
+use ion::{Context, Exception};
+use ion::module::Module;
+use runtime::{Runtime, RuntimeBuilder};
+use runtime::config::{Config, CONFIG, LogLevel};
+use runtime::modules::Loader;
+
+/// Evaluates each script as a module in `tests/scripts/{directory}`, and checks that it completes without throwing.
+/// Scripts import their assertions from `tests/scripts/assert.js`.
+pub async fn run_scripts(directory: &str, scripts: &[(&str, &str)]) {
+	run_scripts_with_config(Config::default(), directory, scripts).await;
+}
+
+/// Evaluates scripts like [run_scripts], with the given runtime configuration.
+pub async fn run_scripts_with_config(config: Config, directory: &str, scripts: &[(&str, &str)]) {
+	CONFIG.set(config.log_level(LogLevel::Debug)).unwrap();
+
+	let engine = JSEngine::init().unwrap();
+	let rt = RustRuntime::new(engine.handle());
+
+	let cx = &Context::from_runtime(&rt);
+	let rt = RuntimeBuilder::<_, ()>::new()
+		.modules(Loader::default())
+		.microtask_queue()
+		.macrotask_queue()
+		.build(cx);
+
+	LocalSet::new()
+		.run_until(async {
+			for script in scripts {
+				eval_module(&rt, cx, directory, *script).await;
+			}
+		})
+		.await;
+}
+
+async fn eval_module(rt: &Runtime<'_, '_>, cx: &Context<'_>, directory: &str, test: (&str, &str)) {
+	let (test, script) = test;
+	let filename = format!("{}.js", test);
+	let path = format!("./tests/scripts/{}/{}.js", directory, test);
+
+	let result = Module::compile(cx, &filename, Some(Path::new(&path)), script);
+	assert!(result.is_ok(), "Exception was thrown in: {}", filename);
+
+	let (_, promise) = result.unwrap();
+	let promise = promise.unwrap();
+
+	let result = rt.run_event_loop().await;
+	assert!(result.is_ok(), "{}: {:#?}", filename, result.unwrap_err());
+
+	match promise.state() {
+		PromiseState::Fulfilled => {}
+		PromiseState::Rejected => {
+			let exception = Exception::from_value(cx, &promise.result(cx));
+			panic!("{}: {:#?}", filename, exception);
+		}
+		PromiseState::Pending => panic!("{}: Module did not complete", filename),
+	}
+}
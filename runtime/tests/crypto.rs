@@ -0,0 +1,19 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+const RANDOM: (&str, &str) = ("random", include_str!("scripts/crypto/random.js"));
+const DIGEST: (&str, &str) = ("digest", include_str!("scripts/crypto/digest.js"));
+const SIGN: (&str, &str) = ("sign", include_str!("scripts/crypto/sign.js"));
+const CIPHER: (&str, &str) = ("cipher", include_str!("scripts/crypto/cipher.js"));
+const DERIVE: (&str, &str) = ("derive", include_str!("scripts/crypto/derive.js"));
+const RSA: (&str, &str) = ("rsa", include_str!("scripts/crypto/rsa.js"));
+
+#[tokio::test]
+async fn crypto() {
+	common::run_scripts("crypto", &[RANDOM, DIGEST, SIGN, CIPHER, DERIVE, RSA]).await;
+}
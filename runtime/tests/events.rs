@@ -0,0 +1,15 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+const TARGET: (&str, &str) = ("target", include_str!("scripts/events/target.js"));
+const EVENTS: (&str, &str) = ("events", include_str!("scripts/events/events.js"));
+
+#[tokio::test]
+async fn events() {
+	common::run_scripts("events", &[TARGET, EVENTS]).await;
+}
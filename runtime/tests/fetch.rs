@@ -0,0 +1,21 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+use runtime::config::Config;
+
+mod common;
+
+const MULTIPART: (&str, &str) = ("multipart", include_str!("scripts/fetch/multipart.js"));
+const INTEGRITY: (&str, &str) = ("integrity", include_str!("scripts/fetch/integrity.js"));
+const SCHEMES: (&str, &str) = ("schemes", include_str!("scripts/fetch/schemes.js"));
+const REQUEST: (&str, &str) = ("request", include_str!("scripts/fetch/request.js"));
+const HEADERS: (&str, &str) = ("headers", include_str!("scripts/fetch/headers.js"));
+
+#[tokio::test]
+async fn fetch() {
+	let config = Config::default().file_urls(true);
+	common::run_scripts_with_config(config, "fetch", &[MULTIPART, INTEGRITY, SCHEMES, REQUEST, HEADERS]).await;
+}
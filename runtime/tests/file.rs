@@ -0,0 +1,15 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+const BLOB: (&str, &str) = ("blob", include_str!("scripts/file/blob.js"));
+const FILE: (&str, &str) = ("file", include_str!("scripts/file/file.js"));
+
+#[tokio::test]
+async fn file() {
+	common::run_scripts("file", &[BLOB, FILE]).await;
+}
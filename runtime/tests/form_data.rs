@@ -0,0 +1,14 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+mod common;
+
+const FORM_DATA: (&str, &str) = ("form-data", include_str!("scripts/form_data/form-data.js"));
+
+#[tokio::test]
+async fn form_data() {
+	common::run_scripts("form_data", &[FORM_DATA]).await;
+}
@@ -0,0 +1,57 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "../assert.js";
+
+const aborted = AbortSignal.abort("aborted");
+const pending = new AbortController();
+const immediate = AbortSignal.any([pending.signal, aborted]);
+ok(immediate.aborted, "AbortSignal.any with an aborted signal");
+equals(immediate.reason, "aborted", "AbortSignal.any reason with an aborted signal");
+
+const first = new AbortController();
+const second = new AbortController();
+const combined = AbortSignal.any([first.signal, second.signal]);
+ok(!combined.aborted, "AbortSignal.any before its signals are aborted");
+
+const reason = new Error("Second");
+const event = new Promise(resolve => combined.addEventListener("abort", resolve, {once: true}));
+second.abort(reason);
+await event;
+ok(combined.aborted, "AbortSignal.any after a signal is aborted");
+equals(combined.reason, reason, "AbortSignal.any reason after a signal is aborted");
+
+first.abort("First");
+equals(combined.reason, reason, "AbortSignal.any reason after another signal is aborted");
+
+const timeout = AbortSignal.any([AbortSignal.timeout(10), pending.signal]);
+await new Promise(resolve => timeout.onabort = resolve);
+ok(timeout.aborted, "AbortSignal.any with a timeout");
+ok(timeout.reason instanceof Error, "AbortSignal.any reason with a timeout");
+
+const outer = new AbortController();
+const inner = AbortSignal.any([outer.signal]);
+const nested = AbortSignal.any([inner, new AbortController().signal]);
+const events = [];
+inner.onabort = () => events.push(`inner:${nested.aborted}`);
+nested.onabort = () => events.push("nested");
+outer.abort("outer");
+equals(events.join(), "inner:true,nested", "AbortSignal.any aborts dependent signals synchronously");
+equals(nested.reason, "outer", "AbortSignal.any of a dependent signal uses its sources");
+
+let error = null;
+try {
+	AbortSignal.any([{}]);
+} catch (e) {
+	error = e;
+}
+ok(error instanceof TypeError, "AbortSignal.any with an object which is not a signal");
+
+equals(AbortSignal.any([]).aborted, false, "AbortSignal.any without signals");
+
+// Signals which are never aborted do not keep the script running.
+const lingering = AbortSignal.any([pending.signal, new AbortController().signal]);
+ok(!lingering.aborted, "AbortSignal.any with signals which are never aborted");
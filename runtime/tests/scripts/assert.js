@@ -0,0 +1,34 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+export function ok(assertion, message) {
+	if (!assertion) {
+		throw new Error(`Assertion Failed: ${message}`);
+	}
+}
+
+export function equals(actual, expected, message) {
+	if (actual !== expected) {
+		throw new Error(`Assertion Failed: ${message}: expected ${expected}, received ${actual}`);
+	}
+}
+
+export async function throws(callback, message) {
+	try {
+		await callback();
+	} catch {
+		return;
+	}
+	throw new Error(`Assertion Failed: ${message}: expected an exception`);
+}
+
+export function bytes(actual, expected, message) {
+	const array = new Uint8Array(actual);
+	equals(array.length, expected.length, `${message}: length`);
+	for (let i = 0; i < array.length; i++) {
+		equals(array[i], expected[i], `${message}: byte ${i}`);
+	}
+}
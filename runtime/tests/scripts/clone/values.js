@@ -0,0 +1,46 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {bytes, equals, ok, throws} from "../assert.js";
+
+const object = {
+	number: 1,
+	string: "string",
+	array: [1, 2, 3],
+	date: new Date(0),
+	regexp: /pattern/gi,
+	map: new Map([["key", "value"]]),
+	set: new Set([1, 2]),
+	bigint: 10n,
+};
+object.self = object;
+
+const clone = structuredClone(object);
+ok(clone !== object, "structuredClone creates a new object");
+equals(clone.number, 1, "Cloned number");
+equals(clone.string, "string", "Cloned string");
+equals(clone.array.join(), "1,2,3", "Cloned array");
+equals(clone.date.getTime(), 0, "Cloned date");
+equals(clone.regexp.source, "pattern", "Cloned regular expression source");
+equals(clone.regexp.flags, "gi", "Cloned regular expression flags");
+equals(clone.map.get("key"), "value", "Cloned map");
+ok(clone.set.has(2), "Cloned set");
+equals(clone.bigint, 10n, "Cloned bigint");
+equals(clone.self, clone, "Cloned cycle");
+
+const array = new Uint8Array([1, 2, 3]);
+const copied = structuredClone(array);
+array[0] = 0;
+bytes(copied, [1, 2, 3], "Cloned typed array");
+
+const buffer = new Uint8Array([4, 5, 6]).buffer;
+const transferred = structuredClone(buffer, {transfer: [buffer]});
+equals(buffer.byteLength, 0, "Transferred buffer is detached");
+bytes(transferred, [4, 5, 6], "Transferred buffer");
+
+await throws(() => structuredClone(() => {}), "structuredClone of a function");
+await throws(() => structuredClone(Symbol("symbol")), "structuredClone of a symbol");
+await throws(() => structuredClone(new EventTarget()), "structuredClone of a native object which is not serializable");
@@ -0,0 +1,107 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "../assert.js";
+
+const subtle = crypto.subtle;
+
+function hex(buffer) {
+	return Array.from(new Uint8Array(buffer), byte => byte.toString(16).padStart(2, "0")).join("");
+}
+
+function fromHex(string) {
+	return new Uint8Array(string.match(/../g).map(byte => parseInt(byte, 16)));
+}
+
+async function rejection(promise, message) {
+	let error = null;
+	try {
+		await promise;
+	} catch (e) {
+		error = e;
+	}
+	ok(error !== null, `${message}: expected a rejection`);
+	return error;
+}
+
+// AES-GCM test cases 2 and 4 from "The Galois/Counter Mode of Operation (GCM)".
+let key = await subtle.importKey("raw", new Uint8Array(16), "AES-GCM", false, ["encrypt"]);
+let ciphertext = await subtle.encrypt({name: "AES-GCM", iv: new Uint8Array(12)}, key, new Uint8Array(16));
+equals(hex(ciphertext), "0388dace60b6a392f328c2b971b2fe78ab6e47d42cec13bdf53a67b21257bddf", "AES-GCM ciphertext is followed by the tag");
+
+key = await subtle.importKey("raw", fromHex("feffe9928665731c6d6a8f9467308308"), {name: "aes-gcm"}, true, ["encrypt", "decrypt"]);
+equals(key.algorithm.name, "AES-GCM", "Algorithm of an AES key");
+equals(key.algorithm.length, 128, "Length of an AES key");
+const gcm = {
+	name: "AES-GCM",
+	iv: fromHex("cafebabefacedbaddecaf888"),
+	additionalData: fromHex("feedfacedeadbeeffeedfacedeadbeefabaddad2"),
+	tagLength: 128,
+};
+const plaintext = fromHex(
+	"d9313225f88406e5a55909c5aff5269a86a7a9531534f7da2e4c303d8a318a72" +
+	"1c3c0c95956809532fcf0e2449a6b525b16aedf5aa0de657ba637b39",
+);
+ciphertext = await subtle.encrypt(gcm, key, plaintext);
+equals(
+	hex(ciphertext),
+	"42831ec2217774244b7221b784d0d49ce3aa212f2c02a4e035c17e2329aca12e21d514b25466931c7d8f6a5aac84aa051ba30b396a0aac973d58e091" +
+	"5bc94fbc3221a5db94fae95ae7121a47",
+	"AES-GCM with additional data",
+);
+equals(hex(await subtle.decrypt(gcm, key, ciphertext)), hex(plaintext), "AES-GCM decrypts");
+
+const tampered = new Uint8Array(ciphertext);
+tampered[0] ^= 1;
+let error = await rejection(subtle.decrypt(gcm, key, tampered), "Decrypting a tampered ciphertext");
+equals(error.message, "Failed to decrypt data", "Tampered AES-GCM ciphertexts are rejected");
+await rejection(subtle.decrypt({...gcm, additionalData: new Uint8Array(4)}, key, ciphertext), "Decrypting with other additional data");
+await rejection(subtle.decrypt({name: "AES-GCM", iv: gcm.iv}, key, ciphertext), "Decrypting without the additional data");
+error = await rejection(subtle.encrypt({...gcm, tagLength: 96}, key, plaintext), "Encrypting with a shorter tag");
+equals(error.message, "AES-GCM only supports tags of 128 bits", "Only 128-bit tags are supported");
+error = await rejection(subtle.encrypt({...gcm, iv: new Uint8Array(16)}, key, plaintext), "Encrypting with a 128-bit IV");
+equals(error.message, "AES-GCM only supports IVs of 96 bits", "Only 96-bit IVs are supported");
+
+const jwk = await subtle.exportKey("jwk", key);
+equals(jwk.kty, "oct", "AES JSON Web Key type");
+equals(jwk.alg, "A128GCM", "AES-GCM JSON Web Key algorithm");
+key = await subtle.importKey("jwk", jwk, "AES-GCM", false, ["decrypt"]);
+equals(hex(await subtle.decrypt(gcm, key, ciphertext)), hex(plaintext), "JSON Web Key export round-trips");
+await rejection(subtle.importKey("jwk", jwk, "AES-CBC", false, ["decrypt"]), "Importing an AES-GCM JSON Web Key for AES-CBC");
+error = await rejection(subtle.encrypt(gcm, key, plaintext), "Encrypting with a key without the usage");
+equals(error.message, "Key does not support the encrypt operation", "Keys are checked for the usage");
+
+error = await rejection(subtle.importKey("raw", new Uint8Array(20), "AES-GCM", false, ["encrypt"]), "Importing a 160-bit AES key");
+equals(error.message, "AES keys must be 128, 192 or 256 bits", "AES keys have a fixed length");
+error = await rejection(subtle.importKey("raw", new Uint8Array(16), "AES-GCM", false, ["sign"]), "Importing an AES key for signing");
+ok(error instanceof SyntaxError, "AES keys cannot be used to sign");
+
+for (const length of [128, 192, 256]) {
+	key = await subtle.generateKey({name: "AES-GCM", length}, true, ["encrypt", "decrypt"]);
+	equals((await subtle.exportKey("raw", key)).byteLength, length / 8, `Generated ${length}-bit AES key`);
+	const iv = crypto.getRandomValues(new Uint8Array(12));
+	ciphertext = await subtle.encrypt({name: "AES-GCM", iv}, key, plaintext);
+	equals(ciphertext.byteLength, plaintext.length + 16, `AES-GCM with a ${length}-bit key appends the tag`);
+	equals(hex(await subtle.decrypt({name: "AES-GCM", iv}, key, ciphertext)), hex(plaintext), `AES-GCM with a ${length}-bit key round-trips`);
+}
+await rejection(subtle.generateKey({name: "AES-GCM", length: 64}, true, ["encrypt"]), "Generating a 64-bit AES key");
+
+// AES-CBC test vector from NIST SP 800-38A, F.2.1, with PKCS #7 padding.
+key = await subtle.importKey("raw", fromHex("2b7e151628aed2a6abf7158809cf4f3c"), "AES-CBC", true, ["encrypt", "decrypt"]);
+const cbc = {name: "AES-CBC", iv: fromHex("000102030405060708090a0b0c0d0e0f")};
+const block = fromHex("6bc1bee22e409f96e93d7e117393172a");
+ciphertext = await subtle.encrypt(cbc, key, block);
+equals(hex(ciphertext), "7649abac8119b246cee98e9b12e9197d8964e0b149c10b7b682e6e39aaeb731c", "AES-CBC adds a block of padding");
+equals(hex(await subtle.decrypt(cbc, key, ciphertext)), hex(block), "AES-CBC decrypts");
+equals((await subtle.exportKey("jwk", key)).alg, "A128CBC", "AES-CBC JSON Web Key algorithm");
+
+ciphertext = await subtle.encrypt(cbc, key, new Uint8Array(5));
+equals(ciphertext.byteLength, 16, "AES-CBC pads to the block size");
+equals((await subtle.decrypt(cbc, key, ciphertext)).byteLength, 5, "AES-CBC removes the padding");
+await rejection(subtle.decrypt(cbc, key, ciphertext.slice(0, 15)), "Decrypting a partial block");
+await rejection(subtle.encrypt({name: "AES-CBC", iv: new Uint8Array(12)}, key, block), "Encrypting with a 96-bit IV");
+error = await rejection(subtle.encrypt(gcm, key, block), "Encrypting with a key for another algorithm");
+equals(error.message, "Key is for AES-CBC, not AES-GCM", "Keys are checked against the algorithm");
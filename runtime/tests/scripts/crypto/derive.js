@@ -0,0 +1,106 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "../assert.js";
+
+const encoder = new TextEncoder();
+const subtle = crypto.subtle;
+
+function hex(buffer) {
+	return Array.from(new Uint8Array(buffer), byte => byte.toString(16).padStart(2, "0")).join("");
+}
+
+function fromHex(string) {
+	return new Uint8Array(string.match(/../g).map(byte => parseInt(byte, 16)));
+}
+
+async function rejection(promise, message) {
+	let error = null;
+	try {
+		await promise;
+	} catch (e) {
+		error = e;
+	}
+	ok(error !== null, `${message}: expected a rejection`);
+	return error;
+}
+
+// PBKDF2 test vectors from RFC 6070, and for SHA-256 from RFC 7914.
+const password = await subtle.importKey("raw", encoder.encode("password"), "PBKDF2", false, ["deriveBits"]);
+equals(password.type, "secret", "PBKDF2 keys are secret");
+equals(password.algorithm.name, "PBKDF2", "Algorithm of a PBKDF2 key");
+const salt = encoder.encode("salt");
+
+const pbkdf2Vectors = [
+	["SHA-1", 1, 160, "0c60c80f961f0e71f3a9b524af6012062fe037a6"],
+	["SHA-1", 4096, 160, "4b007901b765489abead49d926f721d065a429c1"],
+	["SHA-256", 1, 256, "120fb6cffcf8b32c43e7225256c4f837a86548c92ccc35480805987cb70be17b"],
+];
+for (const [hash, iterations, length, expected] of pbkdf2Vectors) {
+	const bits = await subtle.deriveBits({name: "PBKDF2", hash, salt, iterations}, password, length);
+	ok(bits instanceof ArrayBuffer, "Derived bits are an ArrayBuffer");
+	equals(hex(bits), expected, `PBKDF2 with ${hash} and ${iterations} iterations`);
+}
+
+const pbkdf2 = {name: "pbkdf2", hash: {name: "SHA-1"}, salt, iterations: 1};
+equals(hex(await subtle.deriveBits(pbkdf2, password, 64)), "0c60c80f961f0e71", "PBKDF2 output is truncated to the length");
+let error = await rejection(subtle.deriveBits({...pbkdf2, iterations: 0}, password, 160), "Deriving with no iterations");
+equals(error.message, "Iterations must be greater than 0", "PBKDF2 requires iterations");
+error = await rejection(subtle.deriveBits(pbkdf2, password, 12), "Deriving a length which is not a multiple of 8");
+equals(error.message, "Length must be a non-zero multiple of 8", "PBKDF2 lengths are whole bytes");
+await rejection(subtle.deriveBits(pbkdf2, password, 0), "Deriving no bits");
+await rejection(subtle.deriveBits(pbkdf2, password), "Deriving without a length");
+
+error = await rejection(subtle.importKey("raw", encoder.encode("password"), "PBKDF2", true, ["deriveBits"]), "Importing an extractable PBKDF2 key");
+ok(error instanceof SyntaxError, "PBKDF2 keys cannot be extractable");
+await rejection(subtle.importKey("jwk", {kty: "oct", k: "cGFzc3dvcmQ"}, "PBKDF2", false, ["deriveBits"]), "Importing a PBKDF2 key as a JSON Web Key");
+await rejection(subtle.exportKey("raw", password), "Exporting a PBKDF2 key");
+
+// HKDF test vector from RFC 5869, A.1.
+const ikm = await subtle.importKey("raw", new Uint8Array(22).fill(0x0B), "HKDF", false, ["deriveBits"]);
+const hkdf = {
+	name: "HKDF",
+	hash: "SHA-256",
+	salt: fromHex("000102030405060708090a0b0c"),
+	info: fromHex("f0f1f2f3f4f5f6f7f8f9"),
+};
+equals(
+	hex(await subtle.deriveBits(hkdf, ikm, 336)),
+	"3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865",
+	"HKDF with SHA-256",
+);
+equals(hex(await subtle.deriveBits(hkdf, ikm, 128)), "3cb25f25faacd57a90434f64d0362f2a", "HKDF output is a prefix of longer outputs");
+ok(hex(await subtle.deriveBits({...hkdf, info: new Uint8Array()}, ikm, 128)) !== "3cb25f25faacd57a90434f64d0362f2a", "HKDF output depends on the info");
+error = await rejection(subtle.deriveBits(hkdf, ikm, 255 * 256 + 8), "Deriving more than 255 blocks");
+equals(error.message, "Length is too large for HKDF with this hash", "HKDF output is limited by the hash");
+error = await rejection(subtle.deriveBits(hkdf, password, 128), "Deriving HKDF bits with a PBKDF2 key");
+equals(error.message, "Key is for PBKDF2, not HKDF", "Keys are checked against the algorithm");
+
+// ECDH agreement between two generated key pairs.
+for (const [namedCurve, length] of [["P-256", 256], ["P-384", 384]]) {
+	const alice = await subtle.generateKey({name: "ECDH", namedCurve}, false, ["deriveBits"]);
+	const bob = await subtle.generateKey({name: "ECDH", namedCurve}, false, ["deriveBits"]);
+	equals(alice.publicKey.usages.length, 0, "ECDH public keys have no usages");
+
+	const shared = await subtle.deriveBits({name: "ECDH", public: bob.publicKey}, alice.privateKey, length);
+	equals(shared.byteLength, length / 8, `ECDH with ${namedCurve} derives the x-coordinate`);
+	equals(hex(await subtle.deriveBits({name: "ECDH", public: alice.publicKey}, bob.privateKey, length)), hex(shared), `ECDH with ${namedCurve} agrees`);
+	equals(hex(await subtle.deriveBits({name: "ECDH", public: alice.publicKey}, bob.privateKey)), hex(shared), "ECDH without a length derives the whole secret");
+
+	const truncated = new Uint8Array(await subtle.deriveBits({name: "ECDH", public: bob.publicKey}, alice.privateKey, 12));
+	equals(truncated.length, 2, "ECDH lengths are rounded up to whole bytes");
+	equals(truncated[1], new Uint8Array(shared)[1] & 0xF0, "Bits after the length are cleared");
+	await rejection(subtle.deriveBits({name: "ECDH", public: bob.publicKey}, alice.privateKey, length + 8), "Deriving more bits than the shared secret");
+	await rejection(subtle.deriveBits({name: "ECDH", public: alice.privateKey}, bob.privateKey, length), "Deriving with a private key as the public key");
+}
+
+const p256 = await subtle.generateKey({name: "ECDH", namedCurve: "P-256"}, false, ["deriveBits"]);
+const p384 = await subtle.generateKey({name: "ECDH", namedCurve: "P-384"}, false, ["deriveBits"]);
+error = await rejection(subtle.deriveBits({name: "ECDH", public: p384.publicKey}, p256.privateKey, 256), "Deriving with keys on different curves");
+equals(error.message, "Public key must use the same curve as the private key", "ECDH keys must use the same curve");
+const signing = await subtle.generateKey({name: "ECDSA", namedCurve: "P-256"}, false, ["sign", "verify"]);
+error = await rejection(subtle.deriveBits({name: "ECDH", public: signing.publicKey}, p256.privateKey, 256), "Deriving with an ECDSA public key");
+equals(error.message, "Expected public ECDH key", "ECDH requires an ECDH public key");
@@ -0,0 +1,58 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok, throws} from "../assert.js";
+
+function hex(buffer) {
+	return Array.from(new Uint8Array(buffer), byte => byte.toString(16).padStart(2, "0")).join("");
+}
+
+const abc = new TextEncoder().encode("abc");
+
+// Test vectors from FIPS 180-2 and FIPS 202.
+const vectors = {
+	"SHA-1": "a9993e364706816aba3e25717850c26c9cd0d89d",
+	"SHA-256": "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad",
+	"SHA-384": "cb00753f45a35e8bb5a03d699ac65007272c32ab0eded1631a8b605a43ff5bed8086072ba1e7cc2358baeca134c825a7",
+	"SHA-512": "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f",
+	"SHA3-256": "3a985da74fe225b2045c172d6bd390bd855f086e3e9d525b46bfe24511431532",
+	"SHA3-384": "ec01498288516fc926459f58e2c6ad8df9b473cb0fc08c2596da7cf0e49be4b298d88cea927ac7f539f1edf228376d25",
+	"SHA3-512": "b751850b1a57168a5693cd924b6b096e08f621827444f70d884f5d0240d2712e10e116e9192af3c91a7ec57647e3934057340b4cf408d5a56592f8274eec53f0",
+};
+
+for (const [algorithm, expected] of Object.entries(vectors)) {
+	const digest = await crypto.subtle.digest(algorithm, abc);
+	ok(digest instanceof ArrayBuffer, `${algorithm} digest is an ArrayBuffer`);
+	equals(hex(digest), expected, `${algorithm} digest of "abc"`);
+}
+
+const sha256 = vectors["SHA-256"];
+equals(hex(await crypto.subtle.digest({name: "SHA-256"}, abc)), sha256, "Algorithm can be an object");
+equals(hex(await crypto.subtle.digest("sha-256", abc)), sha256, "Algorithm names are case-insensitive");
+equals(hex(await crypto.subtle.digest("SHA-256", abc.buffer)), sha256, "Data can be an ArrayBuffer");
+equals(hex(await crypto.subtle.digest("SHA-256", new DataView(abc.buffer))), sha256, "Data can be a DataView");
+
+const padded = new Uint8Array([0, ...abc, 0]);
+equals(hex(await crypto.subtle.digest("SHA-256", padded.subarray(1, 4))), sha256, "Only the bytes of a view are digested");
+equals(
+	hex(await crypto.subtle.digest("SHA-256", new Uint8Array())),
+	"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+	"SHA-256 digest of empty data",
+);
+equals(
+	hex(await crypto.subtle.digest("SHA-256", new Uint8Array(1000000).fill(0x61))),
+	"cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0",
+	"SHA-256 digest of a million bytes",
+);
+
+const data = new Uint8Array(abc);
+const promise = crypto.subtle.digest("SHA-256", data);
+data.fill(0);
+equals(hex(await promise), sha256, "Data is copied when digest is called");
+
+await throws(() => crypto.subtle.digest("MD5", abc), "Unsupported algorithms reject");
+await throws(() => crypto.subtle.digest({}, abc), "Algorithms without a name reject");
+await throws(() => crypto.subtle.digest("SHA-256", "abc"), "Strings are not a BufferSource");
@@ -0,0 +1,51 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok, throws} from "../assert.js";
+
+for (const Type of [Int8Array, Uint8Array, Uint8ClampedArray, Int16Array, Uint16Array, Int32Array, Uint32Array, BigInt64Array, BigUint64Array]) {
+	const array = new Type(16);
+	equals(crypto.getRandomValues(array), array, `getRandomValues() returns the ${Type.name} it was given`);
+}
+
+const bytes = crypto.getRandomValues(new Uint8Array(1024));
+ok(bytes.some(byte => byte !== 0), "getRandomValues() fills the array");
+ok(new Set(bytes).size > 64, "getRandomValues() fills the array with varied values");
+
+const buffer = new ArrayBuffer(8);
+crypto.getRandomValues(new Uint8Array(buffer, 4));
+ok(new Uint8Array(buffer, 0, 4).every(byte => byte === 0), "getRandomValues() only fills the view");
+
+equals(crypto.getRandomValues(new Uint8Array(65536)).length, 65536, "getRandomValues() fills up to 65536 bytes");
+
+let error = null;
+try {
+	crypto.getRandomValues(new Uint8Array(65537));
+} catch (e) {
+	error = e;
+}
+ok(error instanceof RangeError, "getRandomValues() throws a RangeError for more than 65536 bytes");
+
+error = null;
+try {
+	crypto.getRandomValues(new Float64Array(4));
+} catch (e) {
+	error = e;
+}
+ok(error instanceof TypeError, "getRandomValues() throws a TypeError for floating point arrays");
+await throws(() => crypto.getRandomValues(new ArrayBuffer(4)), "getRandomValues() requires a typed array");
+
+const pattern = /^[0-9a-f]{8}-[0-9a-f]{4}-4[0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$/;
+const uuids = new Set();
+for (let i = 0; i < 100; i++) {
+	const uuid = crypto.randomUUID();
+	ok(pattern.test(uuid), `randomUUID() returns a version 4 UUID, received ${uuid}`);
+	uuids.add(uuid);
+}
+equals(uuids.size, 100, "randomUUID() returns unique UUIDs");
+
+ok(crypto.subtle instanceof SubtleCrypto, "crypto.subtle is a SubtleCrypto");
+equals(crypto.subtle, crypto.subtle, "crypto.subtle is the same object");
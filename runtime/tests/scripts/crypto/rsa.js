@@ -0,0 +1,115 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "../assert.js";
+
+const encoder = new TextEncoder();
+
+function hex(buffer) {
+	return Array.from(new Uint8Array(buffer), byte => byte.toString(16).padStart(2, "0")).join("");
+}
+
+function fromHex(string) {
+	return new Uint8Array(string.match(/../g).map(byte => parseInt(byte, 16)));
+}
+
+// Parameters are parsed before a promise is returned, so their errors are thrown by operations passed as functions.
+async function rejection(operation, message) {
+	let error = null;
+	try {
+		await (typeof operation === "function" ? operation() : operation);
+	} catch (e) {
+		error = e;
+	}
+	ok(error !== null, `${message}: expected a rejection`);
+	return error;
+}
+
+// 1024-bit key, with a signature and ciphertext created with another implementation.
+const pkcs8 = fromHex(
+	"30820277020100300d06092a864886f70d0101010500048202613082025d02010002818100b37b353f8b6ccfedd15f8ad0cdd6568101e1a230272254cb40ab19" +
+	"f2b5a81daba38605a76e78aa6af6aca91134b2f6fa5671667bad761fe0a905dd52b7281cf90554dce57eaf76e1ffb530f9e8346e9d79884f9a64cec93ca0a152" +
+	"73a88312a24ac227b0633c4ae356eb5151f35df55a4cb3cf5db9b8cff55721a88f05ba7b1b020301000102818100a2a59ea58d35f85fed83058b1f5d835ab957" +
+	"bde0a1362d5d5ba732868e34615eef7b3e9872360b498f6cfc71492e7d3e7323a4d9a3620e954ad6b3b087a6644989a331fba3c8de37c0e7dcb6adb53c74e0cc" +
+	"7f5f0a6ed302633d63447daf71965256fe25ed701b8c4ee44b94fc0150bf0943173763e828bfdf5ab2fe410cccd1024100e109a515c0ce904a009f174a2dc83a" +
+	"9eacf1ffd45927bf6a9c0ba46bd9a7f11f662850bd14f535b0952353a7b4289108df9b8fbf00f021ec4240bfb42fc6e8ef024100cc2cf5c33768023d10ecbf44" +
+	"6bca841339b90b78b98bff2b1251a1d97df4fd050b521a3513b717486295f3e2213fd6566f55ebc2fc45585122a065b557c99895024073963ea323bb75c56cf8" +
+	"4a4021afdcdc2fbcc548ceda69a95aa96834b5415bca62f08637f891d297f6a2848f792bf502f7c7b157cc258b670cc8b12eddf36f2502402ea34e780b1decf4" +
+	"f9faba2decd95395f8a776215b70c9169396a5fbc233c5dff3180bf877ca68bd1617de9f96acb84f021b844103d2eb3b8d474a34441b3e89024100bd84e62c9b" +
+	"6ae110187c63ab19239c4fc86304bfa04406e167ecaf6ecde01b8ae4bc885c3c8636dc2d3d841a31634b611536afd228564bc7298183e8db135bc7",
+);
+const spki = fromHex(
+	"30819f300d06092a864886f70d010101050003818d0030818902818100b37b353f8b6ccfedd15f8ad0cdd6568101e1a230272254cb40ab19f2b5a81daba38605" +
+	"a76e78aa6af6aca91134b2f6fa5671667bad761fe0a905dd52b7281cf90554dce57eaf76e1ffb530f9e8346e9d79884f9a64cec93ca0a15273a88312a24ac227" +
+	"b0633c4ae356eb5151f35df55a4cb3cf5db9b8cff55721a88f05ba7b1b0203010001",
+);
+const signature = fromHex(
+	"a1e0168f97fed4193c58299ca40f2dec37cdc9aab091a3667a07f737f4a073748ccbbf8f80cc29991bc6cbe5eee0195a39ff03deca84d67e947dfba9fc9588be" +
+	"b65017e04863b0db77c94f248b64034567c1b4d41f02189f50b018055922bf089469c6003bcc46517d90386c3b40debd7ef104c5b3c16b6f9b31fc30f0b1e000",
+);
+const ciphertext = fromHex(
+	"26fa92b4297f2956af7651fdb21f3501fd76f9560db927c8f55c9445a003a0b69cb6c6e611eae00a297cf168434426cab71103d78c9ad7e6ddd507776a53e694" +
+	"17c79960c9eaa6f3a90eb88960d2fa9e19f89a4d0aaa3f411bc82665e0a9ec6d553a9e852b2769952567f83e1dd9863fb94c8fe16a0cdd55dcc6e65ba896f663",
+);
+
+const subtle = crypto.subtle;
+const data = encoder.encode("abc");
+
+const signing = {name: "RSASSA-PKCS1-v1_5", hash: "SHA-256"};
+const privateKey = await subtle.importKey("pkcs8", pkcs8, signing, true, ["sign"]);
+const publicKey = await subtle.importKey("spki", spki, signing, true, ["verify"]);
+equals(privateKey.type, "private", "Imported PKCS #8 key is private");
+equals(publicKey.type, "public", "Imported SPKI key is public");
+equals(publicKey.algorithm.modulusLength, 1024, "Modulus length of an imported key");
+equals(hex(publicKey.algorithm.publicExponent), "010001", "Public exponent of an imported key");
+equals(publicKey.algorithm.hash.name, "SHA-256", "Hash of an imported key");
+
+equals(hex(await subtle.sign("RSASSA-PKCS1-v1_5", privateKey, data)), hex(signature), "RSASSA-PKCS1-v1_5 signatures are deterministic");
+equals(await subtle.verify("RSASSA-PKCS1-v1_5", publicKey, signature, data), true, "RSASSA-PKCS1-v1_5 signature verifies");
+equals(await subtle.verify("RSASSA-PKCS1-v1_5", publicKey, signature, encoder.encode("abd")), false, "Signature of other data does not verify");
+
+const exported = await subtle.importKey("pkcs8", await subtle.exportKey("pkcs8", privateKey), signing, false, ["sign"]);
+equals(hex(await subtle.sign("RSASSA-PKCS1-v1_5", exported, data)), hex(signature), "PKCS #8 export round-trips");
+equals(hex(await subtle.exportKey("spki", publicKey)), hex(spki), "SPKI export round-trips");
+const jwk = await subtle.exportKey("jwk", privateKey);
+equals(jwk.kty, "RSA", "JSON Web Key type");
+equals(jwk.alg, "RS256", "JSON Web Key algorithm");
+const fromJwk = await subtle.importKey("jwk", jwk, signing, false, ["sign"]);
+equals(hex(await subtle.sign("RSASSA-PKCS1-v1_5", fromJwk, data)), hex(signature), "JSON Web Key export round-trips");
+await rejection(subtle.importKey("jwk", jwk, {name: "RSASSA-PKCS1-v1_5", hash: "SHA-384"}, false, ["sign"]), "Importing a JSON Web Key with another algorithm");
+
+const pss = {name: "RSA-PSS", hash: "SHA-256"};
+const pssPrivate = await subtle.importKey("pkcs8", pkcs8, pss, false, ["sign"]);
+const pssPublic = await subtle.importKey("spki", spki, pss, false, ["verify"]);
+const pssSignature = await subtle.sign({name: "RSA-PSS", saltLength: 32}, pssPrivate, data);
+equals(await subtle.verify({name: "RSA-PSS", saltLength: 32}, pssPublic, pssSignature, data), true, "RSA-PSS signature verifies");
+equals(await subtle.verify({name: "RSA-PSS", saltLength: 32}, pssPublic, pssSignature, encoder.encode("abd")), false, "RSA-PSS signature of other data does not verify");
+await rejection(subtle.verify("RSASSA-PKCS1-v1_5", pssPublic, signature, data), "Verifying with a key for another algorithm");
+
+const oaep = {name: "RSA-OAEP", hash: "SHA-256"};
+const decrypting = await subtle.importKey("pkcs8", pkcs8, oaep, false, ["decrypt"]);
+const encrypting = await subtle.importKey("spki", spki, oaep, false, ["encrypt"]);
+const label = encoder.encode("label \u2713");
+equals(new TextDecoder().decode(await subtle.decrypt({name: "RSA-OAEP", label}, decrypting, ciphertext)), "secret", "RSA-OAEP decrypts with a UTF-8 label");
+await rejection(subtle.decrypt({name: "RSA-OAEP"}, decrypting, ciphertext), "Decrypting without the label");
+
+const encrypted = await subtle.encrypt({name: "RSA-OAEP", label}, encrypting, data);
+equals(encrypted.byteLength, 128, "RSA-OAEP ciphertext has the length of the modulus");
+equals(hex(await subtle.decrypt({name: "RSA-OAEP", label}, decrypting, encrypted)), hex(data), "RSA-OAEP round-trips");
+await rejection(subtle.decrypt({name: "RSA-OAEP", label: encoder.encode("other")}, decrypting, encrypted), "Decrypting with another label");
+
+let invalid = await rejection(() => subtle.encrypt({name: "RSA-OAEP", label: new Uint8Array([0xFF, 0xFE])}, encrypting, data), "Encrypting with a non-UTF-8 label");
+ok(invalid instanceof Error, "Non-UTF-8 labels reject with an Error");
+equals(invalid.name, "NotSupportedError", "Non-UTF-8 labels are not supported");
+invalid = await rejection(() => subtle.decrypt({name: "RSA-OAEP", label: new Uint8Array([0xC3])}, decrypting, encrypted), "Decrypting with a non-UTF-8 label");
+equals(invalid.name, "NotSupportedError", "Non-UTF-8 labels are not supported for decryption");
+await rejection(subtle.decrypt({name: "RSA-OAEP", label}, encrypting, encrypted), "Decrypting with a public key");
+
+const generation = {name: "RSA-OAEP", publicExponent: new Uint8Array([1, 0, 1]), hash: "SHA-256"};
+for (const modulusLength of [512, 1023, 16385]) {
+	const error = await rejection(subtle.generateKey({...generation, modulusLength}, false, ["decrypt"]), `Generating a ${modulusLength}-bit key`);
+	ok(error instanceof RangeError, `Modulus length ${modulusLength} rejects with a RangeError`);
+}
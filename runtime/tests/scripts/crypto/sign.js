@@ -0,0 +1,189 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "../assert.js";
+
+const encoder = new TextEncoder();
+const subtle = crypto.subtle;
+
+function hex(buffer) {
+	return Array.from(new Uint8Array(buffer), byte => byte.toString(16).padStart(2, "0")).join("");
+}
+
+function fromHex(string) {
+	return new Uint8Array(string.match(/../g).map(byte => parseInt(byte, 16)));
+}
+
+async function rejection(promise, message) {
+	let error = null;
+	try {
+		await promise;
+	} catch (e) {
+		error = e;
+	}
+	ok(error !== null, `${message}: expected a rejection`);
+	return error;
+}
+
+// HMAC test vectors from RFC 4231.
+const hiThere = encoder.encode("Hi There");
+const hmacKey = new Uint8Array(20).fill(0x0B);
+const hmacVectors = {
+	"SHA-256": "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+	"SHA-384": "afd03944d84895626b0825f4ab46907f15f9dadbe4101ec682aa034c7cebc59cfaea9ea9076ede7f4af152e8b2fa9cb6",
+	"SHA-512": "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+};
+
+for (const [hash, expected] of Object.entries(hmacVectors)) {
+	const key = await subtle.importKey("raw", hmacKey, {name: "HMAC", hash}, false, ["sign", "verify"]);
+	const signature = await subtle.sign("HMAC", key, hiThere);
+	equals(hex(signature), expected, `HMAC with ${hash}`);
+	equals(await subtle.verify("HMAC", key, signature, hiThere), true, `HMAC with ${hash} verifies`);
+}
+
+let key = await subtle.importKey("raw", encoder.encode("Jefe"), {name: "hmac", hash: {name: "SHA-256"}}, true, ["sign", "verify"]);
+const jefe = encoder.encode("what do ya want for nothing?");
+const jefeSignature = fromHex("5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+equals(hex(await subtle.sign("HMAC", key, jefe)), hex(jefeSignature), "HMAC with a key shorter than the hash");
+equals(key.type, "secret", "HMAC keys are secret");
+equals(key.algorithm.name, "HMAC", "Algorithm names are normalised");
+equals(key.algorithm.hash.name, "SHA-256", "Hash of an HMAC key");
+equals(key.algorithm.length, 32, "Length of an HMAC key");
+equals(await subtle.verify("HMAC", key, jefeSignature, encoder.encode("what do ya want for something?")), false, "HMAC of other data does not verify");
+jefeSignature[0] ^= 1;
+equals(await subtle.verify("HMAC", key, jefeSignature, jefe), false, "Tampered HMAC does not verify");
+
+const jwk = await subtle.exportKey("jwk", key);
+equals(jwk.kty, "oct", "HMAC JSON Web Key type");
+equals(jwk.alg, "HS256", "HMAC JSON Web Key algorithm");
+equals(jwk.k, "SmVmZQ", "HMAC JSON Web Key is base64url without padding");
+equals(jwk.key_ops.join(), "sign,verify", "HMAC JSON Web Key operations");
+equals(jwk.ext, true, "HMAC JSON Web Key is extractable");
+equals(hex(await subtle.exportKey("raw", key)), hex(encoder.encode("Jefe")), "Raw export of an HMAC key");
+await rejection(subtle.importKey("jwk", jwk, {name: "HMAC", hash: "SHA-512"}, false, ["sign"]), "Importing a JSON Web Key with another hash");
+
+key = await subtle.generateKey({name: "HMAC", hash: "SHA-512"}, false, ["sign"]);
+equals(key.algorithm.length, 1024, "Generated HMAC keys default to the block size of the hash");
+equals(key.extractable, false, "Generated key is not extractable");
+equals(key.usages.join(), "sign", "Usages of a generated key");
+equals((await subtle.sign("HMAC", key, hiThere)).byteLength, 64, "HMAC signature has the length of the hash");
+let error = await rejection(subtle.verify("HMAC", key, new Uint8Array(64), hiThere), "Verifying with a key without the usage");
+ok(error instanceof Error, "Missing usages reject");
+error = await rejection(subtle.exportKey("raw", key), "Exporting a key which is not extractable");
+equals(error.message, "Key is not extractable", "Keys which are not extractable cannot be exported");
+error = await rejection(subtle.generateKey({name: "HMAC", hash: "SHA-256"}, false, ["encrypt"]), "Generating an HMAC key for encryption");
+ok(error instanceof SyntaxError, "Invalid usages reject with a SyntaxError");
+error = await rejection(subtle.generateKey({name: "HMAC", hash: "SHA-256"}, false, []), "Generating an HMAC key without usages");
+ok(error instanceof SyntaxError, "Secret keys without usages reject with a SyntaxError");
+
+// ECDSA test vector from RFC 6979, A.2.5.
+const ecdsa = {name: "ECDSA", namedCurve: "P-256"};
+const ecJwk = {
+	kty: "EC",
+	crv: "P-256",
+	d: "ya-p2EW6dRZrXCFXZ7HWk05Qw9s26JsSe4piKxIPZyE",
+	x: "YP7UuiVanTHJYet0xjVtaMBJuJI7Yfps5mliLmDyn7Y",
+	y: "eQP-EAi4vJmkGunpVii8ZPLxsgwtfp9Rd6PClNRGIpk",
+};
+const sample = encoder.encode("sample");
+const ecSignature = fromHex(
+	"efd48b2aacb6a8fd1140dd9cd45e81d69d2c877b56aaf991c34d0ea84eaf3716" +
+	"f7cb1c942d657c41d436c7a1b6e29f65f3e900dbb9aff4064dc4ab2f843acda8",
+);
+
+const ecPrivate = await subtle.importKey("jwk", ecJwk, ecdsa, true, ["sign"]);
+const {d: _, ...ecPublicJwk} = ecJwk;
+const ecPublic = await subtle.importKey("jwk", ecPublicJwk, ecdsa, true, ["verify"]);
+equals(ecPrivate.type, "private", "EC JSON Web Key with d is private");
+equals(ecPublic.type, "public", "EC JSON Web Key without d is public");
+equals(ecPublic.algorithm.namedCurve, "P-256", "Curve of an EC key");
+
+const sha256 = {name: "ECDSA", hash: "SHA-256"};
+equals(await subtle.verify(sha256, ecPublic, ecSignature, sample), true, "ECDSA signature from RFC 6979 verifies");
+equals(await subtle.verify(sha256, ecPublic, ecSignature, encoder.encode("test")), false, "ECDSA signature of other data does not verify");
+equals(await subtle.verify({name: "ECDSA", hash: "SHA-384"}, ecPublic, ecSignature, sample), false, "ECDSA signature with another hash does not verify");
+
+let signature = await subtle.sign(sha256, ecPrivate, sample);
+equals(signature.byteLength, 64, "ECDSA P-256 signature is r and s");
+equals(await subtle.verify(sha256, ecPublic, signature, sample), true, "ECDSA signature verifies");
+const tampered = new Uint8Array(signature);
+tampered[10] ^= 1;
+equals(await subtle.verify(sha256, ecPublic, tampered, sample), false, "Tampered ECDSA signature does not verify");
+
+const raw = await subtle.exportKey("raw", ecPublic);
+equals(raw.byteLength, 65, "Raw EC public key is an uncompressed point");
+const fromRaw = await subtle.importKey("raw", raw, ecdsa, false, ["verify"]);
+equals(await subtle.verify(sha256, fromRaw, ecSignature, sample), true, "Raw export round-trips");
+const fromSpki = await subtle.importKey("spki", await subtle.exportKey("spki", ecPublic), ecdsa, false, ["verify"]);
+equals(await subtle.verify(sha256, fromSpki, ecSignature, sample), true, "SPKI export round-trips");
+const fromPkcs8 = await subtle.importKey("pkcs8", await subtle.exportKey("pkcs8", ecPrivate), ecdsa, false, ["sign"]);
+equals(await subtle.verify(sha256, ecPublic, await subtle.sign(sha256, fromPkcs8, sample), sample), true, "PKCS #8 export round-trips");
+equals((await subtle.exportKey("jwk", ecPrivate)).d, ecJwk.d, "JSON Web Key export keeps d");
+
+error = await rejection(subtle.importKey("jwk", {...ecJwk, crv: "P-384"}, ecdsa, false, ["sign"]), "Importing a JSON Web Key with another curve");
+ok(error instanceof SyntaxError, "JSON Web Keys with another curve reject with a SyntaxError");
+await rejection(subtle.importKey("jwk", {...ecJwk, d: "AQ"}, ecdsa, false, ["sign"]), "Importing a JSON Web Key with a mismatched d");
+error = await rejection(subtle.importKey("jwk", ecPublicJwk, ecdsa, false, ["sign"]), "Importing a public key for signing");
+ok(error instanceof SyntaxError, "Public keys cannot be used to sign");
+
+const pair = await subtle.generateKey({name: "ECDSA", namedCurve: "P-384"}, false, ["sign", "verify"]);
+equals(pair.privateKey.usages.join(), "sign", "Generated private key only signs");
+equals(pair.publicKey.usages.join(), "verify", "Generated public key only verifies");
+equals(pair.publicKey.extractable, true, "Generated public keys are always extractable");
+equals(pair.privateKey.extractable, false, "Generated private key is not extractable");
+const sha384 = {name: "ECDSA", hash: "SHA-384"};
+signature = await subtle.sign(sha384, pair.privateKey, sample);
+equals(signature.byteLength, 96, "ECDSA P-384 signature is r and s");
+equals(await subtle.verify(sha384, pair.publicKey, signature, sample), true, "ECDSA P-384 signature verifies");
+error = await rejection(subtle.sign("HMAC", pair.privateKey, sample), "Signing with a key for another algorithm");
+equals(error.message, "Key is for ECDSA, not HMAC", "Keys are checked against the algorithm");
+
+// Ed25519 test vectors from RFC 8032, section 7.1.
+const ed25519 = {
+	kty: "OKP",
+	crv: "Ed25519",
+	d: "nWGxne_9WmC6hEr0kuwsxERJxWl7MmkZcDusAxyuf2A",
+	x: "11qYAYKxCrfVS_7TyWQHOg7hcvPapiMlrwIaaPcHURo",
+};
+let edPrivate = await subtle.importKey("jwk", ed25519, "Ed25519", true, ["sign"]);
+let edPublic = await subtle.importKey("raw", fromHex("d75a980182b10ab7d54bfed3c964073a0ee172f3daa62325af021a68f707511a"), "Ed25519", true, ["verify"]);
+const empty = new Uint8Array();
+signature = await subtle.sign("Ed25519", edPrivate, empty);
+equals(
+	hex(signature),
+	"e5564300c360ac729086e2cc806e828a84877f1eb8e5d974d873e065224901555fb8821590a33bacc61e39701cf9b46bd25bf5f0595bbe24655141438e7a100b",
+	"Ed25519 signature of an empty message",
+);
+equals(await subtle.verify("Ed25519", edPublic, signature, empty), true, "Ed25519 signature verifies");
+equals(await subtle.verify("Ed25519", edPublic, signature, new Uint8Array([0])), false, "Ed25519 signature of other data does not verify");
+equals((await subtle.exportKey("jwk", edPublic)).x, ed25519.x, "Ed25519 public key exports as a JSON Web Key");
+
+edPrivate = await subtle.importKey("jwk", {
+	kty: "OKP",
+	crv: "Ed25519",
+	alg: "EdDSA",
+	d: "TM0Imyj_ltqdtsNG7BFOD1uKMZ81q6Yk2oz27U-4pvs",
+	x: "PUAXw-hDiVqStwqnTRt-vJyYLM8uxJaMwM1V8Sr0Zgw",
+}, {name: "ed25519"}, false, ["sign"]);
+edPublic = await subtle.importKey("raw", fromHex("3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c"), "Ed25519", false, ["verify"]);
+signature = await subtle.sign("Ed25519", edPrivate, new Uint8Array([0x72]));
+equals(
+	hex(signature),
+	"92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00",
+	"Ed25519 signature of a single byte",
+);
+const edTampered = new Uint8Array(signature);
+edTampered[0] ^= 1;
+equals(await subtle.verify("Ed25519", edPublic, edTampered, new Uint8Array([0x72])), false, "Tampered Ed25519 signature does not verify");
+
+await rejection(subtle.importKey("jwk", {...ed25519, alg: "ES256"}, "Ed25519", false, ["sign"]), "Importing an Ed25519 JSON Web Key with another algorithm");
+await rejection(subtle.importKey("raw", new Uint8Array(31), "Ed25519", false, ["verify"]), "Importing a raw Ed25519 key of the wrong length");
+
+const edPair = await subtle.generateKey("Ed25519", true, ["sign", "verify"]);
+signature = await subtle.sign("Ed25519", edPair.privateKey, sample);
+equals(signature.byteLength, 64, "Ed25519 signatures are 64 bytes");
+equals(await subtle.verify("Ed25519", edPair.publicKey, signature, sample), true, "Generated Ed25519 signature verifies");
+equals((await subtle.exportKey("raw", edPair.publicKey)).byteLength, 32, "Raw Ed25519 public keys are 32 bytes");
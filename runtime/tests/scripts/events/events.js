@@ -0,0 +1,37 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "../assert.js";
+
+const event = new Event("plain", {bubbles: true, cancelable: true});
+equals(event.type, "plain", "Event.type");
+ok(event.bubbles, "Event.bubbles");
+ok(event.cancelable, "Event.cancelable");
+ok(!event.composed, "Event.composed");
+ok(!event.isTrusted, "Event.isTrusted of events created by scripts");
+ok(!event.defaultPrevented, "Event.defaultPrevented before cancelling");
+event.preventDefault();
+ok(event.defaultPrevented, "Event.defaultPrevented after cancelling");
+ok(typeof event.timeStamp === "number", "Event.timeStamp");
+
+const detail = {value: 1};
+const custom = new CustomEvent("custom", {detail});
+ok(custom instanceof Event, "CustomEvent inherits from Event");
+equals(custom.detail, detail, "CustomEvent.detail");
+equals(new CustomEvent("custom").detail, null, "Default CustomEvent.detail");
+
+const target = new EventTarget();
+let received = null;
+target.addEventListener("custom", event => received = event.detail);
+target.dispatchEvent(custom);
+equals(received, detail, "CustomEvent.detail in listeners");
+
+const message = new MessageEvent("message", {data: "data", origin: "https://example.com", lastEventId: "1"});
+ok(message instanceof Event, "MessageEvent inherits from Event");
+equals(message.data, "data", "MessageEvent.data");
+equals(message.origin, "https://example.com", "MessageEvent.origin");
+equals(message.lastEventId, "1", "MessageEvent.lastEventId");
+equals(new MessageEvent("message").data, null, "Default MessageEvent.data");
@@ -0,0 +1,96 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "../assert.js";
+
+const target = new EventTarget();
+const calls = [];
+
+function first(event) {
+	calls.push("first");
+	equals(this, target, "this of listeners");
+	equals(event.target, target, "Event.target during dispatch");
+	equals(event.currentTarget, target, "Event.currentTarget during dispatch");
+	equals(event.eventPhase, Event.AT_TARGET, "Event.eventPhase during dispatch");
+}
+
+target.addEventListener("test", first);
+target.addEventListener("test", first);
+target.addEventListener("test", () => calls.push("once"), {once: true});
+target.addEventListener("test", {
+	handleEvent(event) {
+		calls.push("handleEvent");
+		target.addEventListener("test", () => calls.push("added"));
+		target.removeEventListener("test", last);
+		equals(event.type, "test", "Event.type");
+	},
+});
+
+function last() {
+	calls.push("last");
+}
+
+target.addEventListener("test", last);
+
+const event = new Event("test");
+ok(target.dispatchEvent(event), "Dispatch of an event which was not cancelled");
+equals(calls.join(), "first,once,handleEvent", "Listeners called during the first dispatch");
+equals(event.eventPhase, Event.NONE, "Event.eventPhase after dispatch");
+equals(event.currentTarget, null, "Event.currentTarget after dispatch");
+equals(event.target, target, "Event.target after dispatch");
+
+calls.length = 0;
+target.dispatchEvent(new Event("test"));
+equals(calls.join(), "first,handleEvent,added", "Listeners called during the second dispatch");
+
+const cancelTarget = new EventTarget();
+cancelTarget.addEventListener("cancel", event => event.preventDefault());
+ok(!cancelTarget.dispatchEvent(new Event("cancel", {cancelable: true})), "Dispatch of a cancelled event");
+ok(cancelTarget.dispatchEvent(new Event("cancel")), "Dispatch of an event which is not cancelable");
+
+const passiveTarget = new EventTarget();
+passiveTarget.addEventListener("passive", event => event.preventDefault(), {passive: true});
+ok(passiveTarget.dispatchEvent(new Event("passive", {cancelable: true})), "Dispatch of an event cancelled by a passive listener");
+
+const stopTarget = new EventTarget();
+const stopped = [];
+stopTarget.addEventListener("stop", event => {
+	stopped.push(1);
+	event.stopImmediatePropagation();
+});
+stopTarget.addEventListener("stop", () => stopped.push(2));
+stopTarget.dispatchEvent(new Event("stop"));
+equals(stopped.join(), "1", "Listeners called after stopImmediatePropagation");
+
+const signalTarget = new EventTarget();
+const controller = new AbortController();
+let signalled = 0;
+signalTarget.addEventListener("signal", () => signalled++, {signal: controller.signal});
+signalTarget.dispatchEvent(new Event("signal"));
+controller.abort();
+signalTarget.dispatchEvent(new Event("signal"));
+equals(signalled, 1, "Calls of a listener removed by its signal");
+
+const nestedTarget = new EventTarget();
+let nested = null;
+nestedTarget.addEventListener("nested", event => {
+	try {
+		nestedTarget.dispatchEvent(event);
+	} catch (error) {
+		nested = error;
+	}
+});
+nestedTarget.dispatchEvent(new Event("nested"));
+ok(nested !== null, "Dispatch of an event which is being dispatched");
+
+const throwingTarget = new EventTarget();
+let afterThrow = false;
+throwingTarget.addEventListener("throw", () => {
+	throw new Error("Listener Error");
+});
+throwingTarget.addEventListener("throw", () => afterThrow = true);
+throwingTarget.dispatchEvent(new Event("throw"));
+ok(afterThrow, "Listeners called after a listener throws");
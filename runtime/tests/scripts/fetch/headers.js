@@ -0,0 +1,120 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok, throws} from "../assert.js";
+
+const url = "https://example.com/";
+
+let headers = new Headers([["X-B", "2"], ["x-a", "1"], ["X-B", "3"], ["Set-Cookie", "a=1"], ["Set-Cookie", "b=2"]]);
+equals(headers.get("x-b"), "2, 3", "Values of a header are combined");
+equals(headers.has("X-A"), true, "Names are case-insensitive");
+equals(headers.get("missing"), null, "Missing headers are null");
+
+const entries = [...headers];
+equals(entries.length, 4, "Set-Cookie entries are not combined");
+equals(entries.map(([name]) => name).join(), "set-cookie,set-cookie,x-a,x-b", "Entries are sorted by lowercase name");
+equals(entries[3][1], "2, 3", "Entries combine values");
+equals([...headers.keys()].join(), "set-cookie,set-cookie,x-a,x-b", "Keys");
+equals([...headers.values()].join("|"), "a=1|b=2|1|2, 3", "Values");
+equals([...headers.entries()].length, entries.length, "entries() matches the iterator");
+
+const cookies = headers.getSetCookie();
+equals(cookies.length, 2, "getSetCookie() returns every Set-Cookie header");
+equals(cookies[1], "b=2", "getSetCookie() keeps the order of the headers");
+equals(new Headers().getSetCookie().length, 0, "getSetCookie() without headers is empty");
+
+const visited = [];
+const thisArg = {};
+headers.forEach(function (value, name, object) {
+	equals(this, thisArg, "forEach() calls the callback with thisArg");
+	equals(object, headers, "forEach() passes the headers");
+	visited.push(`${name}=${value}`);
+}, thisArg);
+equals(visited.join("|"), "set-cookie=a=1|set-cookie=b=2|x-a=1|x-b=2, 3", "forEach() passes the value and name of each entry");
+
+const iterator = headers.keys();
+headers.append("x-c", "4");
+headers.delete("x-a");
+equals([...iterator].join(), "set-cookie,set-cookie,x-a,x-b", "Iterators are unaffected by later modifications");
+equals([...headers.keys()].join(), "set-cookie,set-cookie,x-b,x-c", "New iterators see modifications");
+
+headers.set("X-B", "5");
+equals(headers.get("x-b"), "5", "set() replaces every value");
+equals(headers.delete("x-b"), true, "delete() returns whether the header existed");
+equals(headers.delete("x-b"), false, "delete() of a missing header returns false");
+
+await throws(() => new Headers({"Bad Name": "value"}), "Invalid header names throw");
+await throws(() => headers.append("", "value"), "Empty header names throw");
+await throws(() => headers.set("x-a", "line\nbreak"), "Invalid header values throw");
+
+let request = new Request(url, {
+	headers: {
+		"Accept": "text/plain",
+		"Cookie": "a=1",
+		"Proxy-Authorization": "Basic abc",
+		"Sec-Fetch-Mode": "cors",
+		"X-HTTP-Method-Override": "GET, TRACE",
+		"X-Method-Override": "PATCH",
+		"X-Custom": "value",
+	},
+});
+equals(request.headers.get("accept"), "text/plain", "Request keeps allowed headers");
+equals(request.headers.get("x-custom"), "value", "Request keeps custom headers");
+equals(request.headers.get("x-method-override"), "PATCH", "Request keeps method overrides of allowed methods");
+ok(!request.headers.has("cookie"), "Request drops Cookie");
+ok(!request.headers.has("proxy-authorization"), "Request drops Proxy- headers");
+ok(!request.headers.has("sec-fetch-mode"), "Request drops Sec- headers");
+ok(!request.headers.has("x-http-method-override"), "Request drops method overrides of forbidden methods");
+
+request = new Request(url, {
+	mode: "no-cors",
+	method: "POST",
+	headers: {
+		"Accept": "text/html",
+		"Content-Type": "text/plain;charset=UTF-8",
+		"Content-Language": "en",
+		"X-Custom": "value",
+	},
+});
+equals(request.headers.get("accept"), "text/html", "No-cors request keeps Accept");
+equals(request.headers.get("content-type"), "text/plain;charset=UTF-8", "No-cors request keeps simple Content-Type");
+equals(request.headers.get("content-language"), "en", "No-cors request keeps Content-Language");
+ok(!request.headers.has("x-custom"), "No-cors request drops headers which are not safelisted");
+
+request = new Request(url, {mode: "no-cors", method: "POST", headers: {"Content-Type": "application/json"}});
+ok(!request.headers.has("content-type"), "No-cors request drops non-simple Content-Type");
+
+let error = null;
+try {
+	new Request(url, {mode: "no-cors", method: "PUT"});
+} catch (e) {
+	error = e;
+}
+ok(error instanceof TypeError, "No-cors request with a PUT method throws a TypeError");
+
+request = new Request(url, {headers: {"X-Custom": "value"}});
+const immutable = request.headers;
+error = null;
+try {
+	immutable.set("x-custom", "changed");
+} catch (e) {
+	error = e;
+}
+ok(error instanceof TypeError, "Modifying the headers of a request throws a TypeError");
+await throws(() => immutable.append("x-other", "value"), "Appending to the headers of a request throws");
+await throws(() => immutable.delete("x-custom"), "Deleting the headers of a request throws");
+equals(request.headers.get("x-custom"), "value", "Headers of a request are unchanged");
+
+headers = new Headers({"X-A": "1", "Set-Cookie": "a=1"});
+headers.append("x-a", "2");
+const cloned = structuredClone(headers);
+ok(cloned !== headers, "Cloned headers are a new object");
+equals(cloned.get("x-a"), "1, 2", "Cloned headers keep every value");
+equals(cloned.getSetCookie()[0], "a=1", "Cloned headers keep Set-Cookie");
+cloned.set("x-b", "3");
+ok(!headers.has("x-b"), "Cloned headers are independent");
+
+await throws(() => structuredClone(request.headers).set("x-a", "1"), "Cloned immutable headers stay immutable");
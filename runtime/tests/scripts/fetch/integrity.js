@@ -0,0 +1,36 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, throws} from "../assert.js";
+
+const url = "data:,hello%20world";
+
+const sha256 = "sha256-uU0nuZNNPgilLlLX2n2r+sSE7+N6U4DukIj3rOLvzek=";
+const sha384 = "sha384-/b2OdaZ/KfcBpOBAOF4uI5hjA+oQI5IRr5B/y7g1eLPkF8txzmRu/QgZ3YwIjeG9";
+const sha512 = "sha512-MJ7MSJwS1utMxA9QyQLytNDtd+5RGnx6m808qG1M2G+YndNbxf9JlnDaNCVbRbDP2DDoH2Bdz33FVC6TrpzXbw==";
+const mismatch = "sha512-AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA==";
+
+async function text(integrity) {
+	return (await fetch(url, {integrity})).text();
+}
+
+equals(await text(sha256), "hello world", "SHA-256 metadata matches");
+equals(await text(sha384), "hello world", "SHA-384 metadata matches");
+equals(await text(sha512), "hello world", "SHA-512 metadata matches");
+equals(await text("sha256-uU0nuZNNPgilLlLX2n2r-sSE7-N6U4DukIj3rOLvzek"), "hello world", "URL-safe digest without padding matches");
+equals(await text(`SHA256-${sha256.slice(7)}`), "hello world", "Algorithm names are case-insensitive");
+equals(await text(`${sha256}?option`), "hello world", "Options are ignored");
+equals(await text(`md5-XrY7u+Ae7tCTyyK7j1rNww== ${sha256}`), "hello world", "Unknown algorithms are ignored");
+equals(await text("md5-XrY7u+Ae7tCTyyK7j1rNww== invalid"), "hello world", "Metadata without valid items matches");
+equals(await text(`${mismatch} ${sha512}`), "hello world", "Any digest of the strongest algorithm matches");
+equals(await text(`${sha256} ${sha512}`), "hello world", "Multiple algorithms match");
+
+await throws(() => text(mismatch), "Mismatched digest rejects");
+await throws(() => text(`${sha256} ${mismatch}`), "Only the strongest algorithm is considered");
+await throws(async () => (await fetch(url, {integrity: mismatch})).arrayBuffer(), "Mismatched digest rejects before arrayBuffer()");
+
+const error = await fetch(url, {integrity: mismatch}).then(() => null, error => error);
+equals(error instanceof TypeError, true, "Mismatched digest rejects with a TypeError");
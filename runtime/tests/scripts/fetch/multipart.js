@@ -0,0 +1,49 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "../assert.js";
+
+function boundary(request) {
+	const contentType = request.headers.get("Content-Type");
+	const match = /^multipart\/form-data; boundary=(.+)$/.exec(contentType);
+	ok(match !== null, `multipart/form-data Content-Type: ${contentType}`);
+	return match[1];
+}
+
+const form = new FormData();
+form.append("field\n\"name\"", "line\rbreak");
+form.append("file", new File(["contents"], "file.txt", {type: "text/plain"}));
+form.append("blob", new Blob([new Uint8Array([0, 1])]));
+
+const request = new Request("https://example.com/", {method: "POST", body: form});
+const separator = boundary(request);
+ok(/^----FormBoundary[0-9a-f]{32}$/.test(separator), `Boundary is generated from random data: ${separator}`);
+ok(separator !== boundary(new Request("https://example.com/", {method: "POST", body: form})), "Boundaries differ between encodings");
+
+const body = await request.text();
+equals(body, [
+	`--${separator}`,
+	"Content-Disposition: form-data; name=\"field%0D%0A%22name%22\"",
+	"",
+	"line\r\nbreak",
+	`--${separator}`,
+	"Content-Disposition: form-data; name=\"file\"; filename=\"file.txt\"",
+	"Content-Type: text/plain",
+	"",
+	"contents",
+	`--${separator}`,
+	"Content-Disposition: form-data; name=\"blob\"; filename=\"blob\"",
+	"Content-Type: application/octet-stream",
+	"",
+	"\u0000\u0001",
+	`--${separator}--`,
+	"",
+].join("\r\n"), "multipart/form-data body");
+equals(body.split(separator).length, 5, "Boundary only occurs in delimiters");
+
+const params = new Request("https://example.com/", {method: "POST", body: new URLSearchParams({a: "1", b: "x y"})});
+equals(params.headers.get("Content-Type"), "application/x-www-form-urlencoded;charset=UTF-8", "URLSearchParams Content-Type");
+equals(await params.text(), "a=1&b=x+y", "URLSearchParams body");
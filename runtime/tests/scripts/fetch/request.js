@@ -0,0 +1,93 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {bytes, equals, ok, throws} from "../assert.js";
+
+const url = "https://example.com/path?query";
+
+let request = new Request(url);
+equals(request.method, "GET", "Default method");
+equals(request.url, url, "URL");
+equals(request.credentials, "same-origin", "Default credentials");
+equals(request.cache, "default", "Default cache mode");
+equals(request.redirect, "follow", "Default redirect mode");
+equals(request.integrity, "", "Default integrity");
+equals(request.bodyUsed, false, "Request without a body is unused");
+equals(await request.text(), "", "Request without a body reads as empty");
+equals(request.bodyUsed, false, "Reading an empty body does not use it");
+
+request = new Request(url, {method: "post", body: "{\"a\": [1, 2]}", headers: {"Content-Type": "application/json"}, redirect: "manual"});
+equals(request.method, "POST", "Method is normalised");
+equals(request.redirect, "manual", "Redirect mode");
+equals(request.headers.get("Content-Type"), "application/json", "Headers");
+equals((await request.json()).a[1], 2, "json() parses the body");
+equals(request.bodyUsed, true, "Reading the body uses it");
+await throws(() => request.text(), "Body cannot be read twice");
+await throws(() => request.clone(), "Request with a used body cannot be cloned");
+
+request = new Request(url, {method: "POST", body: new Uint8Array([1, 2, 3])});
+bytes(await request.arrayBuffer(), [1, 2, 3], "arrayBuffer() reads the body");
+
+request = new Request(url, {method: "POST", body: "blob", headers: {"Content-Type": "text/custom"}});
+const blob = await request.blob();
+equals(blob.type, "text/custom", "blob() uses the Content-Type of the request");
+equals(await blob.text(), "blob", "blob() reads the body");
+
+request = new Request(url, {method: "POST", body: new URLSearchParams({a: "1"})});
+equals(request.headers.get("Content-Type"), "application/x-www-form-urlencoded;charset=UTF-8", "Content-Type is implied by the body");
+equals(await request.text(), "a=1", "URLSearchParams body");
+
+request = new Request(url, {method: "PUT", body: "cloned"});
+let clone = request.clone();
+ok(clone !== request, "clone() returns a new request");
+equals(clone.method, "PUT", "Clone has the same method");
+equals(clone.url, url, "Clone has the same URL");
+equals(await request.text(), "cloned", "Original body can be read");
+equals(await clone.text(), "cloned", "Cloned body can be read independently");
+
+async function* chunks() {
+	yield "streamed ";
+	yield "body";
+}
+
+request = new Request(url, {method: "POST", body: chunks()});
+clone = request.clone();
+equals(await clone.text(), "streamed body", "Cloned streamed body can be read");
+equals(await request.text(), "streamed body", "Original streamed body can be read after its clone");
+
+const copy = new Request(new Request(url, {method: "DELETE", cache: "no-store"}), {redirect: "error"});
+equals(copy.method, "DELETE", "Request created from a request keeps its method");
+equals(copy.cache, "no-store", "Request created from a request keeps its cache mode");
+equals(copy.redirect, "error", "Request created from a request uses the overridden options");
+
+await throws(() => new Request(url, {body: "body"}), "GET request cannot have a body");
+await throws(() => new Request(url, {method: "HEAD", body: "body"}), "HEAD request cannot have a body");
+
+const controller = new AbortController();
+request = new Request(url, {signal: controller.signal});
+ok(request.signal instanceof AbortSignal, "signal is an AbortSignal");
+ok(request.signal === request.signal, "The same signal is returned on every access");
+equals(request.signal.aborted, false, "Signal is not aborted initially");
+clone = request.clone();
+controller.abort("reason");
+equals(request.signal.aborted, true, "Signal follows the signal of the init");
+equals(request.signal.reason, "reason", "Signal has the abort reason");
+equals(clone.signal.aborted, true, "Signal of a clone follows the original");
+equals(new Request(url).signal.aborted, false, "Request without a signal has a signal which is never aborted");
+
+const lingering = new Request(url, {signal: new AbortController().signal});
+equals(lingering.signal.aborted, false, "Signal which is never aborted does not keep the script running");
+ok(lingering.headers === lingering.headers, "The same headers are returned on every access");
+
+let posted = new Request(url, {method: "POST", body: "body"});
+const copied = new Request(posted);
+equals(posted.bodyUsed, true, "Creating a request from a request uses its body");
+equals(await copied.text(), "body", "Request created from a request has its body");
+await throws(() => new Request(posted), "Request with a used body cannot be used again");
+
+posted = new Request("data:,", {method: "POST", body: "body"});
+await fetch(posted).catch(() => {});
+equals(posted.bodyUsed, true, "Fetching a request uses its body");
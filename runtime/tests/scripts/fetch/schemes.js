@@ -0,0 +1,44 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {bytes, equals, throws} from "../assert.js";
+
+let response = await fetch("data:,hello%20world#fragment");
+equals(response.status, 200, "data: URL status");
+equals(response.headers.get("Content-Type"), "text/plain;charset=US-ASCII", "Default data: URL MIME type");
+equals(await response.text(), "hello world", "Percent-encoded data: URL body");
+
+response = await fetch("data:text/html;base64,PGI+aGk8L2I+");
+equals(response.headers.get("Content-Type"), "text/html", "Base64 data: URL MIME type");
+equals(await response.text(), "<b>hi</b>", "Base64 data: URL body");
+
+response = await fetch("data:image/png ; base64,AAEC");
+equals(response.headers.get("Content-Type"), "image/png", "MIME type is trimmed before the base64 marker");
+equals(response.headers.get("Content-Length"), "3", "data: URL Content-Length");
+bytes(await response.arrayBuffer(), [0, 1, 2], "Binary data: URL body");
+
+response = await fetch("data:;BASE64,aGVs%20bG8");
+equals(await response.text(), "hello", "Base64 is decoded after percent-decoding, ignoring whitespace and padding");
+
+response = await fetch("data:;charset=utf-8,%E2%9C%93");
+equals(response.headers.get("Content-Type"), "text/plain;charset=utf-8", "MIME type without an essence defaults to text/plain");
+equals(await response.text(), "✓", "UTF-8 data: URL body");
+
+response = await fetch("data:invalid,text");
+equals(response.headers.get("Content-Type"), "text/plain;charset=US-ASCII", "Invalid MIME type is replaced");
+
+await throws(() => fetch("data:;base64,a"), "Invalid base64 rejects");
+await throws(() => fetch("data:text/plain"), "data: URL without a comma rejects");
+await throws(() => fetch("data:,text", {method: "POST", body: "body"}), "data: URL cannot be fetched with POST");
+
+response = await fetch(new URL("./asset.json", import.meta.url));
+equals(response.headers.get("Content-Type"), "application/json", "file: URL MIME type is guessed from the extension");
+equals(response.headers.get("Content-Length"), "26", "file: URL Content-Length");
+equals((await response.json()).name, "bundled asset", "file: URL body");
+
+await throws(() => fetch(new URL("./missing.json", import.meta.url)), "Missing file rejects");
+await throws(() => fetch(new URL("./", import.meta.url)), "Directory rejects");
+await throws(() => fetch(new URL("./asset.json", import.meta.url), {method: "HEAD"}), "file: URL cannot be fetched with HEAD");
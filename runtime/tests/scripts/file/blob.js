@@ -0,0 +1,39 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {bytes, equals} from "../assert.js";
+
+const inner = new Blob(["World"]);
+const blob = new Blob(["Hello, ", inner, new Uint8Array([33])], {type: "Text/Plain"});
+equals(blob.size, 13, "Blob.size");
+equals(blob.type, "text/plain", "Blob.type is lowercase");
+equals(await blob.text(), "Hello, World!", "Blob.text");
+bytes(await new Blob([new Uint8Array([1, 2, 3]).buffer]).arrayBuffer(), [1, 2, 3], "Blob.arrayBuffer");
+
+equals(new Blob([], {type: "text/é"}).type, "", "Blob.type with characters outside ASCII");
+equals(new Blob().size, 0, "Size of an empty Blob");
+
+equals(await blob.slice(7).text(), "World!", "Blob.slice with a start");
+equals(await blob.slice(2, 5).text(), "llo", "Blob.slice with a start and end");
+equals(await blob.slice(-6, -1).text(), "World", "Blob.slice with negative indices");
+equals(await blob.slice(5, 100).text(), ", World!", "Blob.slice past the end");
+equals(blob.slice(8, 2).size, 0, "Blob.slice with an end before the start");
+const single = new Blob(["hello world"]);
+equals(single.slice(5, 2).size, 0, "Blob.slice of a single part with an end before the start");
+equals(await single.slice(-2, -5).text(), "", "Blob.slice of a single part with negative indices in reverse");
+equals(blob.slice(0, 1, "text/html").type, "text/html", "Blob.slice with a type");
+equals(blob.slice().type, "", "Blob.slice without a type");
+
+let streamed = "";
+const decoder = new TextDecoder();
+for await (const chunk of blob.stream()) {
+	streamed += decoder.decode(chunk, {stream: true});
+}
+equals(streamed, "Hello, World!", "Blob.stream");
+
+const clone = structuredClone(blob);
+equals(clone.type, "text/plain", "Cloned Blob.type");
+equals(await clone.text(), "Hello, World!", "Cloned Blob contents");
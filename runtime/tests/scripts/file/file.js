@@ -0,0 +1,28 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok} from "../assert.js";
+
+const file = new File(["contents"], "name.txt", {type: "text/plain", lastModified: 1000});
+ok(file instanceof Blob, "File inherits from Blob");
+equals(file.name, "name.txt", "File.name");
+equals(file.lastModified, 1000, "File.lastModified");
+equals(file.type, "text/plain", "File.type");
+equals(file.size, 8, "File.size");
+equals(await file.text(), "contents", "File.text");
+equals(await file.slice(0, 4).text(), "cont", "File.slice");
+
+const before = Date.now();
+ok(new File([], "now").lastModified >= before, "Default File.lastModified");
+
+const blob = new Blob([file, "!"]);
+equals(await blob.text(), "contents!", "Blob containing a File");
+
+const clone = structuredClone(file);
+ok(clone instanceof File, "Cloned File is a File");
+equals(clone.name, "name.txt", "Cloned File.name");
+equals(clone.lastModified, 1000, "Cloned File.lastModified");
+equals(await clone.text(), "contents", "Cloned File contents");
@@ -0,0 +1,38 @@
+/*
+ * This Source Code Form is subject to the terms of the Mozilla Public
+ * License, v. 2.0. If a copy of the MPL was not distributed with this
+ * file, You can obtain one at http://mozilla.org/MPL/2.0/.
+ */
+
+import {equals, ok, throws} from "../assert.js";
+
+const form = new FormData();
+form.append("name", "first");
+form.append("name", "second");
+form.append("other", 1);
+form.append("blob", new Blob(["data"], {type: "text/plain"}));
+form.append("file", new File(["file"], "original.txt"), "renamed.txt");
+
+equals(form.get("name"), "first", "FormData.get");
+equals(form.getAll("name").join(), "first,second", "FormData.getAll");
+equals(form.get("other"), "1", "FormData.get of a converted value");
+equals(form.get("missing"), null, "FormData.get of a missing entry");
+ok(form.has("blob"), "FormData.has");
+
+const blob = form.get("blob");
+ok(blob instanceof File, "Blob entries are converted to files");
+equals(blob.name, "blob", "Default name of a Blob entry");
+equals(blob.type, "text/plain", "Type of a Blob entry");
+equals(await blob.text(), "data", "Contents of a Blob entry");
+equals(form.get("file").name, "renamed.txt", "Name of a File entry with a filename");
+
+form.set("name", "third");
+equals(form.getAll("name").join(), "third", "FormData.set replaces all entries");
+equals([...form.keys()].join(), "name,other,blob,file", "FormData.set keeps the position of the first entry");
+
+form.delete("other");
+ok(!form.has("other"), "FormData.delete");
+equals([...form].map(([name]) => name).join(), "name,blob,file", "FormData iteration");
+equals([...form.values()][0], "third", "FormData.values");
+
+await throws(() => form.append("string", "value", "filename"), "FormData.append with a filename for a string");